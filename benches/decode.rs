@@ -0,0 +1,88 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use ola_lang_abi::{Abi, FixedArray4, Type, Value};
+use std::hint::black_box;
+
+const LEN: u64 = 10_000;
+
+fn decode_u32_array(c: &mut Criterion) {
+    let mut bs = vec![LEN];
+    bs.extend(0..LEN);
+    let tys = [Type::Array(Box::new(Type::U32))];
+
+    c.bench_function("decode u32[10000]", |b| {
+        b.iter(|| Value::decode_from_slice(black_box(&bs), black_box(&tys)).unwrap())
+    });
+}
+
+fn decode_tuple_array(c: &mut Criterion) {
+    let value = Value::Array(
+        (0..LEN)
+            .map(|i| {
+                Value::Tuple(vec![
+                    ("a".to_string(), Value::U32(i)),
+                    ("b".to_string(), Value::String("olavm".to_string())),
+                ])
+            })
+            .collect(),
+        Type::Tuple(vec![
+            ("a".to_string(), Type::U32),
+            ("b".to_string(), Type::String),
+        ]),
+    );
+    let bs = Value::encode(std::slice::from_ref(&value));
+    let tys = [value.type_of()];
+
+    c.bench_function("decode (u32,string)[10000]", |b| {
+        b.iter(|| Value::decode_from_slice(black_box(&bs), black_box(&tys)).unwrap())
+    });
+    c.bench_function("decode_borrowed (u32,string)[10000]", |b| {
+        b.iter(|| Value::decode_borrowed(black_box(&bs), black_box(&tys)).unwrap())
+    });
+}
+
+fn decode_leading_param(c: &mut Criterion) {
+    let bs = Value::encode(&[
+        Value::Address(FixedArray4([1, 2, 3, 4])),
+        Value::Array((0..LEN).map(Value::U32).collect(), Type::U32),
+    ]);
+    let tys = [Type::Address, Type::Array(Box::new(Type::U32))];
+
+    c.bench_function("decode (address,u32[10000])", |b| {
+        b.iter(|| Value::decode_from_slice(black_box(&bs), black_box(&tys)).unwrap())
+    });
+    c.bench_function("decode_iter address of (address,u32[10000])", |b| {
+        b.iter(|| {
+            Value::decode_iter(black_box(&bs), black_box(&tys))
+                .next()
+                .unwrap()
+                .unwrap()
+        })
+    });
+}
+
+fn decode_input_selector(c: &mut Criterion) {
+    let functions = (0..200)
+        .map(|i| {
+            serde_json::json!({
+                "type": "function",
+                "name": format!("f{}", i),
+                "inputs": [{ "name": "x", "type": "u32" }]
+            })
+        })
+        .collect::<Vec<_>>();
+    let abi: Abi = serde_json::from_value(serde_json::Value::Array(functions)).unwrap();
+    let input = [7, 1, abi.functions[199].method_id()];
+
+    c.bench_function("decode_input of the last of 200 functions", |b| {
+        b.iter(|| abi.decode_input_from_slice(black_box(&input)).unwrap())
+    });
+}
+
+criterion_group!(
+    benches,
+    decode_u32_array,
+    decode_tuple_array,
+    decode_leading_param,
+    decode_input_selector
+);
+criterion_main!(benches);
@@ -0,0 +1,151 @@
+//! Whole-crate benchmarks guarding against performance regressions.
+//!
+//! Record a baseline on the base branch, then compare a change against it:
+//!
+//! ```text
+//! cargo bench --bench regression -- --save-baseline main
+//! cargo bench --bench regression -- --baseline main
+//! ```
+//!
+//! When comparing, the run fails if the mean time of a benchmark grew by
+//! more than `OLA_ABI_BENCH_THRESHOLD` (a fraction, 0.10 by default). It
+//! also fails if the threshold is invalid or a benchmark has no change
+//! estimate, e.g. as it's missing from the baseline.
+
+use anyhow::{anyhow, Context, Result};
+use criterion::{criterion_group, Criterion};
+use ola_lang_abi::{Abi, FixedArray4, Type, Value};
+use std::{hint::black_box, path::PathBuf, process::ExitCode};
+
+const BOOK_ABI: &str = include_str!("../examples/BookExample.json");
+
+/// Benchmark ids, also the directories of their criterion results.
+const PARSE_ABI: &str = "parse_abi";
+const ENCODE_ARRAY: &str = "encode_u32_array_1000";
+const DECODE_LOG: &str = "decode_log";
+
+const DEFAULT_THRESHOLD: f64 = 0.10;
+
+fn parse_abi(c: &mut Criterion) {
+    c.bench_function(PARSE_ABI, |b| {
+        b.iter(|| serde_json::from_str::<Abi>(black_box(BOOK_ABI)).unwrap())
+    });
+}
+
+fn encode_array(c: &mut Criterion) {
+    let values = [Value::Array((0..1000).map(Value::U32).collect(), Type::U32)];
+
+    c.bench_function(ENCODE_ARRAY, |b| {
+        b.iter(|| Value::encode(black_box(&values)))
+    });
+}
+
+fn decode_log(c: &mut Criterion) {
+    let abi: Abi = serde_json::from_str(BOOK_ABI).unwrap();
+    let topics = [
+        abi.events[0].topic(),
+        FixedArray4([0, 0, 0, 10]),
+        FixedArray4([
+            1298737262017568572,
+            12445360621592034485,
+            13004999764278192581,
+            3441866816748036873,
+        ]),
+    ];
+    let data = [5, 104, 101, 108, 108, 111];
+
+    c.bench_function(DECODE_LOG, |b| {
+        b.iter(|| {
+            abi.decode_log_from_slice(black_box(&topics), black_box(&data))
+                .unwrap()
+        })
+    });
+}
+
+criterion_group!(benches, parse_abi, encode_array, decode_log);
+
+/// Mean time changes against the baseline given with `--baseline`, as
+/// estimated by criterion, of the benchmarks slower than `threshold`.
+fn regressions(threshold: f64) -> Result<Vec<String>> {
+    let dir = std::env::var_os("CRITERION_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            std::env::var_os("CARGO_TARGET_DIR")
+                .map_or_else(|| PathBuf::from("target"), PathBuf::from)
+                .join("criterion")
+        });
+
+    let mut regressions = vec![];
+
+    for id in [PARSE_ABI, ENCODE_ARRAY, DECODE_LOG] {
+        let path = dir.join(id).join("change").join("estimates.json");
+        let estimates =
+            std::fs::read(&path).with_context(|| format!("no change estimate of {}", id))?;
+        let estimates: serde_json::Value = serde_json::from_slice(&estimates)
+            .with_context(|| format!("invalid {}", path.display()))?;
+        let change = estimates["mean"]["point_estimate"]
+            .as_f64()
+            .ok_or_else(|| anyhow!("no mean change in {}", path.display()))?;
+
+        if change > threshold {
+            regressions.push(format!("{}: {:+.1}%", id, change * 100.0));
+        }
+    }
+
+    Ok(regressions)
+}
+
+/// Threshold given with `OLA_ABI_BENCH_THRESHOLD`, or the default one.
+fn threshold() -> Result<f64> {
+    let threshold = match std::env::var("OLA_ABI_BENCH_THRESHOLD") {
+        Ok(threshold) => threshold,
+        Err(std::env::VarError::NotPresent) => return Ok(DEFAULT_THRESHOLD),
+        Err(e) => return Err(anyhow!("invalid OLA_ABI_BENCH_THRESHOLD: {}", e)),
+    };
+
+    match threshold.parse::<f64>() {
+        Ok(threshold) if threshold.is_finite() && threshold >= 0.0 => Ok(threshold),
+        _ => Err(anyhow!(
+            "invalid OLA_ABI_BENCH_THRESHOLD {:?}, expected a non-negative fraction",
+            threshold
+        )),
+    }
+}
+
+fn main() -> ExitCode {
+    // changes are only estimated when comparing with a baseline
+    let threshold = if std::env::args().any(|arg| arg == "--baseline") {
+        match threshold() {
+            Ok(threshold) => Some(threshold),
+            Err(e) => return fail(e),
+        }
+    } else {
+        None
+    };
+
+    benches();
+    Criterion::default().configure_from_args().final_summary();
+
+    let Some(threshold) = threshold else {
+        return ExitCode::SUCCESS;
+    };
+    let regressions = match regressions(threshold) {
+        Ok(regressions) => regressions,
+        Err(e) => return fail(e),
+    };
+    if regressions.is_empty() {
+        return ExitCode::SUCCESS;
+    }
+
+    eprintln!(
+        "regressions over {:.1}%:\n{}",
+        threshold * 100.0,
+        regressions.join("\n")
+    );
+    ExitCode::FAILURE
+}
+
+fn fail(e: anyhow::Error) -> ExitCode {
+    eprintln!("error: {:#}", e);
+    ExitCode::FAILURE
+}
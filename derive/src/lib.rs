@@ -0,0 +1,182 @@
+//! Derive macros mapping Rust structs to Ola ABI tuples, re-exported by
+//! `ola-lang-abi` with the `derive` feature.
+//!
+//! ```ignore
+//! use ola_lang_abi::{AbiDecode, AbiEncode};
+//!
+//! #[derive(AbiEncode, AbiDecode)]
+//! struct Book {
+//!     id: u32,
+//!     name: String,
+//! }
+//! ```
+//!
+//! `AbiEncode` implements `ToValue` and `AbiType`, `AbiDecode` implements
+//! `FromValue`. Fields map to tuple members in declaration order, named after
+//! the field unless renamed with `#[abi(rename = "bookId")]`; tuple struct
+//! members are named by position.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, Index, LitStr, Member, Type};
+
+/// Implements `ToValue` and `AbiType` for a struct, as a tuple of its fields.
+#[proc_macro_derive(AbiEncode, attributes(abi))]
+pub fn derive_abi_encode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    abi_encode(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Implements `FromValue` for a struct, from a tuple of its fields.
+#[proc_macro_derive(AbiDecode, attributes(abi))]
+pub fn derive_abi_decode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    abi_decode(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Struct field, as a tuple member.
+struct Field {
+    member: Member,
+    name: String,
+    ty: Type,
+}
+
+fn fields(input: &DeriveInput) -> syn::Result<Vec<Field>> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "ABI tuples can only be derived for structs",
+            ))
+        }
+    };
+
+    let fields = match fields {
+        Fields::Named(fields) => fields.named.iter().collect::<Vec<_>>(),
+        Fields::Unnamed(fields) => fields.unnamed.iter().collect(),
+        Fields::Unit => vec![],
+    };
+
+    fields
+        .into_iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let (member, name) = match &field.ident {
+                Some(ident) => (Member::Named(ident.clone()), ident.to_string()),
+                None => (Member::Unnamed(Index::from(i)), i.to_string()),
+            };
+
+            Ok(Field {
+                member,
+                name: rename(field)?.unwrap_or(name),
+                ty: field.ty.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Name given by `#[abi(rename = "...")]`.
+fn rename(field: &syn::Field) -> syn::Result<Option<String>> {
+    let mut name = None;
+
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("abi"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                name = Some(meta.value()?.parse::<LitStr>()?.value());
+                Ok(())
+            } else {
+                Err(meta.error("unsupported abi attribute, expected `rename`"))
+            }
+        })?;
+    }
+
+    Ok(name)
+}
+
+fn abi_encode(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = fields(input)?;
+    let ident = &input.ident;
+
+    let mut generics = input.generics.clone();
+    let where_clause = generics.make_where_clause();
+    for field in &fields {
+        let ty = &field.ty;
+        where_clause
+            .predicates
+            .push(syn::parse_quote!(#ty: ::ola_lang_abi::ToValue + ::ola_lang_abi::AbiType));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let values = fields.iter().map(|Field { member, name, .. }| {
+        quote! {
+            (::std::string::String::from(#name), ::ola_lang_abi::ToValue::to_value(&self.#member))
+        }
+    });
+    let types = fields.iter().map(|Field { name, ty, .. }| {
+        quote! {
+            (::std::string::String::from(#name), <#ty as ::ola_lang_abi::AbiType>::abi_type())
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics ::ola_lang_abi::ToValue for #ident #ty_generics #where_clause {
+            fn to_value(&self) -> ::ola_lang_abi::Value {
+                ::ola_lang_abi::Value::Tuple(::std::vec![#(#values),*])
+            }
+        }
+
+        impl #impl_generics ::ola_lang_abi::AbiType for #ident #ty_generics #where_clause {
+            fn abi_type() -> ::ola_lang_abi::Type {
+                ::ola_lang_abi::Type::Tuple(::std::vec![#(#types),*])
+            }
+        }
+    })
+}
+
+fn abi_decode(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = fields(input)?;
+    let ident = &input.ident;
+    let struct_name = ident.to_string();
+    let len = fields.len();
+
+    let mut generics = input.generics.clone();
+    let where_clause = generics.make_where_clause();
+    for field in &fields {
+        let ty = &field.ty;
+        where_clause
+            .predicates
+            .push(syn::parse_quote!(#ty: ::ola_lang_abi::FromValue));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let inits = fields.iter().enumerate().map(|(i, Field { member, .. })| {
+        quote! {
+            #member: ::ola_lang_abi::FromValue::from_value(&members[#i].1)?
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics ::ola_lang_abi::FromValue for #ident #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn from_value(
+                value: &::ola_lang_abi::Value,
+            ) -> ::ola_lang_abi::__private::Result<Self> {
+                let members = ::ola_lang_abi::__private::struct_members(value, #struct_name, #len)?;
+
+                ::std::result::Result::Ok(Self { #(#inits),* })
+            }
+        }
+    })
+}
@@ -0,0 +1,207 @@
+//! End-to-end indexer demo.
+//!
+//! Reads a JSONL file of raw transactions and logs, decodes them against an
+//! ABI in batches, with [`Abi::decode_inputs`] and [`Abi::decode_logs`], and
+//! writes one decoded JSON object per line to stdout. Records that can't be
+//! decoded are reported inline, timing statistics go to stderr.
+//!
+//! ```text
+//! cargo run --release --example indexer -- examples/BookExample.json examples/indexer.jsonl
+//! ```
+//!
+//! Every input line is either a transaction or a log:
+//!
+//! ```text
+//! {"tx": "0x01", "calldata": [60, 5, 111, 108, 97, 118, 109, 7, 120553111]}
+//! {"tx": "0x01", "topics": ["0xb447..."], "data": [5, 104, 101, 108, 108, 111]}
+//! ```
+
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    time::{Duration, Instant},
+};
+
+use ola_lang_abi::{Abi, DecodedParams, RawLog};
+use serde::Deserialize;
+use serde_json::{json, Value as Json};
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Record {
+    Transaction {
+        tx: String,
+        calldata: Vec<u64>,
+    },
+    Log {
+        tx: String,
+        #[serde(flatten)]
+        log: RawLog,
+    },
+}
+
+/// Number of records decoded at once.
+const BATCH_SIZE: usize = 1024;
+
+#[derive(Default)]
+struct Stats {
+    records: usize,
+    decoded: usize,
+    failed: usize,
+    words: usize,
+    decoding: Duration,
+}
+
+fn params_json(params: &DecodedParams) -> Json {
+    params
+        .iter()
+        .map(|param| (param.param.name.clone(), param.value.to_json()))
+        .collect::<serde_json::Map<_, _>>()
+        .into()
+}
+
+/// Decodes the records of a batch, parsed from their line or not, into one
+/// JSON object per record, in order.
+fn decode_batch(
+    abi: &Abi,
+    batch: &[(usize, Result<Record, String>)],
+    stats: &mut Stats,
+) -> Vec<Json> {
+    let records = batch.iter().filter_map(|(_, record)| record.as_ref().ok());
+    let calldata = records
+        .clone()
+        .filter_map(|record| match record {
+            Record::Transaction { calldata, .. } => Some(calldata),
+            Record::Log { .. } => None,
+        })
+        .collect::<Vec<_>>();
+    let logs = records
+        .filter_map(|record| match record {
+            Record::Log { log, .. } => Some(log),
+            Record::Transaction { .. } => None,
+        })
+        .collect::<Vec<_>>();
+
+    stats.words += calldata
+        .iter()
+        .map(|calldata| calldata.len())
+        .sum::<usize>();
+    stats.words += logs
+        .iter()
+        .map(|log| log.data.len() + 4 * log.topics.len())
+        .sum::<usize>();
+
+    let started = Instant::now();
+    let mut calls = abi.decode_inputs(calldata).into_iter();
+    let mut events = abi.decode_logs(logs).into_iter();
+    stats.decoding += started.elapsed();
+
+    batch
+        .iter()
+        .map(|(line, record)| {
+            let decoded = match record {
+                Ok(Record::Transaction { tx, .. }) => calls
+                    .next()
+                    .expect("one result per call")
+                    .map(|(function, params)| {
+                        json!({
+                            "tx": tx,
+                            "function": function.signature(),
+                            "params": params_json(&params),
+                        })
+                    })
+                    .map_err(|err| format!("{:#}", err)),
+                Ok(Record::Log { tx, .. }) => events
+                    .next()
+                    .expect("one result per log")
+                    .map(|(event, params)| {
+                        json!({
+                            "tx": tx,
+                            "event": event.signature(),
+                            "params": params_json(&params),
+                        })
+                    })
+                    .map_err(|err| format!("{:#}", err)),
+                Err(error) => Err(error.clone()),
+            };
+
+            match decoded {
+                Ok(decoded) => {
+                    stats.decoded += 1;
+                    decoded
+                }
+                Err(error) => {
+                    stats.failed += 1;
+                    json!({ "line": line, "error": error })
+                }
+            }
+        })
+        .collect()
+}
+
+fn write_batch(out: &mut impl Write, decoded: &[Json]) {
+    for decoded in decoded {
+        serde_json::to_writer(&mut *out, decoded).expect("failed to write output");
+        writeln!(out).expect("failed to write output");
+    }
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let (abi_path, input_path) = match (args.next(), args.next()) {
+        (Some(abi), Some(input)) => (abi, input),
+        _ => {
+            eprintln!("usage: indexer <abi.json> <records.jsonl>");
+            std::process::exit(2);
+        }
+    };
+
+    // Parse ABI JSON file
+    let abi: Abi = {
+        let file = File::open(&abi_path).expect("failed to open ABI file");
+
+        serde_json::from_reader(file).expect("failed to parse ABI")
+    };
+
+    let input = BufReader::new(File::open(&input_path).expect("failed to open records file"));
+    let stdout = std::io::stdout();
+    let mut out = BufWriter::new(stdout.lock());
+
+    let mut stats = Stats::default();
+    let started = Instant::now();
+
+    let mut batch = Vec::with_capacity(BATCH_SIZE);
+    for (i, line) in input.lines().enumerate() {
+        let line = line.expect("failed to read records file");
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        stats.records += 1;
+
+        let record =
+            serde_json::from_str::<Record>(&line).map_err(|err| format!("invalid record: {}", err));
+        batch.push((i + 1, record));
+
+        if batch.len() == BATCH_SIZE {
+            write_batch(&mut out, &decode_batch(&abi, &batch, &mut stats));
+            batch.clear();
+        }
+    }
+    write_batch(&mut out, &decode_batch(&abi, &batch, &mut stats));
+
+    out.flush().expect("failed to write output");
+
+    let elapsed = started.elapsed();
+    eprintln!(
+        "{} records ({} decoded, {} failed) in {:?}, {:?} decoding",
+        stats.records, stats.decoded, stats.failed, elapsed, stats.decoding
+    );
+    if !stats.decoding.is_zero() {
+        eprintln!(
+            "{:.0} records/s, {:.0} words/s",
+            stats.records as f64 / stats.decoding.as_secs_f64(),
+            stats.words as f64 / stats.decoding.as_secs_f64()
+        );
+    }
+}
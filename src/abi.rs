@@ -1,7 +1,15 @@
-use anyhow::{anyhow, Result};
 use serde::{de::Visitor, Deserialize, Serialize};
-
-use crate::{params::Param, DecodedParams, Event, FixedArray4, Value};
+use std::{collections::BTreeMap, path::Path};
+
+use crate::{
+    calldata::{self, ByteOrder},
+    error::{AbiError, Result},
+    hash::{method_id_full_of, method_id_of, method_id_with_endianness},
+    params::Param,
+    selector_map::SelectorMap,
+    DecodeOptions, DecodedParams, Error, Event, ExtraTopics, FixedArray4, RawLog, ToValues, Type,
+    Value, MAX_TYPE_DEPTH,
+};
 
 /// Contract ABI (Abstract Binary Interface).
 ///
@@ -18,99 +26,1574 @@ use crate::{params::Param, DecodedParams, Event, FixedArray4, Value};
 ///
 /// let abi: Abi = serde_json::from_str(abi_json).unwrap();
 /// ```
-#[derive(Debug, Clone, Eq, PartialEq)]
+///
+/// Artifacts holding several contracts are supported either as a map of
+/// contract name to ABI, or as a single array whose entries carry a
+/// `"contract"` (or `"group"`) field. Each contract is then also reachable
+/// through [`Abi::contract`].
+///
+/// ```
+/// use ola_lang_abi::Abi;
+///
+/// let abi_json = r#"{
+///     "Token": [{"type": "function", "name": "transfer", "inputs": []}],
+///     "Vault": [{"type": "function", "name": "deposit", "inputs": []}]
+/// }"#;
+///
+/// let abi: Abi = serde_json::from_str(abi_json).unwrap();
+///
+/// assert!(abi.contract("Token").unwrap().function("transfer").is_some());
+/// assert!(abi.contract("Token").unwrap().function("deposit").is_none());
+/// assert_eq!(abi.functions.len(), 2);
+/// ```
+///
+/// Functions and events keep the order they have in the JSON, and
+/// [`Abi::iter_entries`] yields them interleaved as they were, which is also
+/// the order they are serialized in.
+#[derive(Debug, Clone, Default, Eq)]
 pub struct Abi {
     /// Contract defined functions.
     pub functions: Vec<Function>,
 
-    pub events: Vec<Event>,
-}
+    pub events: Vec<Event>,
+
+    /// Contract defined errors.
+    pub errors: Vec<Error>,
+
+    /// Contract constructor, if the ABI has a constructor entry.
+    ///
+    /// Constructors of the contracts of multi-contract artifacts are only
+    /// found in their [`Abi::namespaces`].
+    pub constructor: Option<Constructor>,
+
+    /// Per-contract ABIs of multi-contract artifacts, by contract name.
+    ///
+    /// Their entries are also part of the top-level lists.
+    pub namespaces: BTreeMap<String, Abi>,
+
+    /// Original order of the functions, events and errors.
+    ///
+    /// Ignored, in favour of functions followed by events and errors, unless
+    /// it refers to every entry exactly once.
+    pub entry_order: Vec<EntryIndex>,
+
+    /// Contract of each entry of [`Abi::entry_order`] in multi-contract
+    /// artifacts, along with the position of the entry in the contract
+    /// [`Abi::namespaces`] ABI.
+    ///
+    /// Ignored unless it has one item per entry of a consistent order.
+    pub entry_contracts: Vec<Option<(String, usize)>>,
+
+    /// Entries of unknown kinds skipped by [`Abi::from_json_permissive`].
+    ///
+    /// Not part of the ABI itself: ignored when comparing and serializing.
+    pub skipped: Vec<SkippedEntry>,
+
+    /// Index of the functions by method id and signature.
+    ///
+    /// Not part of the ABI itself: ignored when comparing and serializing.
+    pub(crate) selectors: SelectorMap,
+}
+
+/// ABI entry of an unknown kind, skipped by [`Abi::from_json_permissive`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SkippedEntry {
+    /// Contract of the entry, in multi-contract artifacts.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contract: Option<String>,
+    /// Position of the entry in its JSON array.
+    pub index: usize,
+    /// Entry kind, i.e. its `"type"` field.
+    pub kind: String,
+    /// Entry JSON, as it was.
+    pub raw: serde_json::Value,
+}
+
+/// Position of an entry in [`Abi::functions`], [`Abi::events`] or
+/// [`Abi::errors`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EntryIndex {
+    Function(usize),
+    Event(usize),
+    Error(usize),
+}
+
+/// ABI entry, as yielded by [`Abi::iter_entries`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AbiItem<'a> {
+    Function(&'a Function),
+    Event(&'a Event),
+    Error(&'a Error),
+}
+
+// A parsed ABI is shared by the workers of multi-threaded indexers, its lazy
+// indexes must stay thread-safe.
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Abi>();
+};
+
+impl PartialEq for Abi {
+    fn eq(&self, other: &Self) -> bool {
+        self.functions == other.functions
+            && self.events == other.events
+            && self.errors == other.errors
+            && self.constructor == other.constructor
+            && self.namespaces == other.namespaces
+            && self.iter_entries().eq(other.iter_entries())
+    }
+}
+
+impl Abi {
+    /// Appends a function, keeping track of the entry order.
+    pub fn push_function(&mut self, f: Function) {
+        self.entry_order
+            .push(EntryIndex::Function(self.functions.len()));
+        self.entry_contracts.push(None);
+        self.functions.push(f);
+    }
+
+    /// Appends an event, keeping track of the entry order.
+    pub fn push_event(&mut self, e: Event) {
+        self.entry_order.push(EntryIndex::Event(self.events.len()));
+        self.entry_contracts.push(None);
+        self.events.push(e);
+    }
+
+    /// Appends an error, keeping track of the entry order.
+    pub fn push_error(&mut self, e: Error) {
+        self.entry_order.push(EntryIndex::Error(self.errors.len()));
+        self.entry_contracts.push(None);
+        self.errors.push(e);
+    }
+
+    /// Appends a copy of an entry, recording its contract and position in
+    /// the contract namespace.
+    fn push_item(&mut self, item: AbiItem, contract: Option<(String, usize)>) {
+        match item {
+            AbiItem::Function(f) => self.push_function(f.clone()),
+            AbiItem::Event(e) => self.push_event(e.clone()),
+            AbiItem::Error(e) => self.push_error(e.clone()),
+        }
+
+        if let Some(last) = self.entry_contracts.last_mut() {
+            *last = contract;
+        }
+    }
+
+    /// Appends an entry with `push`, to the top-level lists and, when it
+    /// belongs to a contract, to the namespace of the contract.
+    fn push_namespaced(&mut self, contract: Option<String>, push: impl Fn(&mut Abi)) {
+        push(self);
+
+        if let Some(contract) = contract {
+            let namespace = self.namespaces.entry(contract.clone()).or_default();
+            let index = namespace.entry_order.len();
+            push(namespace);
+
+            if let Some(last) = self.entry_contracts.last_mut() {
+                *last = Some((contract, index));
+            }
+        }
+    }
+
+    /// Iterates over functions, events and errors in their original order.
+    pub fn iter_entries(&self) -> impl Iterator<Item = AbiItem<'_>> {
+        self.entry_indexes()
+            .into_iter()
+            .map(move |index| match index {
+                EntryIndex::Function(i) => AbiItem::Function(&self.functions[i]),
+                EntryIndex::Event(i) => AbiItem::Event(&self.events[i]),
+                EntryIndex::Error(i) => AbiItem::Error(&self.errors[i]),
+            })
+    }
+
+    fn entry_indexes(&self) -> Vec<EntryIndex> {
+        if self.has_consistent_order() {
+            self.entry_order.clone()
+        } else {
+            (0..self.functions.len())
+                .map(EntryIndex::Function)
+                .chain((0..self.events.len()).map(EntryIndex::Event))
+                .chain((0..self.errors.len()).map(EntryIndex::Error))
+                .collect()
+        }
+    }
+
+    /// Contract and position in it of each entry, in the order of
+    /// [`Abi::iter_entries`].
+    fn item_contracts(&self) -> Vec<Option<(&str, usize)>> {
+        if self.has_consistent_order() && self.entry_contracts.len() == self.entry_order.len() {
+            self.entry_contracts
+                .iter()
+                .map(|contract| {
+                    contract
+                        .as_ref()
+                        .map(|(contract, index)| (contract.as_str(), *index))
+                })
+                .collect()
+        } else {
+            vec![None; self.entry_indexes().len()]
+        }
+    }
+
+    fn has_consistent_order(&self) -> bool {
+        if self.entry_order.len() != self.functions.len() + self.events.len() + self.errors.len() {
+            return false;
+        }
+
+        let mut functions = vec![false; self.functions.len()];
+        let mut events = vec![false; self.events.len()];
+        let mut errors = vec![false; self.errors.len()];
+
+        self.entry_order.iter().all(|index| {
+            let seen = match index {
+                EntryIndex::Function(i) => functions.get_mut(*i),
+                EntryIndex::Event(i) => events.get_mut(*i),
+                EntryIndex::Error(i) => errors.get_mut(*i),
+            };
+
+            match seen {
+                Some(seen) if !*seen => {
+                    *seen = true;
+                    true
+                }
+                _ => false,
+            }
+        })
+    }
+
+    /// Parses an ABI, skipping entries of unknown kinds instead of failing.
+    ///
+    /// Skipped entries are recorded in [`Abi::skipped`], so that users can
+    /// tell e.g. entries of newer compilers were ignored. Compiler artifacts
+    /// are unwrapped to their ABI, as by [`Abi::from_artifact_json`].
+    ///
+    /// ```
+    /// use ola_lang_abi::Abi;
+    ///
+    /// let abi = Abi::from_json_permissive(r#"[
+    ///     {"type": "fallback", "inputs": []},
+    ///     {"type": "function", "name": "f", "inputs": []}
+    /// ]"#).unwrap();
+    ///
+    /// assert_eq!(abi.functions.len(), 1);
+    /// assert_eq!(abi.skipped[0].kind, "fallback");
+    /// ```
+    pub fn from_json_permissive(json: &str) -> Result<Abi> {
+        let json: serde_json::Value =
+            serde_json::from_str(json).map_err(|e| AbiError::InvalidAbi(e.to_string()))?;
+        let mut skipped = vec![];
+
+        let json = match json {
+            serde_json::Value::Object(mut artifact) if crate::artifact::is_artifact(&artifact) => {
+                artifact.remove("abi").unwrap()
+            }
+            json => json,
+        };
+        let json = match json {
+            serde_json::Value::Object(contracts) => serde_json::Value::Object(
+                contracts
+                    .into_iter()
+                    .map(|(contract, entries)| {
+                        let entries = skip_unknown(entries, Some(&contract), &mut skipped);
+                        (contract, entries)
+                    })
+                    .collect(),
+            ),
+            entries => skip_unknown(entries, None, &mut skipped),
+        };
+
+        let mut abi: Abi =
+            serde_json::from_value(json).map_err(|e| AbiError::InvalidAbi(e.to_string()))?;
+        abi.skipped = skipped;
+
+        Ok(abi)
+    }
+
+    /// Loads an ABI file, or the ABI of a compiler artifact, recording the
+    /// path as the provenance of its functions and events.
+    pub fn load(path: impl AsRef<Path>) -> Result<Abi> {
+        let path = path.as_ref();
+        let json = std::fs::read(path).map_err(|source| AbiError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let abi = Abi::from_artifact_json(&json).map_err(|e| {
+            AbiError::InvalidAbi(format!("failed to parse {}: {}", path.display(), e))
+        })?;
+
+        Ok(abi.with_source(path.display().to_string()))
+    }
+
+    /// Records method ids computed with the given byte order as the
+    /// selectors of the functions without a compiler emitted one, including
+    /// those of the [`Abi::namespaces`].
+    ///
+    /// Only needed for toolchains reading the keccak256 prefix
+    /// little-endian; big-endian, the default, is what the ola-lang compiler
+    /// emits.
+    ///
+    /// ```
+    /// use ola_lang_abi::{Abi, SelectorEndianness};
+    ///
+    /// let abi = Abi::from_human_readable(&["function createBook(u32 id, string name)"])
+    ///     .unwrap()
+    ///     .with_selector_endianness(SelectorEndianness::LittleEndian);
+    /// assert_eq!(abi.functions[0].method_id(), 0x977e2f07);
+    /// ```
+    pub fn with_selector_endianness(mut self, endianness: SelectorEndianness) -> Self {
+        for f in &mut self.functions {
+            if f.selector.is_none() {
+                f.selector = Some(f.computed_method_id_with(endianness));
+            }
+        }
+
+        self.namespaces = std::mem::take(&mut self.namespaces)
+            .into_iter()
+            .map(|(contract, abi)| (contract, abi.with_selector_endianness(endianness)))
+            .collect();
+        self.selectors.reset();
+
+        self
+    }
+
+    /// Records `source`, e.g. a file path or artifact name, as the
+    /// provenance of the functions and events, along with their contract and
+    /// position in it.
+    pub fn with_source(mut self, source: impl Into<String>) -> Self {
+        let source = source.into();
+
+        // top-level entries of multi-contract artifacts belong to a namespace
+        let provenances = self
+            .item_contracts()
+            .into_iter()
+            .enumerate()
+            .map(|(index, contract)| match contract {
+                Some((contract, index)) => Provenance {
+                    source: Some(source.clone()),
+                    contract: Some(contract.to_string()),
+                    index,
+                },
+                None => Provenance {
+                    source: Some(source.clone()),
+                    contract: None,
+                    index,
+                },
+            })
+            .collect::<Vec<_>>();
+
+        self.set_provenances(provenances);
+
+        for (contract, abi) in &mut self.namespaces {
+            let provenances = (0..abi.entry_indexes().len())
+                .map(|index| Provenance {
+                    source: Some(source.clone()),
+                    contract: Some(contract.clone()),
+                    index,
+                })
+                .collect();
+
+            abi.set_provenances(provenances);
+        }
+
+        self
+    }
+
+    fn set_provenances(&mut self, provenances: Vec<Provenance>) {
+        for (index, provenance) in self.entry_indexes().into_iter().zip(provenances) {
+            match index {
+                EntryIndex::Function(i) => self.functions[i].provenance = Some(provenance),
+                EntryIndex::Event(i) => self.events[i].provenance = Some(provenance),
+                EntryIndex::Error(_) => {}
+            }
+        }
+        self.selectors.reset();
+    }
+
+    /// Appends the entries of another ABI, e.g. of another contract.
+    ///
+    /// Entries with a signature already known are skipped. Fails, naming
+    /// where both came from, if a function selector collides with the one of
+    /// a different signature.
+    pub fn merge(&mut self, mut other: Abi) -> Result<()> {
+        for (contract, abi) in std::mem::take(&mut other.namespaces) {
+            match self.namespaces.get_mut(&contract) {
+                Some(namespace) => namespace.merge(abi)?,
+                None => {
+                    self.namespaces.insert(contract, abi);
+                }
+            }
+        }
+
+        for (item, contract) in other.iter_entries().zip(other.item_contracts()) {
+            // the entry may have moved in the merged namespace
+            let contract = contract.and_then(|(contract, _)| {
+                let index = self.namespaces.get(contract)?.position(item)?;
+                Some((contract.to_string(), index))
+            });
+
+            match item {
+                AbiItem::Function(f) => {
+                    let existing = self.functions.iter().find(|existing| {
+                        existing.method_id() == f.method_id()
+                            || existing.signature() == f.signature()
+                    });
+
+                    match existing {
+                        Some(existing) if existing.signature() == f.signature() => {}
+                        Some(existing) => {
+                            return Err(AbiError::Ambiguous(format!(
+                                "selector {:#010x} of {} collides with {}",
+                                f.method_id(),
+                                f.describe(),
+                                existing.describe()
+                            )))
+                        }
+                        None => self.push_item(item, contract),
+                    }
+                }
+                AbiItem::Event(e) => {
+                    if !self
+                        .events
+                        .iter()
+                        .any(|existing| existing.signature() == e.signature())
+                    {
+                        self.push_item(item, contract);
+                    }
+                }
+                AbiItem::Error(e) => {
+                    if !self
+                        .errors
+                        .iter()
+                        .any(|existing| existing.signature() == e.signature())
+                    {
+                        self.push_item(item, contract);
+                    }
+                }
+            }
+        }
+
+        match (&self.constructor, other.constructor) {
+            (Some(existing), Some(c)) if *existing != c => {
+                return Err(AbiError::Ambiguous(format!(
+                    "{} collides with {}",
+                    c.signature(),
+                    existing.signature()
+                )))
+            }
+            (None, c) => self.constructor = c,
+            _ => {}
+        }
+
+        self.skipped.extend(other.skipped);
+
+        Ok(())
+    }
+
+    /// Position in [`Abi::iter_entries`] of the entry with the signature of
+    /// `item`.
+    fn position(&self, item: AbiItem) -> Option<usize> {
+        self.iter_entries().position(|other| match (item, other) {
+            (AbiItem::Function(a), AbiItem::Function(b)) => a.signature() == b.signature(),
+            (AbiItem::Event(a), AbiItem::Event(b)) => a.signature() == b.signature(),
+            (AbiItem::Error(a), AbiItem::Error(b)) => a.signature() == b.signature(),
+            _ => false,
+        })
+    }
+
+    /// Returns the ABI of the named contract of a multi-contract artifact.
+    pub fn contract(&self, name: &str) -> Option<&Abi> {
+        self.namespaces.get(name)
+    }
+
+    /// Returns the first function with the given name.
+    pub fn function(&self, name: &str) -> Option<&Function> {
+        self.functions.iter().find(|f| f.name == name)
+    }
+
+    /// Returns the first function with the method id.
+    ///
+    /// Lookups by method id and signature go through an index of the
+    /// functions, built on the first one, instead of hashing every function
+    /// signature.
+    pub fn function_by_selector(&self, method_id: u64) -> Option<&Function> {
+        self.functions_by_selector(method_id).into_iter().next()
+    }
+
+    /// Returns the function with the given signature, e.g.
+    /// `transfer(address,u32)`.
+    pub fn function_by_signature(&self, signature: &str) -> Option<&Function> {
+        self.selectors
+            .signature(&self.functions, signature)
+            .map(|i| &self.functions[i])
+    }
+
+    fn functions_by_selector(&self, method_id: u64) -> Vec<&Function> {
+        self.selectors
+            .selector(&self.functions, method_id)
+            .into_iter()
+            .map(|i| &self.functions[i])
+            .collect()
+    }
+
+    /// Checks that no two functions share a method id, listing every
+    /// collision otherwise.
+    ///
+    /// Colliding calls are told apart by the payload they fit when decoding,
+    /// which fails when it fits several; with large ABIs, consider
+    /// [`SelectorWidth::Full`].
+    ///
+    /// ```
+    /// use ola_lang_abi::Abi;
+    ///
+    /// let abi: Abi = serde_json::from_str(r#"[
+    ///     { "type": "function", "name": "a", "inputs": [], "selector": 7 },
+    ///     { "type": "function", "name": "b", "inputs": [], "selector": 7 },
+    ///     { "type": "function", "name": "c", "inputs": [] }
+    /// ]"#).unwrap();
+    ///
+    /// assert_eq!(
+    ///     abi.validate().unwrap_err().to_string(),
+    ///     "method id collisions: 0x00000007 shared by a(), b()"
+    /// );
+    /// ```
+    pub fn validate(&self) -> Result<()> {
+        let mut by_selector = BTreeMap::<u64, Vec<&Function>>::new();
+        for f in &self.functions {
+            by_selector.entry(f.method_id()).or_default().push(f);
+        }
+
+        let collisions = by_selector
+            .into_iter()
+            .filter(|(_, functions)| functions.len() > 1)
+            .map(|(method_id, functions)| {
+                format!(
+                    "{:#010x} shared by {}",
+                    method_id,
+                    functions
+                        .iter()
+                        .map(|f| f.describe())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            })
+            .collect::<Vec<_>>();
+
+        if !collisions.is_empty() {
+            return Err(AbiError::Ambiguous(format!(
+                "method id collisions: {}",
+                collisions.join("; ")
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the first event with the given name.
+    pub fn event(&self, name: &str) -> Option<&Event> {
+        self.events.iter().find(|e| e.name == name)
+    }
+
+    /// Returns the log filter object of Ola node log subscriptions for an
+    /// event, given by name or signature, see [`EventFilter::to_json`].
+    ///
+    /// `indexed` holds the values of the indexed params in order, `None`
+    /// matching any value; missing trailing ones match any value too.
+    ///
+    /// ```
+    /// use ola_lang_abi::{Abi, Value};
+    ///
+    /// let abi: Abi = serde_json::from_str(r#"[{
+    ///     "type": "event",
+    ///     "name": "Transfer",
+    ///     "inputs": [
+    ///         { "name": "from", "type": "u32", "indexed": true },
+    ///         { "name": "to", "type": "u32", "indexed": true },
+    ///         { "name": "amount", "type": "u32", "indexed": false }
+    ///     ],
+    ///     "anonymous": false
+    /// }]"#).unwrap();
+    ///
+    /// let filter = abi
+    ///     .encode_event_topic_filter("Transfer", None, &[None, Some(Value::U32(7))])
+    ///     .unwrap();
+    ///
+    /// assert_eq!(filter["topics"][0], abi.events[0].topic().to_hex_string());
+    /// assert!(filter["topics"][1].is_null());
+    /// assert!(filter.get("address").is_none());
+    /// ```
+    ///
+    /// [`EventFilter::to_json`]: crate::EventFilter::to_json
+    pub fn encode_event_topic_filter(
+        &self,
+        event: &str,
+        address: Option<FixedArray4>,
+        indexed: &[Option<Value>],
+    ) -> Result<serde_json::Value> {
+        let e = self
+            .events
+            .iter()
+            .find(|e| e.name == event || e.signature() == event)
+            .ok_or(AbiError::EventNotFound)?;
+
+        let offset = usize::from(!e.anonymous);
+        indexed
+            .iter()
+            .enumerate()
+            .filter_map(|(i, value)| Some((i + offset, value.clone()?)))
+            .fold(e.filter(), |filter, (index, value)| {
+                filter.topic(index, value)
+            })
+            .to_json(address)
+    }
+
+    // Decode function input from slice.
+    pub fn decode_input_from_slice<'a>(
+        &'a self,
+        input: &[u64],
+    ) -> Result<(&'a Function, DecodedParams)> {
+        self.decode_input_with_options(input, DecodeOptions::default())
+    }
+
+    /// Decodes a batch of function inputs, e.g. the calldata of the
+    /// transactions of a block, into one result per input, in order.
+    ///
+    /// ```
+    /// use ola_lang_abi::Abi;
+    ///
+    /// let abi = Abi::from_human_readable(&["function createBook(u32 id, string name)"]).unwrap();
+    /// let decoded = abi.decode_inputs([&[60, 1, 97, 3, 0x072f7e97][..], &[1, 0, 5]]);
+    ///
+    /// assert_eq!(decoded[0].as_ref().unwrap().0.name, "createBook");
+    /// assert!(decoded[1].is_err());
+    /// ```
+    pub fn decode_inputs<I>(&self, inputs: I) -> Vec<Result<(&Function, DecodedParams)>>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<[u64]>,
+    {
+        inputs
+            .into_iter()
+            .map(|input| self.decode_input_from_slice(input.as_ref()))
+            .collect()
+    }
+
+    /// Decode function input from slice with the given options.
+    ///
+    /// In strict mode, the param-len word has to match the length of the
+    /// params, and the params have to be decoded without trailing words,
+    /// which verifiers need to reject malformed calldata.
+    ///
+    /// ```
+    /// use ola_lang_abi::{Abi, DecodeOptions, Value};
+    ///
+    /// let abi: Abi = serde_json::from_str(r#"[{
+    ///     "type": "function",
+    ///     "name": "setId",
+    ///     "inputs": [{ "name": "id", "type": "u32" }],
+    ///     "outputs": []
+    /// }]"#).unwrap();
+    /// let strict = DecodeOptions { strict: true, ..Default::default() };
+    ///
+    /// let calldata = abi.encode_input_with_signature("setId(u32)", &[Value::U32(7)]).unwrap();
+    /// assert!(abi.decode_input_with_options(&calldata, strict).is_ok());
+    ///
+    /// // a trailing word, and the param-len claiming it
+    /// let method_id = calldata[2];
+    /// assert!(abi.decode_input_with_options(&[7, 0, 1, method_id], strict).is_err());
+    /// assert!(abi.decode_input_with_options(&[7, 0, 2, method_id], strict).is_err());
+    /// assert!(abi.decode_input_from_slice(&[7, 0, 1, method_id]).is_ok());
+    /// ```
+    pub fn decode_input_with_options<'a>(
+        &'a self,
+        input: &[u64],
+        options: DecodeOptions,
+    ) -> Result<(&'a Function, DecodedParams)> {
+        if input.len() < 2 {
+            return Err(AbiError::Truncated(
+                "missing function param-len and method_id".to_string(),
+            ));
+        }
+
+        let candidates = self.functions_by_selector(input[input.len() - 1]);
+        let mut candidates = candidates.into_iter();
+
+        let f = candidates.next().ok_or(AbiError::FunctionNotFound)?;
+
+        if candidates.next().is_some() {
+            // colliding selectors, keep the one call the payload fits exactly
+            let mut decodes = self.decode_input_candidates_with_options(input, options);
+
+            return match decodes.len() {
+                1 => Ok(decodes.remove(0)),
+                0 => Err(AbiError::InvalidInput(
+                    "input matches none of the functions sharing its method id".to_string(),
+                )),
+                _ => Err(AbiError::Ambiguous(format!(
+                    "ambiguous input, decodes as {}",
+                    decodes
+                        .iter()
+                        .map(|(f, _)| f.describe())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ))),
+            };
+        }
+
+        // input = [param1, param2, .. , param-len, method_id]
+        let params = &input[0..input.len() - 2];
+        if options.strict {
+            check_param_len(params, input[input.len() - 2])?;
+        }
+
+        let decoded_params = f.decode_input_with_options(params, options)?;
+
+        Ok((f, decoded_params))
+    }
+
+    /// Decodes function input against every function with its method id.
+    ///
+    /// Method ids are truncated hashes and may collide; this returns the
+    /// decodes of all candidates whose params make up exactly the payload.
+    pub fn decode_input_candidates<'a>(
+        &'a self,
+        input: &[u64],
+    ) -> Vec<(&'a Function, DecodedParams)> {
+        self.decode_input_candidates_with_options(input, DecodeOptions::default())
+    }
+
+    /// Decodes function input against every function with its method id,
+    /// with the given options.
+    ///
+    /// The framing is checked strictly whatever the options, as the exact
+    /// fit of the payload is what tells the candidates apart.
+    pub fn decode_input_candidates_with_options<'a>(
+        &'a self,
+        input: &[u64],
+        options: DecodeOptions,
+    ) -> Vec<(&'a Function, DecodedParams)> {
+        let options = DecodeOptions {
+            strict: true,
+            ..options
+        };
+        let (payload, method_id) = match input {
+            [payload @ .., param_len, method_id] if *param_len == payload.len() as u64 => {
+                (payload, *method_id)
+            }
+            _ => return vec![],
+        };
+
+        self.functions_by_selector(method_id)
+            .into_iter()
+            .filter_map(|f| Some((f, f.decode_input_with_options(payload, options).ok()?)))
+            .collect()
+    }
+
+    /// Decode function input from slice, following proxy dispatches.
+    ///
+    /// When the called function is a known proxy dispatch (see
+    /// [`PROXY_DISPATCH_FUNCTIONS`]) taking a single `fields` param, that param
+    /// is decoded as calldata against the first implementation ABI having the
+    /// called function, recursively, up to [`MAX_TYPE_DEPTH`] calls deep. A
+    /// malformed inner call is an error of that ABI, not mistaken for a
+    /// payload that isn't a call nor retried against the other ABIs.
+    pub fn decode_through<'a>(
+        &'a self,
+        impl_abis: &[&'a Abi],
+        input: &[u64],
+    ) -> Result<NestedDecode<'a>> {
+        self.decode_through_at(impl_abis, input, 0)
+    }
+
+    fn decode_through_at<'a>(
+        &'a self,
+        impl_abis: &[&'a Abi],
+        input: &[u64],
+        depth: usize,
+    ) -> Result<NestedDecode<'a>> {
+        // proxied calls are decoded recursively, crafted calldata nesting
+        // them too deep would exhaust the stack
+        if depth >= MAX_TYPE_DEPTH {
+            return Err(AbiError::InvalidInput(format!(
+                "proxied calls nest deeper than {} levels",
+                MAX_TYPE_DEPTH
+            )));
+        }
+
+        let (function, params) = self.decode_input_from_slice(input)?;
+
+        let inner = match proxy_payload(function, &params) {
+            Some(payload) => {
+                let inner = impl_abis
+                    .iter()
+                    .map(|abi| abi.decode_through_at(impl_abis, payload, depth + 1))
+                    .find(|inner| !matches!(inner, Err(AbiError::FunctionNotFound)))
+                    .unwrap_or_else(|| {
+                        Err(AbiError::InvalidInput(format!(
+                            "proxied call from {} does not match any implementation ABI",
+                            function.name
+                        )))
+                    })?;
+
+                Some(Box::new(inner))
+            }
+            None => None,
+        };
+
+        Ok(NestedDecode {
+            function,
+            params,
+            inner,
+        })
+    }
+
+    // Decode function ouput from slice.
+    pub fn decode_output_from_slice<'a>(
+        &'a self,
+        signature: &str,
+        output: &[u64],
+    ) -> Result<(&'a Function, DecodedParams)> {
+        self.decode_output_with_options(signature, output, DecodeOptions::default())
+    }
+
+    /// Decode function output from slice with the given options, see
+    /// [`Abi::decode_input_with_options`].
+    pub fn decode_output_with_options<'a>(
+        &'a self,
+        signature: &str,
+        output: &[u64],
+        options: DecodeOptions,
+    ) -> Result<(&'a Function, DecodedParams)> {
+        let f = self
+            .function_by_signature(signature)
+            .ok_or(AbiError::FunctionNotFound)?;
+
+        // output = [param1, param2, .. , param-len]
+        let (param_len, params) = output
+            .split_last()
+            .ok_or_else(|| AbiError::Truncated("missing output param-len".to_string()))?;
+        if options.strict {
+            check_param_len(params, *param_len)?;
+        }
+
+        let decoded_params = f.decode_output_with_options(params, options)?;
+
+        Ok((f, decoded_params))
+    }
+
+    /// Decode function output from slice, looking the function up by name.
+    ///
+    /// Fails if the name is overloaded, in which case the full signature has
+    /// to be given to [`Abi::decode_output_from_slice`].
+    pub fn decode_output_by_name<'a>(
+        &'a self,
+        name: &str,
+        output: &[u64],
+    ) -> Result<(&'a Function, DecodedParams)> {
+        let mut candidates = self.functions.iter().filter(|f| f.name == name);
+
+        let f = candidates.next().ok_or(AbiError::FunctionNotFound)?;
+
+        if let Some(other) = candidates.next() {
+            return Err(AbiError::Ambiguous(format!(
+                "ambiguous function name {}: {}, {}{}",
+                name,
+                f.describe(),
+                other.describe(),
+                candidates
+                    .map(|f| format!(", {}", f.describe()))
+                    .collect::<String>()
+            )));
+        }
+
+        // output = [param1, param2, .. , param-len]
+        let (_, params) = output
+            .split_last()
+            .ok_or_else(|| AbiError::Truncated("missing output param-len".to_string()))?;
+
+        Ok((f, f.decode_output_from_slice(params)?))
+    }
+
+    /// Decode function output from slice, looking the function up by method
+    /// id, for tooling that only knows the selector of the call.
+    ///
+    /// If several functions share the method id, the data is decoded with
+    /// the one whose outputs it fits exactly, failing if there are none or
+    /// several of them.
+    pub fn decode_output_by_selector<'a>(
+        &'a self,
+        method_id: u64,
+        output: &[u64],
+    ) -> Result<(&'a Function, DecodedParams)> {
+        let candidates = self.functions_by_selector(method_id);
+
+        if candidates.is_empty() {
+            return Err(AbiError::FunctionNotFound);
+        }
+
+        // output = [param1, param2, .. , param-len]
+        let (_, params) = output
+            .split_last()
+            .ok_or_else(|| AbiError::Truncated("missing output param-len".to_string()))?;
+
+        if let [f] = candidates[..] {
+            return Ok((f, f.decode_output_from_slice(params)?));
+        }
+
+        // colliding selectors, keep the one output the data fits exactly
+        let mut decodes = candidates
+            .into_iter()
+            .filter_map(|f| {
+                let tys = f
+                    .outputs
+                    .iter()
+                    .map(|p| p.type_.clone())
+                    .collect::<Vec<_>>();
+                let values = crate::codec::decode(&tys, params).ok()?;
+
+                Some((
+                    f,
+                    DecodedParams::from(f.outputs.iter().cloned().zip(values).collect::<Vec<_>>()),
+                ))
+            })
+            .collect::<Vec<_>>();
+
+        match decodes.len() {
+            1 => Ok(decodes.remove(0)),
+            0 => Err(AbiError::InvalidInput(
+                "output matches none of the functions sharing its method id".to_string(),
+            )),
+            _ => Err(AbiError::Ambiguous(format!(
+                "ambiguous output, decodes as {}",
+                decodes
+                    .iter()
+                    .map(|(f, _)| f.describe())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))),
+        }
+    }
+
+    /// Decodes the result of a call, which is either the function's return
+    /// data `[params..., param-len]` or, if the call reverted, an error
+    /// payload `[params..., param-len, selector]` of one of the ABI errors.
+    ///
+    /// The data is taken for a revert only if it ends with a known error
+    /// selector preceded by a matching length word, and the params decode
+    /// exactly.
+    pub fn decode_call_result<'a>(
+        &'a self,
+        signature: &str,
+        data: &[u64],
+    ) -> Result<CallOutcome<'a>> {
+        if let Some(error) = self.decode_revert(data) {
+            return Ok(CallOutcome::Revert(error));
+        }
+
+        let (_, params) = self.decode_output_from_slice(signature, data)?;
+
+        Ok(CallOutcome::Return(params))
+    }
+
+    fn decode_revert(&self, data: &[u64]) -> Option<DecodedError<'_>> {
+        let (error, params) = self.decode_error_from_slice(data).ok()?;
+
+        Some(DecodedError { error, params })
+    }
+
+    /// Decode an error (revert) payload from slice.
+    ///
+    /// The payload is laid out like calldata, `[params..., param-len,
+    /// selector]`, the selector being computed from the error signature
+    /// like a function method id.
+    pub fn decode_error_from_slice<'a>(
+        &'a self,
+        data: &[u64],
+    ) -> Result<(&'a Error, DecodedParams)> {
+        let (payload, param_len, selector) = match data {
+            [payload @ .., param_len, selector] => (payload, *param_len, *selector),
+            _ => {
+                return Err(AbiError::Truncated(
+                    "missing error param-len and selector".to_string(),
+                ))
+            }
+        };
+
+        if param_len != payload.len() as u64 {
+            return Err(AbiError::LengthMismatch(format!(
+                "error param-len is {}, but the payload has {} words",
+                param_len,
+                payload.len()
+            )));
+        }
+
+        let mut last_err = AbiError::ErrorNotFound;
+
+        // selectors may collide, keep the first error the payload fits
+        for error in self.errors.iter().filter(|e| e.selector() == selector) {
+            match error.decode_params_from_slice(payload) {
+                Ok(params) => return Ok((error, params)),
+                Err(err) => last_err = err,
+            }
+        }
+
+        Err(last_err)
+    }
+
+    /// Decode a log as returned by a node, e.g. a deserialized [`RawLog`] or
+    /// a `(topics, data)` tuple.
+    ///
+    /// ```
+    /// use ola_lang_abi::{Abi, RawLog, Value};
+    ///
+    /// let abi: Abi = serde_json::from_str(r#"[{
+    ///     "type": "event",
+    ///     "name": "Approve",
+    ///     "inputs": [{"name": "x", "type": "u32", "indexed": true}],
+    ///     "anonymous": false
+    /// }]"#).unwrap();
+    /// let topic = abi.events[0].topic();
+    ///
+    /// let log: RawLog = format!(r#"{{"topics": ["{}", "0x0a"], "data": "0x"}}"#, topic)
+    ///     .parse()
+    ///     .unwrap();
+    /// let (event, params) = abi.decode_log(log).unwrap();
+    /// assert_eq!((event.name.as_str(), &params[0].value), ("Approve", &Value::U32(10)));
+    /// ```
+    pub fn decode_log(&self, log: impl Into<RawLog>) -> Result<(&Event, DecodedParams)> {
+        let log = log.into();
+
+        self.decode_log_from_slice(&log.topics, &log.data)
+    }
+
+    /// Decodes a batch of logs, e.g. the logs of a block, into one result
+    /// per log, in order.
+    pub fn decode_logs<'a, 'l>(
+        &'a self,
+        logs: impl IntoIterator<Item = &'l RawLog>,
+    ) -> Vec<Result<(&'a Event, DecodedParams)>> {
+        logs.into_iter()
+            .map(|log| self.decode_log_from_slice(&log.topics, &log.data))
+            .collect()
+    }
+
+    /// Decode event data from slice.
+    pub fn decode_log_from_slice<'a>(
+        &'a self,
+        topics: &[FixedArray4],
+        data: &[u64],
+    ) -> Result<(&'a Event, DecodedParams)> {
+        let (event, params, _) = self.decode_log_with(topics, data, ExtraTopics::Strict)?;
+
+        Ok((event, params))
+    }
+
+    /// Decode event data from slice, handling topics beyond the ones of the
+    /// event as `extra` says.
+    ///
+    /// Returns the ignored topics along with the event and its params: the
+    /// ones past [`Event::topics_len`], always empty unless
+    /// [`ExtraTopics::Ignore`]. Worth a warning, the node may have changed
+    /// its log format.
+    pub fn decode_log_with<'a, 't>(
+        &'a self,
+        topics: &'t [FixedArray4],
+        data: &[u64],
+        extra: ExtraTopics,
+    ) -> Result<(&'a Event, DecodedParams, &'t [FixedArray4])> {
+        if topics.is_empty() {
+            return Err(AbiError::Truncated("missing event topic id".to_string()));
+        }
+
+        let e = self
+            .events
+            .iter()
+            .find(|e| e.topic() == topics[0])
+            .ok_or(AbiError::EventNotFound)?;
+
+        let (topics, ignored) = e.split_topics(topics, extra)?;
+        let decoded_params = e.decode_data_from_slice(topics, data)?;
+
+        Ok((e, decoded_params, ignored))
+    }
+
+    /// Decode a log, falling back to anonymous events when no event topic
+    /// matches the first topic.
+    ///
+    /// A log matching an event topic decodes as with
+    /// [`Abi::decode_log_from_slice`] into a single candidate. Otherwise,
+    /// as anonymous logs carry no event topic, every anonymous event with as
+    /// many indexed params as there are topics, and whose data params take
+    /// up exactly the log data, is a candidate, in ABI order. Fails with
+    /// [`AbiError::EventNotFound`] when there is none.
+    pub fn decode_log_candidates<'a>(
+        &'a self,
+        topics: &[FixedArray4],
+        data: &[u64],
+    ) -> Result<Vec<(&'a Event, DecodedParams)>> {
+        let named = topics.first().and_then(|topic| {
+            self.events
+                .iter()
+                .find(|e| !e.anonymous && e.topic() == *topic)
+        });
+        if let Some(e) = named {
+            return Ok(vec![(e, e.decode_data_from_slice(topics, data)?)]);
+        }
+
+        let candidates = self
+            .events
+            .iter()
+            .filter(|e| e.anonymous && e.topics_len() == topics.len())
+            .filter_map(|e| {
+                let params = e.decode_data_from_slice(topics, data).ok()?;
+
+                let data_values = params
+                    .iter()
+                    .filter(|decoded| !decoded.param.indexed.unwrap_or(false))
+                    .map(|decoded| decoded.value.clone())
+                    .collect::<Vec<_>>();
+                (Value::encode(&data_values).len() == data.len()).then_some((e, params))
+            })
+            .collect::<Vec<_>>();
+
+        if candidates.is_empty() {
+            return Err(AbiError::EventNotFound);
+        }
+
+        Ok(candidates)
+    }
+
+    /// Encodes a call of the function with the given signature, checking the
+    /// values against its inputs first, see [`Function::validate_inputs`].
+    pub fn encode_input_with_signature(
+        &self,
+        signature: &str,
+        params: &[Value],
+    ) -> Result<Vec<u64>> {
+        let f = self
+            .function_by_signature(signature)
+            .ok_or(AbiError::FunctionNotFound)?;
+        f.validate_inputs(params)?;
+
+        let mut params = Value::encode(params);
+        params.push(params.len() as u64);
+        params.push(f.method_id());
+
+        Ok(params)
+    }
+
+    /// Encodes a call of the function with the given name, resolving
+    /// overloads by the types of the values: the one function whose inputs
+    /// have the canonical types of the values ([`Value::type_of`]), and that
+    /// they type check against, is called.
+    ///
+    /// The values are given as [`Value`]s or as a tuple of plain Rust values,
+    /// see [`ToValues`].
+    ///
+    /// ```
+    /// use ola_lang_abi::{Abi, FixedArray4, Value};
+    ///
+    /// let abi = Abi::from_human_readable(&[
+    ///     "function transfer(address to, u32 amount)",
+    ///     "function transfer(address to, field amount)",
+    /// ]).unwrap();
+    /// let to = FixedArray4([0, 0, 0, 1]);
+    ///
+    /// let input = abi.encode_input_by_name("transfer", (to, 5u32)).unwrap();
+    /// assert_eq!(input.last(), Some(&abi.functions[0].method_id()));
+    ///
+    /// let to = Value::Address(to);
+    /// let input = abi.encode_input_by_name("transfer", &[to.clone(), Value::Field(5)]).unwrap();
+    /// assert_eq!(input.last(), Some(&abi.functions[1].method_id()));
+    ///
+    /// assert_eq!(
+    ///     abi.encode_input_by_name("transfer", &[to, Value::Bool(true)]).unwrap_err().to_string(),
+    ///     "no overload of transfer takes (address,bool): transfer(address,u32), transfer(address,field)"
+    /// );
+    /// ```
+    pub fn encode_input_by_name(&self, name: &str, params: impl ToValues) -> Result<Vec<u64>> {
+        let params = params.to_values();
+        let overloads = self
+            .functions
+            .iter()
+            .filter(|f| f.name == name)
+            .collect::<Vec<_>>();
+        if overloads.is_empty() {
+            return Err(AbiError::FunctionNotFound);
+        }
+
+        let matches = overloads
+            .iter()
+            .copied()
+            .filter(|f| {
+                f.inputs.len() == params.len()
+                    && f.inputs.iter().zip(&params).all(|(param, value)| {
+                        value.type_of().to_string() == param.type_.to_string()
+                            && value.check_type(&param.type_).is_ok()
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        let types = params
+            .iter()
+            .map(|value| value.type_of().to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        let describe = |functions: &[&Function]| {
+            functions
+                .iter()
+                .map(|f| f.describe())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        match matches[..] {
+            [f] => {
+                f.validate_inputs(&params)?;
+
+                let mut input = Value::encode(&params);
+                input.push(input.len() as u64);
+                input.push(f.method_id());
+
+                Ok(input)
+            }
+            [] => Err(AbiError::InvalidInput(format!(
+                "no overload of {} takes ({}): {}",
+                name,
+                types,
+                describe(&overloads)
+            ))),
+            _ => Err(AbiError::Ambiguous(format!(
+                "ambiguous call {}({}), matches {}",
+                name,
+                types,
+                describe(&matches)
+            ))),
+        }
+    }
+
+    /// Encodes a function call with the given selector width, the
+    /// calldata being `[params..., param-len, method_id]` or, with
+    /// [`SelectorWidth::Full`], `[params..., param-len, method_id_full...]`.
+    pub fn encode_input_with_selector(
+        &self,
+        signature: &str,
+        params: &[Value],
+        width: SelectorWidth,
+    ) -> Result<Vec<u64>> {
+        let f = self
+            .function_by_signature(signature)
+            .ok_or(AbiError::FunctionNotFound)?;
+        f.validate_inputs(params)?;
+
+        let mut input = Value::encode(params);
+        input.push(input.len() as u64);
+        match width {
+            SelectorWidth::Short => input.push(f.method_id()),
+            SelectorWidth::Full => input.extend(f.method_id_full().0),
+        }
 
-impl Abi {
-    // Decode function input from slice.
-    pub fn decode_input_from_slice<'a>(
+        Ok(input)
+    }
+
+    /// Decodes function input encoded with the given selector width, see
+    /// [`Abi::encode_input_with_selector`].
+    ///
+    /// ```
+    /// use ola_lang_abi::{Abi, DecodeOptions, SelectorWidth, Value};
+    ///
+    /// let abi: Abi = serde_json::from_str(r#"[{
+    ///     "type": "function",
+    ///     "name": "setId",
+    ///     "inputs": [{ "name": "id", "type": "u32" }],
+    ///     "outputs": []
+    /// }]"#).unwrap();
+    ///
+    /// let calldata = abi
+    ///     .encode_input_with_selector("setId(u32)", &[Value::U32(7)], SelectorWidth::Full)
+    ///     .unwrap();
+    /// assert_eq!(calldata.len(), 6);
+    ///
+    /// let (f, params) = abi
+    ///     .decode_input_with_selector(&calldata, SelectorWidth::Full, DecodeOptions::default())
+    ///     .unwrap();
+    /// assert_eq!(f.name, "setId");
+    /// assert_eq!(params[0].value, Value::U32(7));
+    /// ```
+    pub fn decode_input_with_selector<'a>(
         &'a self,
         input: &[u64],
+        width: SelectorWidth,
+        options: DecodeOptions,
     ) -> Result<(&'a Function, DecodedParams)> {
+        if width == SelectorWidth::Short {
+            return self.decode_input_with_options(input, options);
+        }
+
+        if input.len() < 5 {
+            return Err(AbiError::Truncated(
+                "missing function param-len and full-width method id".to_string(),
+            ));
+        }
+
+        let (rest, method_id) = input.split_at(input.len() - 4);
         let f = self
             .functions
             .iter()
-            .find(|f| f.method_id() == input[input.len() - 1])
-            .ok_or_else(|| anyhow!("ABI function not found"))?;
+            .find(|f| f.method_id_full().0 == method_id)
+            .ok_or(AbiError::FunctionNotFound)?;
 
-        // input = [param1, param2, .. , param-len, method_id]
+        let params = &rest[..rest.len() - 1];
+        if options.strict {
+            check_param_len(params, rest[rest.len() - 1])?;
+        }
 
-        let decoded_params = f.decode_input_from_slice(&input[0..input.len() - 2])?;
+        Ok((f, f.decode_input_with_options(params, options)?))
+    }
 
-        Ok((f, decoded_params))
+    /// Encodes a function call as bytes, 8 per calldata word in the given
+    /// byte order, for RPC endpoints taking byte strings.
+    pub fn encode_input_to_bytes(
+        &self,
+        signature: &str,
+        params: &[Value],
+        order: ByteOrder,
+    ) -> Result<Vec<u8>> {
+        let input = self.encode_input_with_signature(signature, params)?;
+
+        Ok(calldata::to_bytes(&input, order))
     }
 
-    // Decode function ouput from slice.
-    pub fn decode_output_from_slice<'a>(
+    /// Decodes function input given as bytes, 8 per calldata word in the
+    /// given byte order.
+    ///
+    /// ```
+    /// use ola_lang_abi::{calldata::ByteOrder, Abi, Value};
+    ///
+    /// let abi: Abi = serde_json::from_str(r#"[{
+    ///     "type": "function",
+    ///     "name": "setId",
+    ///     "inputs": [{ "name": "id", "type": "u32" }],
+    ///     "outputs": []
+    /// }]"#).unwrap();
+    ///
+    /// let bytes = abi
+    ///     .encode_input_to_bytes("setId(u32)", &[Value::U32(7)], ByteOrder::LittleEndian)
+    ///     .unwrap();
+    /// assert_eq!(bytes[..8], [7, 0, 0, 0, 0, 0, 0, 0]);
+    ///
+    /// let (f, params) = abi.decode_input_from_bytes(&bytes, ByteOrder::LittleEndian).unwrap();
+    /// assert_eq!(f.name, "setId");
+    /// assert_eq!(params[0].value, Value::U32(7));
+    /// assert!(abi.decode_input_from_bytes(&bytes, ByteOrder::BigEndian).is_err());
+    /// ```
+    pub fn decode_input_from_bytes<'a>(
         &'a self,
-        signature: &str,
-        output: &[u64],
+        bytes: &[u8],
+        order: ByteOrder,
     ) -> Result<(&'a Function, DecodedParams)> {
-        let f = self
-            .functions
-            .iter()
-            .find(|f| f.signature() == signature)
-            .ok_or_else(|| anyhow!("ABI function not found"))?;
+        let input = calldata::from_bytes(bytes, order)
+            .map_err(|e| AbiError::InvalidInput(format!("invalid input bytes: {}", e)))?;
 
-        // output = [param1, param2, .. , param-len]
+        self.decode_input_from_slice(&input)
+    }
 
-        let decoded_params = f.decode_output_from_slice(&output[0..output.len() - 1])?;
+    pub fn encode_values(&self, params: &[Value]) -> Result<Vec<u64>> {
+        let mut params = Value::encode(params);
+        params.push(params.len() as u64);
 
-        Ok((f, decoded_params))
+        Ok(params)
     }
 
-    /// Decode event data from slice.
-    pub fn decode_log_from_slice<'a>(
-        &'a self,
-        topics: &[FixedArray4],
-        data: &[u64],
-    ) -> Result<(&'a Event, DecodedParams)> {
-        if topics.is_empty() {
-            return Err(anyhow!("missing event topic id"));
+    /// Encodes the constructor input `[params..., param-len]` of a
+    /// deployment, checking the values against the constructor inputs.
+    ///
+    /// An ABI without constructor takes no params.
+    pub fn encode_constructor_input(&self, params: &[Value]) -> Result<Vec<u64>> {
+        let mut input = crate::codec::encode(&self.constructor_types(), params)?;
+        input.push(input.len() as u64);
+
+        Ok(input)
+    }
+
+    /// Decodes the constructor input `[params..., param-len]` of a
+    /// deployment.
+    pub fn decode_constructor_input(&self, input: &[u64]) -> Result<DecodedParams> {
+        let (len, params) = input
+            .split_last()
+            .ok_or_else(|| AbiError::Truncated("missing constructor param-len".to_string()))?;
+
+        if *len != params.len() as u64 {
+            return Err(AbiError::LengthMismatch(format!(
+                "constructor param-len is {}, but {} words were given",
+                len,
+                params.len()
+            )));
         }
 
-        let e = self
-            .events
+        let values = crate::codec::decode(&self.constructor_types(), params)?;
+        let inputs = self
+            .constructor
             .iter()
-            .find(|e| e.topic() == topics[0])
-            .ok_or_else(|| anyhow!("ABI event not found"))?;
-
-        let decoded_params = e.decode_data_from_slice(topics, data)?;
+            .flat_map(|c| c.inputs.iter().cloned());
 
-        Ok((e, decoded_params))
+        Ok(DecodedParams::from(inputs.zip(values).collect::<Vec<_>>()))
     }
 
-    pub fn encode_input_with_signature(
-        &self,
-        signature: &str,
-        params: &[Value],
-    ) -> Result<Vec<u64>> {
-        let f = self
-            .functions
+    fn constructor_types(&self) -> Vec<Type> {
+        self.constructor
             .iter()
-            .find(|f| f.signature() == signature)
-            .ok_or_else(|| anyhow!("ABI function not found"))?;
+            .flat_map(|c| c.inputs.iter().map(|param| param.type_.clone()))
+            .collect()
+    }
+}
 
-        let mut params = Value::encode(params);
-        params.push(params.len() as u64);
-        params.push(f.method_id());
+/// Names of the functions treated as proxy dispatches by [`Abi::decode_through`].
+pub const PROXY_DISPATCH_FUNCTIONS: &[&str] = &["delegate", "delegatecall", "forward"];
 
-        Ok(params)
+/// Function call decoded through zero or more proxy dispatches.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct NestedDecode<'a> {
+    /// Called function.
+    pub function: &'a Function,
+    /// Decoded function params.
+    pub params: DecodedParams,
+    /// Decoded inner call, if the function is a proxy dispatch.
+    pub inner: Option<Box<NestedDecode<'a>>>,
+}
+
+/// Origin of a function or event, recorded by [`Abi::load`],
+/// [`Abi::with_source`] and kept by [`Abi::merge`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Provenance {
+    /// File path or artifact name.
+    pub source: Option<String>,
+    /// Contract, in multi-contract artifacts.
+    pub contract: Option<String>,
+    /// Position of the entry in its contract ABI.
+    pub index: usize,
+}
+
+impl std::fmt::Display for Provenance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(source) = &self.source {
+            write!(f, "{}, ", source)?;
+        }
+        if let Some(contract) = &self.contract {
+            write!(f, "contract {}, ", contract)?;
+        }
+        write!(f, "entry {}", self.index)
     }
+}
 
-    pub fn encode_values(&self, params: &[Value]) -> Result<Vec<u64>> {
-        let mut params = Value::encode(params);
-        params.push(params.len() as u64);
+/// Entry kinds known to the ABI parser.
+const ENTRY_KINDS: [&str; 4] = ["function", "event", "error", "constructor"];
+
+/// Removes the entries of unknown kinds from a JSON array of ABI entries.
+fn skip_unknown(
+    entries: serde_json::Value,
+    contract: Option<&str>,
+    skipped: &mut Vec<SkippedEntry>,
+) -> serde_json::Value {
+    let entries = match entries {
+        serde_json::Value::Array(entries) => entries,
+        other => return other,
+    };
+
+    let mut kept = vec![];
+
+    for (index, entry) in entries.into_iter().enumerate() {
+        match entry.get("type").and_then(|kind| kind.as_str()) {
+            Some(kind) if !ENTRY_KINDS.contains(&kind) => skipped.push(SkippedEntry {
+                contract: contract.map(String::from),
+                index,
+                kind: kind.to_string(),
+                raw: entry,
+            }),
+            _ => kept.push(entry),
+        }
+    }
 
-        Ok(params)
+    serde_json::Value::Array(kept)
+}
+
+/// Result of a call, see [`Abi::decode_call_result`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CallOutcome<'a> {
+    /// Decoded return values.
+    Return(DecodedParams),
+    /// Decoded error the call reverted with.
+    Revert(DecodedError<'a>),
+}
+
+/// Decoded error payload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedError<'a> {
+    /// Matched ABI error.
+    pub error: &'a Error,
+    /// Decoded error params.
+    pub params: DecodedParams,
+}
+
+fn proxy_payload<'p>(function: &Function, params: &'p DecodedParams) -> Option<&'p [u64]> {
+    if !PROXY_DISPATCH_FUNCTIONS.contains(&function.name.as_str()) || params.len() != 1 {
+        return None;
+    }
+
+    match &params[0].value {
+        Value::Fields(payload) => Some(payload),
+        _ => None,
+    }
+}
+
+/// Checks that the named members of the tuples in a value come in the
+/// order of the members of `ty`, down through arrays, options and maps.
+///
+/// The value must already type check against `ty`: members are matched by
+/// position, and unnamed members, on either side, match any name.
+fn check_member_order(value: &Value, ty: &Type) -> Result<()> {
+    match (value, ty) {
+        (Value::Tuple(values), Type::Tuple(tys)) => {
+            for ((name, value), (expected, ty)) in values.iter().zip(tys) {
+                if !name.is_empty() && !expected.is_empty() && name != expected {
+                    return Err(AbiError::InvalidInput(format!(
+                        "expected tuple member {}, got {}",
+                        expected, name
+                    )));
+                }
+                check_member_order(value, ty)?;
+            }
+            Ok(())
+        }
+        (Value::FixedArray(values, _), Type::FixedArray(ty, _))
+        | (Value::Array(values, _), Type::Array(ty)) => values
+            .iter()
+            .try_for_each(|value| check_member_order(value, ty)),
+        (Value::Some(value), Type::Option(ty)) => check_member_order(value, ty),
+        (Value::Map(entries, ..), Type::Map(key_ty, value_ty)) => {
+            entries.iter().try_for_each(|(key, value)| {
+                check_member_order(key, key_ty)?;
+                check_member_order(value, value_ty)
+            })
+        }
+        _ => Ok(()),
     }
 }
 
+/// Checks the param-len word against the length of the params.
+fn check_param_len(params: &[u64], param_len: u64) -> Result<()> {
+    if param_len != params.len() as u64 {
+        return Err(AbiError::LengthMismatch(format!(
+            "param-len word is {}, but the params are {} words",
+            param_len,
+            params.len()
+        )));
+    }
+
+    Ok(())
+}
+
 impl Serialize for Abi {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -118,25 +1601,47 @@ impl Serialize for Abi {
     {
         let mut entries = vec![];
 
-        for f in &self.functions {
-            entries.push(AbiEntry {
-                type_: String::from("function"),
-                name: Some(f.name.clone()),
-                inputs: Some(f.inputs.clone()),
-                outputs: Some(f.outputs.clone()),
-                anonymous: None,
-            });
+        if let Some(c) = &self.constructor {
+            entries.push(AbiEntry::constructor(c, None));
+        }
+        for (contract, abi) in &self.namespaces {
+            if let Some(c) = &abi.constructor {
+                entries.push(AbiEntry::constructor(c, Some(contract)));
+            }
         }
 
-        for e in &self.events {
-            entries.push(AbiEntry {
-                type_: String::from("event"),
-                name: Some(e.name.clone()),
-                inputs: Some(e.inputs.clone()),
-                outputs: None,
-                anonymous: Some(e.anonymous),
+        let mut listed = self
+            .namespaces
+            .iter()
+            .map(|(contract, abi)| (contract.as_str(), vec![false; abi.entry_indexes().len()]))
+            .collect::<BTreeMap<_, _>>();
+
+        for (item, contract) in self.iter_entries().zip(self.item_contracts()) {
+            let contract = contract.and_then(|(contract, index)| {
+                *listed.get_mut(contract)?.get_mut(index)? = true;
+                Some(contract)
+            });
+
+            entries.push(match item {
+                AbiItem::Function(f) => AbiEntry::function(f, contract),
+                AbiItem::Event(e) => AbiEntry::event(e, contract),
+                AbiItem::Error(e) => AbiEntry::error(e, contract),
             });
         }
+
+        // namespaced entries missing from the top-level lists
+        for (contract, abi) in &self.namespaces {
+            for (item, listed) in abi.iter_entries().zip(&listed[contract.as_str()]) {
+                if !listed {
+                    entries.push(match item {
+                        AbiItem::Function(f) => AbiEntry::function(f, Some(contract)),
+                        AbiItem::Event(e) => AbiEntry::event(e, Some(contract)),
+                        AbiItem::Error(e) => AbiEntry::error(e, Some(contract)),
+                    });
+                }
+            }
+        }
+
         entries.serialize(serializer)
     }
 }
@@ -146,7 +1651,106 @@ impl<'de> Deserialize<'de> for Abi {
     where
         D: serde::Deserializer<'de>,
     {
-        deserializer.deserialize_seq(AbiVisitor)
+        deserializer.deserialize_any(AbiVisitor)
+    }
+}
+
+/// Contract constructor definition.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Constructor {
+    /// Constructor inputs.
+    pub inputs: Vec<Param>,
+    /// Documentation attached by the compiler (`"doc"` or `"notice"`).
+    pub docs: Option<String>,
+}
+
+impl Constructor {
+    /// Returns the constructor's signature, e.g. `constructor(u32,string)`.
+    pub fn signature(&self) -> String {
+        format!(
+            "constructor({})",
+            self.inputs
+                .iter()
+                .map(|param| param.type_.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        )
+    }
+}
+
+/// Width of the method id closing calldata.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SelectorWidth {
+    /// The 4 byte [`Function::method_id`] in one word.
+    #[default]
+    Short,
+    /// The full keccak256 [`Function::method_id_full`] in four words, which
+    /// can't collide.
+    Full,
+}
+
+/// Byte order in which the first 4 bytes of the keccak256 hash of a
+/// signature are read into a method id.
+///
+/// The ola-lang compiler reads them big-endian, as Solidity does, so
+/// `createBook(u32,string)` closes its calldata with `0x072f7e97`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum SelectorEndianness {
+    /// First byte most significant, the compiler's order.
+    #[default]
+    BigEndian,
+    /// First byte least significant.
+    LittleEndian,
+}
+
+/// Whether a function reads or writes contract state and accepts value, as
+/// emitted by the compiler in `stateMutability`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StateMutability {
+    /// Neither reads nor writes state.
+    Pure,
+    /// Reads but doesn't write state.
+    View,
+    /// Writes state, rejecting value.
+    #[default]
+    NonPayable,
+    /// Writes state and accepts value.
+    Payable,
+}
+
+impl StateMutability {
+    /// Returns the keyword naming the mutability, e.g. `view`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StateMutability::Pure => "pure",
+            StateMutability::View => "view",
+            StateMutability::NonPayable => "nonpayable",
+            StateMutability::Payable => "payable",
+        }
+    }
+}
+
+impl std::fmt::Display for StateMutability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for StateMutability {
+    type Err = AbiError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "pure" => Ok(StateMutability::Pure),
+            "view" => Ok(StateMutability::View),
+            "nonpayable" => Ok(StateMutability::NonPayable),
+            "payable" => Ok(StateMutability::Payable),
+            _ => Err(AbiError::InvalidAbi(format!(
+                "invalid state mutability {:?}",
+                s
+            ))),
+        }
     }
 }
 
@@ -159,18 +1763,65 @@ pub struct Function {
     pub inputs: Vec<Param>,
     /// Function outputs.
     pub outputs: Vec<Param>,
+    /// Selector emitted by the compiler, if any.
+    ///
+    /// When present it is used as the method id instead of the locally
+    /// computed one, so encoding matches what was deployed on-chain.
+    pub selector: Option<u64>,
+    /// State mutability emitted by the compiler, `nonpayable` when absent.
+    pub state_mutability: StateMutability,
+    /// Documentation attached by the compiler (`"doc"` or `"notice"`).
+    pub docs: Option<String>,
+    /// Where the function was loaded from, see [`Abi::load`].
+    pub provenance: Option<Provenance>,
 }
 
 impl Function {
-    /// Computes the function's method id (function selector).
+    /// Returns the function's method id (function selector).
+    ///
+    /// This is the compiler emitted selector if the ABI entry had one,
+    /// otherwise the id computed from the function signature: the first 4
+    /// bytes of its keccak256 hash read as a big-endian `u32`.
     pub fn method_id(&self) -> u64 {
-        use tiny_keccak::{Hasher, Keccak};
+        self.selector.unwrap_or_else(|| self.computed_method_id())
+    }
+
+    /// Returns whether the function doesn't write state, so that it can be
+    /// queried with a call rather than sent in a transaction.
+    pub fn is_view(&self) -> bool {
+        matches!(
+            self.state_mutability,
+            StateMutability::View | StateMutability::Pure
+        )
+    }
+
+    /// Returns the signature, followed by the provenance if known, for
+    /// error messages.
+    pub fn describe(&self) -> String {
+        match &self.provenance {
+            Some(provenance) => format!("{} ({})", self.signature(), provenance),
+            None => self.signature(),
+        }
+    }
+
+    /// Computes the function's method id from its signature.
+    pub fn computed_method_id(&self) -> u64 {
+        method_id_of(&self.signature())
+    }
+
+    /// Computes the function's method id from its signature, reading the
+    /// hash prefix in the given byte order.
+    pub fn computed_method_id_with(&self, endianness: SelectorEndianness) -> u64 {
+        method_id_with_endianness(&self.signature(), endianness)
+    }
 
-        let mut keccak_out = [0u8; 32];
-        let mut hasher = Keccak::v256();
-        hasher.update(self.signature().as_bytes());
-        hasher.finalize(&mut keccak_out);
-        u32::from_be_bytes(keccak_out[0..4].try_into().unwrap()) as u64
+    /// Returns the function's full-width method id, the whole keccak256
+    /// hash of its signature as big-endian words; the method id computed
+    /// from the signature is the top half of the first word.
+    ///
+    /// Compiler emitted selectors are short only, so they don't apply.
+    pub fn method_id_full(&self) -> FixedArray4 {
+        method_id_full_of(&self.signature())
     }
 
     /// Returns the function's signature.
@@ -186,8 +1837,64 @@ impl Function {
         )
     }
 
+    /// Checks that the values match the function inputs: their number, their
+    /// types down to array elements and fixed array lengths, and the order
+    /// of named tuple members, which values with members swapped would
+    /// otherwise encode in.
+    ///
+    /// ```
+    /// use ola_lang_abi::{Abi, Type, Value};
+    ///
+    /// let abi = Abi::from_human_readable(&["function setIds(u32[2] ids)"]).unwrap();
+    /// let f = &abi.functions[0];
+    ///
+    /// let ids = |ids: Vec<Value>| Value::FixedArray(ids, Type::U32);
+    /// assert!(f.validate_inputs(&[ids(vec![Value::U32(1), Value::U32(2)])]).is_ok());
+    /// assert_eq!(
+    ///     f.validate_inputs(&[ids(vec![Value::U32(1)])]).unwrap_err().to_string(),
+    ///     "invalid param ids: expected 2 elements of u32, got 1"
+    /// );
+    /// assert!(f.validate_inputs(&[]).is_err());
+    /// ```
+    pub fn validate_inputs(&self, params: &[Value]) -> Result<()> {
+        if params.len() != self.inputs.len() {
+            return Err(AbiError::LengthMismatch(format!(
+                "{} takes {} params, got {}",
+                self.signature(),
+                self.inputs.len(),
+                params.len()
+            )));
+        }
+
+        for (i, (param, value)) in self.inputs.iter().zip(params).enumerate() {
+            value
+                .check_type(&param.type_)
+                .and_then(|_| check_member_order(value, &param.type_))
+                .map_err(|e| {
+                    let name = if param.name.is_empty() {
+                        i.to_string()
+                    } else {
+                        param.name.clone()
+                    };
+                    AbiError::InvalidInput(format!("invalid param {}: {}", name, e))
+                })?;
+        }
+
+        Ok(())
+    }
+
     // Decode function input from slice.
     pub fn decode_input_from_slice(&self, input: &[u64]) -> Result<DecodedParams> {
+        self.decode_input_with_options(input, DecodeOptions::default())
+    }
+
+    /// Decode function input params, without param-len and method id, with
+    /// the given options.
+    pub fn decode_input_with_options(
+        &self,
+        input: &[u64],
+        options: DecodeOptions,
+    ) -> Result<DecodedParams> {
         let inputs_types = self
             .inputs
             .iter()
@@ -198,13 +1905,23 @@ impl Function {
             self.inputs
                 .iter()
                 .cloned()
-                .zip(Value::decode_from_slice(input, &inputs_types)?)
+                .zip(Value::decode_with_options(input, &inputs_types, options)?)
                 .collect::<Vec<_>>(),
         ))
     }
 
     // Decode function output from slice.
     pub fn decode_output_from_slice(&self, output: &[u64]) -> Result<DecodedParams> {
+        self.decode_output_with_options(output, DecodeOptions::default())
+    }
+
+    /// Decode function output params, without param-len, with the given
+    /// options.
+    pub fn decode_output_with_options(
+        &self,
+        output: &[u64],
+        options: DecodeOptions,
+    ) -> Result<DecodedParams> {
         let ouputs_types = self
             .outputs
             .iter()
@@ -215,7 +1932,7 @@ impl Function {
             self.outputs
                 .iter()
                 .cloned()
-                .zip(Value::decode_from_slice(output, &ouputs_types)?)
+                .zip(Value::decode_with_options(output, &ouputs_types, options)?)
                 .collect::<Vec<_>>(),
         ))
     }
@@ -234,6 +1951,95 @@ struct AbiEntry {
     outputs: Option<Vec<Param>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     anonymous: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    selector: Option<SelectorEntry>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    state_mutability: Option<StateMutability>,
+    #[serde(alias = "group", skip_serializing_if = "Option::is_none")]
+    contract: Option<String>,
+    #[serde(alias = "notice", skip_serializing_if = "Option::is_none")]
+    doc: Option<String>,
+}
+
+impl AbiEntry {
+    fn function(f: &Function, contract: Option<&str>) -> Self {
+        AbiEntry {
+            type_: String::from("function"),
+            name: Some(f.name.clone()),
+            inputs: Some(f.inputs.clone()),
+            outputs: Some(f.outputs.clone()),
+            anonymous: None,
+            selector: f.selector.map(SelectorEntry::Number),
+            // the default is left implicit, as in ABIs predating the field
+            state_mutability: Some(f.state_mutability)
+                .filter(|&mutability| mutability != StateMutability::NonPayable),
+            contract: contract.map(String::from),
+            doc: f.docs.clone(),
+        }
+    }
+
+    fn event(e: &Event, contract: Option<&str>) -> Self {
+        AbiEntry {
+            type_: String::from("event"),
+            name: Some(e.name.clone()),
+            inputs: Some(e.inputs.clone()),
+            outputs: None,
+            anonymous: Some(e.anonymous),
+            selector: None,
+            state_mutability: None,
+            contract: contract.map(String::from),
+            doc: None,
+        }
+    }
+
+    fn constructor(c: &Constructor, contract: Option<&str>) -> Self {
+        AbiEntry {
+            type_: String::from("constructor"),
+            name: None,
+            inputs: Some(c.inputs.clone()),
+            outputs: None,
+            anonymous: None,
+            selector: None,
+            state_mutability: None,
+            contract: contract.map(String::from),
+            doc: c.docs.clone(),
+        }
+    }
+
+    fn error(e: &Error, contract: Option<&str>) -> Self {
+        AbiEntry {
+            type_: String::from("error"),
+            name: Some(e.name.clone()),
+            inputs: Some(e.inputs.clone()),
+            outputs: None,
+            anonymous: None,
+            selector: None,
+            state_mutability: None,
+            contract: contract.map(String::from),
+            doc: None,
+        }
+    }
+}
+
+/// Compiler emitted selector, either a number or a hex string.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum SelectorEntry {
+    Number(u64),
+    Hex(String),
+}
+
+impl SelectorEntry {
+    fn to_u64(&self) -> Result<u64> {
+        match self {
+            SelectorEntry::Number(selector) => Ok(*selector),
+            SelectorEntry::Hex(selector) => {
+                u64::from_str_radix(selector.trim_start_matches("0x"), 16).map_err(|e| {
+                    AbiError::InvalidAbi(format!("invalid function selector {}: {}", selector, e))
+                })
+            }
+        }
+    }
 }
 
 struct AbiVisitor;
@@ -249,10 +2055,7 @@ impl<'de> Visitor<'de> for AbiVisitor {
     where
         A: serde::de::SeqAccess<'de>,
     {
-        let mut abi = Abi {
-            functions: vec![],
-            events: vec![],
-        };
+        let mut abi = Abi::default();
 
         loop {
             let entry = seq.next_element::<AbiEntry>()?;
@@ -270,11 +2073,23 @@ impl<'de> Visitor<'de> for AbiVisitor {
                             serde::de::Error::custom("missing function name".to_string())
                         })?;
 
-                        abi.functions.push(Function {
+                        let selector = entry
+                            .selector
+                            .map(|selector| selector.to_u64())
+                            .transpose()
+                            .map_err(serde::de::Error::custom)?;
+
+                        let f = Function {
                             name,
                             inputs,
                             outputs,
-                        });
+                            selector,
+                            state_mutability: entry.state_mutability.unwrap_or_default(),
+                            docs: entry.doc,
+                            provenance: None,
+                        };
+
+                        abi.push_namespaced(entry.contract, |abi| abi.push_function(f.clone()));
                     }
                     "event" => {
                         let inputs = entry.inputs.unwrap_or_default();
@@ -287,11 +2102,42 @@ impl<'de> Visitor<'de> for AbiVisitor {
                             serde::de::Error::custom("missing event anonymous field".to_string())
                         })?;
 
-                        abi.events.push(Event {
+                        let e = Event {
                             name,
                             inputs,
                             anonymous,
-                        });
+                            provenance: None,
+                        };
+
+                        abi.push_namespaced(entry.contract, |abi| abi.push_event(e.clone()));
+                    }
+                    "error" => {
+                        let inputs = entry.inputs.unwrap_or_default();
+
+                        let name = entry.name.ok_or_else(|| {
+                            serde::de::Error::custom("missing error name".to_string())
+                        })?;
+
+                        let e = Error { name, inputs };
+
+                        abi.push_namespaced(entry.contract, |abi| abi.push_error(e.clone()));
+                    }
+                    "constructor" => {
+                        let c = Constructor {
+                            inputs: entry.inputs.unwrap_or_default(),
+                            docs: entry.doc,
+                        };
+
+                        let abi = match entry.contract {
+                            Some(contract) => abi.namespaces.entry(contract).or_default(),
+                            None => &mut abi,
+                        };
+
+                        if abi.constructor.is_some() {
+                            return Err(serde::de::Error::custom("duplicate constructor"));
+                        }
+
+                        abi.constructor = Some(c);
                     }
 
                     _ => {
@@ -304,13 +2150,29 @@ impl<'de> Visitor<'de> for AbiVisitor {
             }
         }
     }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::MapAccess<'de>,
+    {
+        let mut abi = Abi::default();
+
+        while let Some((contract, contract_abi)) = map.next_entry::<String, Abi>()? {
+            for (index, item) in contract_abi.iter_entries().enumerate() {
+                abi.push_item(item, Some((contract.clone(), index)));
+            }
+            abi.namespaces.insert(contract, contract_abi);
+        }
+
+        Ok(abi)
+    }
 }
 
 #[cfg(test)]
 mod test {
     use pretty_assertions::assert_eq;
 
-    use crate::types::Type;
+    use crate::{types::Type, EncodeOptions, Encoder, IntegerPacking};
 
     use super::*;
 
@@ -391,27 +2253,255 @@ mod test {
                     name: "".to_string(),
                     type_: Type::Address,
                     indexed: None,
+                    docs: None,
                 },
                 Param {
                     name: "x".to_string(),
                     type_: Type::FixedArray(Box::new(Type::U32), 2),
                     indexed: None,
+                    docs: None,
                 },
             ],
             outputs: vec![],
+            selector: None,
+            state_mutability: StateMutability::NonPayable,
+            docs: None,
+            provenance: None,
+        }
+    }
+
+    #[test]
+    fn function_signature() {
+        let fun = test_function();
+        assert_eq!(fun.signature(), "funname(address,u32[2])");
+    }
+
+    #[test]
+    fn function_method_id() {
+        let fun = test_function();
+        assert_eq!(fun.method_id(), 0xf146ff09);
+    }
+
+    #[test]
+    fn function_compiler_selector() {
+        let abi: Abi = serde_json::from_value(serde_json::json!([
+            {
+                "type": "function",
+                "name": "funname",
+                "inputs": [
+                    { "name": "", "type": "address" },
+                    { "name": "x", "type": "u32[2]" }
+                ],
+                "outputs": [],
+                "selector": "0x1122334455667788"
+            },
+            {
+                "type": "function",
+                "name": "other",
+                "inputs": [],
+                "outputs": [],
+                "selector": 42
+            }
+        ]))
+        .unwrap();
+
+        assert_eq!(abi.functions[0].method_id(), 0x1122334455667788);
+        assert_eq!(abi.functions[0].computed_method_id(), 0xf146ff09);
+        assert_eq!(abi.functions[1].method_id(), 42);
+
+        let encoded = abi.encode_input_with_signature("other()", &[]).unwrap();
+        assert_eq!(encoded, vec![0, 42]);
+        assert_eq!(
+            abi.decode_input_from_slice(&encoded).unwrap().0.name,
+            "other"
+        );
+
+        let ser_abi = serde_json::to_string(&abi).unwrap();
+        assert_eq!(serde_json::from_str::<Abi>(&ser_abi).unwrap(), abi);
+    }
+
+    #[test]
+    fn function_validate_inputs() {
+        let abi = Abi::from_human_readable(&[
+            "function add((u32 id, string name)[] books, bool?)",
+            "function noop()",
+        ])
+        .unwrap();
+        let f = &abi.functions[0];
+        let book = |members: [(&str, Value); 2]| {
+            Value::Tuple(
+                members
+                    .into_iter()
+                    .map(|(name, value)| (name.to_string(), value))
+                    .collect(),
+            )
+        };
+        let books = |books: Vec<Value>| Value::Array(books, f.inputs[0].type_.clone());
+        let ola = || Value::String("ola".to_string());
+
+        let ok = [
+            books(vec![book([("id", Value::U32(1)), ("name", ola())])]),
+            Value::None(Type::Bool),
+        ];
+        assert!(f.validate_inputs(&ok).is_ok());
+        assert!(abi.encode_input_with_signature(&f.signature(), &ok).is_ok());
+
+        // unnamed members are taken in order
+        let unnamed = [
+            books(vec![book([("", Value::U32(1)), ("", ola())])]),
+            Value::Some(Box::new(Value::Bool(true))),
+        ];
+        assert!(f.validate_inputs(&unnamed).is_ok());
+
+        for (params, err) in [
+            (
+                vec![ok[0].clone()],
+                "add((u32,string)[],bool?) takes 2 params, got 1",
+            ),
+            (
+                vec![
+                    books(vec![book([("id", Value::U32(1)), ("name", Value::U32(2))])]),
+                    ok[1].clone(),
+                ],
+                "invalid param books: expected string, got u32",
+            ),
+            (
+                vec![
+                    books(vec![book([("name", Value::U32(1)), ("id", ola())])]),
+                    ok[1].clone(),
+                ],
+                "invalid param books: expected tuple member id, got name",
+            ),
+            (
+                vec![
+                    books(vec![book([("name", ola()), ("id", Value::U32(1))])]),
+                    ok[1].clone(),
+                ],
+                "invalid param books: expected u32, got string",
+            ),
+            (
+                vec![ok[0].clone(), Value::Bool(true)],
+                "invalid param 1: expected bool?, got bool",
+            ),
+        ] {
+            assert_eq!(f.validate_inputs(&params).unwrap_err().to_string(), err);
+            assert_eq!(
+                abi.encode_input_with_signature(&f.signature(), &params)
+                    .unwrap_err()
+                    .to_string(),
+                err
+            );
         }
+
+        assert!(abi.encode_input_with_signature("noop()", &[]).is_ok());
+        assert!(abi
+            .encode_input_with_signature("noop()", &[Value::U32(1)])
+            .is_err());
     }
 
     #[test]
-    fn function_signature() {
-        let fun = test_function();
-        assert_eq!(fun.signature(), "funname(address,u32[2])");
+    fn abi_encode_input_by_name() {
+        let abi: Abi = serde_json::from_value(serde_json::json!({
+            "Token": [
+                { "type": "function", "name": "mint", "inputs": [{ "name": "ids", "type": "u32[]" }] },
+                { "type": "function", "name": "mint", "inputs": [{ "name": "ids", "type": "field[]" }] },
+                { "type": "function", "name": "burn", "inputs": [{ "name": "id", "type": "u32" }] }
+            ],
+            "Vault": [
+                { "type": "function", "name": "burn", "inputs": [{ "name": "id", "type": "u32" }] }
+            ]
+        }))
+        .unwrap();
+
+        // empty arrays are told apart by their element type
+        for (i, ty) in [Type::U32, Type::Field].into_iter().enumerate() {
+            let params = [Value::Array(vec![], ty)];
+            assert_eq!(
+                abi.encode_input_by_name("mint", &params).unwrap(),
+                vec![0, 1, abi.functions[i].method_id()]
+            );
+        }
+
+        let mixed = Value::Array(vec![Value::U32(1)], Type::Field);
+        assert!(abi.encode_input_by_name("mint", &[mixed]).is_err());
+        assert!(abi.encode_input_by_name("mint", ()).is_err());
+        assert!(matches!(
+            abi.encode_input_by_name("nope", ()),
+            Err(AbiError::FunctionNotFound)
+        ));
+        assert_eq!(
+            abi.encode_input_by_name("burn", &[Value::U32(1)])
+                .unwrap_err()
+                .to_string(),
+            "ambiguous call burn(u32), matches burn(u32), burn(u32)"
+        );
+
+        // plain Rust values, as a tuple
+        let abi = Abi::from_human_readable(&["function createBook(u32 id, string name)"]).unwrap();
+        assert_eq!(
+            abi.encode_input_by_name("createBook", (60u32, "olavm"))
+                .unwrap(),
+            vec![60, 5, 111, 108, 97, 118, 109, 7, 0x072f7e97]
+        );
+        assert!(abi.encode_input_by_name("createBook", (60u32,)).is_err());
+
+        // named tuple members are checked against the chosen overload
+        let abi = Abi::from_human_readable(&["function move((u32 from, u32 to) hop)"]).unwrap();
+        let hop = |from: &str, to: &str| {
+            Value::Tuple(vec![
+                (from.to_string(), Value::U32(1)),
+                (to.to_string(), Value::U32(2)),
+            ])
+        };
+        assert!(abi
+            .encode_input_by_name("move", &[hop("from", "to")])
+            .is_ok());
+        assert_eq!(
+            abi.encode_input_by_name("move", &[hop("to", "from")])
+                .unwrap_err()
+                .to_string(),
+            "invalid param hop: expected tuple member from, got to"
+        );
     }
 
     #[test]
-    fn function_method_id() {
-        let fun = test_function();
-        assert_eq!(fun.method_id(), 0xf146ff09);
+    fn function_method_id_endianness() {
+        let abi: Abi = serde_json::from_str(include_str!("../examples/BookExample.json")).unwrap();
+
+        // calldata of createBook(60, "olavm") built by the ola-lang compiler
+        let calldata = [60, 5, 111, 108, 97, 118, 109, 7, 120553111];
+        let f = abi.function("createBook").unwrap();
+        assert_eq!(f.method_id(), calldata[8]);
+        assert_eq!(
+            f.computed_method_id_with(SelectorEndianness::BigEndian),
+            0x072f7e97
+        );
+        assert_eq!(
+            f.computed_method_id_with(SelectorEndianness::LittleEndian),
+            0x977e2f07
+        );
+        assert_eq!(
+            abi.encode_input_with_signature(
+                "createBook(u32,string)",
+                &[Value::U32(60), Value::String("olavm".to_string())]
+            )
+            .unwrap(),
+            calldata
+        );
+
+        let little = abi
+            .clone()
+            .with_selector_endianness(SelectorEndianness::LittleEndian);
+        let f = little.function("createBook").unwrap();
+        assert_eq!(f.method_id(), 0x977e2f07);
+        assert_eq!(little.function_by_selector(0x977e2f07), Some(f));
+        assert_eq!(little.function_by_selector(calldata[8]), None);
+
+        // compiler emitted selectors are kept
+        let mut abi = abi;
+        abi.functions[0].selector = Some(42);
+        let little = abi.with_selector_endianness(SelectorEndianness::LittleEndian);
+        assert_eq!(little.functions[0].method_id(), 42);
     }
 
     #[test]
@@ -428,7 +2518,7 @@ mod test {
         let fun = test_function();
         let abi = Abi {
             functions: vec![fun],
-            events: vec![],
+            ..Default::default()
         };
 
         let mut params = Value::encode(&input_values);
@@ -450,6 +2540,112 @@ mod test {
         assert_eq!(dec, (&abi.functions[0], expected_decoded_params));
     }
 
+    #[test]
+    fn abi_decode_through_proxy() {
+        let proxy = Abi {
+            functions: vec![Function {
+                name: "delegate".to_string(),
+                inputs: vec![Param {
+                    name: "data".to_string(),
+                    type_: Type::Fields,
+                    indexed: None,
+                    docs: None,
+                }],
+                outputs: vec![],
+                selector: None,
+                state_mutability: StateMutability::NonPayable,
+                docs: None,
+                provenance: None,
+            }],
+            ..Default::default()
+        };
+        let implementation = Abi {
+            functions: vec![test_function()],
+            ..Default::default()
+        };
+
+        let inner_values = vec![
+            Value::Address(crate::FixedArray4([1, 2, 3, 4])),
+            Value::FixedArray(vec![Value::U32(37), Value::U32(109)], Type::U32),
+        ];
+        let inner_call = implementation
+            .encode_input_with_signature("funname(address,u32[2])", &inner_values)
+            .unwrap();
+        let outer_call = proxy
+            .encode_input_with_signature("delegate(fields)", &[Value::Fields(inner_call)])
+            .unwrap();
+
+        let decoded = proxy
+            .decode_through(&[&implementation], &outer_call)
+            .expect("decode_through failed");
+
+        assert_eq!(decoded.function.name, "delegate");
+        let inner = decoded.inner.expect("missing inner call");
+        assert_eq!(inner.function, &implementation.functions[0]);
+        assert_eq!(
+            inner
+                .params
+                .iter()
+                .map(|p| p.value.clone())
+                .collect::<Vec<_>>(),
+            inner_values
+        );
+        assert!(inner.inner.is_none());
+
+        assert_eq!(
+            proxy
+                .decode_through(&[], &outer_call)
+                .unwrap_err()
+                .to_string(),
+            "proxied call from delegate does not match any implementation ABI"
+        );
+
+        // a truncated inner call is reported as such, not as a mismatch
+        let mut truncated = implementation
+            .encode_input_with_signature("funname(address,u32[2])", &inner_values)
+            .unwrap();
+        truncated.drain(..2);
+        let outer_call = proxy
+            .encode_input_with_signature("delegate(fields)", &[Value::Fields(truncated)])
+            .unwrap();
+        let err = proxy
+            .decode_through(&[&implementation], &outer_call)
+            .unwrap_err();
+        assert_eq!(err.code(), "TRUNCATED");
+    }
+
+    #[test]
+    fn abi_decode_through_deep_proxies() {
+        let proxy = Abi::from_human_readable(&["function delegate(fields data)"]).unwrap();
+        let router =
+            Abi::from_human_readable(&["function route()", "function delegate(fields data)"])
+                .unwrap();
+        let impl_abis = [&proxy, &router];
+        let delegate = |call: Vec<u64>| {
+            proxy
+                .encode_input_with_signature("delegate(fields)", &[Value::Fields(call)])
+                .unwrap()
+        };
+
+        // a delegate call whose param-len word claims a missing param
+        let malformed = vec![1, proxy.functions[0].method_id()];
+        let chain = |depth| (0..depth).fold(malformed.clone(), |call, _| delegate(call));
+
+        // both ABIs dispatch, yet the malformed call is decoded once per level
+        let err = proxy
+            .decode_through(&impl_abis, &chain(MAX_TYPE_DEPTH - 1))
+            .unwrap_err();
+        assert_ne!(err.code(), "INVALID_INPUT");
+
+        assert_eq!(
+            proxy
+                .decode_through(&impl_abis, &chain(MAX_TYPE_DEPTH * 4))
+                .unwrap_err()
+                .to_string(),
+            "proxied calls nest deeper than 64 levels"
+        );
+    }
+
     #[test]
     fn abi_json_work() {
         let v = serde_json::json!([
@@ -496,6 +2692,7 @@ mod test {
                             name: "n".to_string(),
                             type_: Type::U32,
                             indexed: None,
+                            docs: None,
                         },
                         Param {
                             name: "x".to_string(),
@@ -504,12 +2701,816 @@ mod test {
                                 ("b".to_string(), Type::String)
                             ]),
                             indexed: None,
+                            docs: None,
                         }
                     ],
                     outputs: vec![],
+                    selector: None,
+                    state_mutability: StateMutability::NonPayable,
+                    docs: None,
+                    provenance: None,
                 }],
-                events: vec![],
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn abi_decode_output_by_name() {
+        let abi: Abi = serde_json::from_value(serde_json::json!([
+            {
+                "type": "function",
+                "name": "getBook",
+                "inputs": [{ "name": "id", "type": "u32" }],
+                "outputs": [{ "name": "title", "type": "string" }]
+            },
+            { "type": "function", "name": "set", "inputs": [{ "name": "x", "type": "u32" }] },
+            { "type": "function", "name": "set", "inputs": [{ "name": "x", "type": "bool" }] }
+        ]))
+        .unwrap();
+
+        let output = [2, 111, 108, 3];
+        let (f, params) = abi.decode_output_by_name("getBook", &output).unwrap();
+        assert_eq!(f.name, "getBook");
+        assert_eq!(
+            (f, params),
+            abi.decode_output_from_slice("getBook(u32)", &output)
+                .unwrap()
+        );
+
+        assert_eq!(
+            abi.decode_output_by_name("set", &[0])
+                .unwrap_err()
+                .to_string(),
+            "ambiguous function name set: set(u32), set(bool)"
+        );
+        assert!(abi.decode_output_by_name("missing", &[0]).is_err());
+        assert!(abi.decode_output_by_name("getBook", &[]).is_err());
+    }
+
+    #[test]
+    fn abi_decode_output_by_selector() {
+        let abi: Abi = serde_json::from_value(serde_json::json!([
+            {
+                "type": "function",
+                "name": "getBook",
+                "inputs": [{ "name": "id", "type": "u32" }],
+                "outputs": [{ "name": "title", "type": "string" }]
+            },
+            { "type": "function", "name": "a", "inputs": [], "outputs": [{ "name": "x", "type": "u32" }], "selector": 7 },
+            { "type": "function", "name": "b", "inputs": [], "outputs": [{ "name": "x", "type": "bool" }, { "name": "y", "type": "bool" }], "selector": 7 },
+            { "type": "function", "name": "c", "inputs": [], "outputs": [{ "name": "x", "type": "field" }], "selector": 7 }
+        ]))
+        .unwrap();
+
+        let output = [2, 111, 108, 3];
+        let method_id = abi.functions[0].method_id();
+        assert_eq!(
+            abi.decode_output_by_selector(method_id, &output).unwrap(),
+            abi.decode_output_from_slice("getBook(u32)", &output)
+                .unwrap()
+        );
+
+        let (f, _) = abi.decode_output_by_selector(7, &[1, 0, 2]).unwrap();
+        assert_eq!(f.name, "b");
+        assert_eq!(
+            abi.decode_output_by_selector(7, &[1, 1])
+                .unwrap_err()
+                .to_string(),
+            "ambiguous output, decodes as a(), c()"
+        );
+        assert!(matches!(
+            abi.decode_output_by_selector(8, &[1]),
+            Err(AbiError::FunctionNotFound)
+        ));
+        assert!(abi.decode_output_by_selector(method_id, &[]).is_err());
+    }
+
+    #[test]
+    fn abi_decode_colliding_selectors() {
+        let function = |name: &str, ty: &str| {
+            serde_json::json!({
+                "type": "function",
+                "name": name,
+                "inputs": [{ "name": "x", "type": ty }],
+                "selector": 7
+            })
+        };
+        let abi: Abi = serde_json::from_value(serde_json::json!([
+            function("a", "u32"),
+            function("b", "address"),
+            function("c", "bool")
+        ]))
+        .unwrap();
+
+        let (f, _) = abi.decode_input_from_slice(&[1, 2, 3, 4, 4, 7]).unwrap();
+        assert_eq!(f.name, "b");
+
+        // a single word fits both u32 and bool
+        assert_eq!(abi.decode_input_candidates(&[1, 1, 7]).len(), 2);
+        assert_eq!(
+            abi.decode_input_from_slice(&[1, 1, 7])
+                .unwrap_err()
+                .to_string(),
+            "ambiguous input, decodes as a(u32), c(bool)"
+        );
+        assert!(abi.decode_input_from_slice(&[1, 2, 2, 7]).is_err());
+        assert!(abi.decode_input_candidates(&[1, 2, 7]).is_empty());
+
+        // candidates are decoded with the options
+        let abi: Abi = serde_json::from_value(serde_json::json!([
+            function("a", "u32[]"),
+            function("c", "bool")
+        ]))
+        .unwrap();
+        let ids = Value::Array((1..=10).map(Value::U32).collect(), Type::U32);
+        let mut input = vec![];
+        Encoder::with_options(
+            &mut input,
+            EncodeOptions {
+                integer_packing: IntegerPacking::Varint,
+                ..Default::default()
+            },
+        )
+        .encode(std::slice::from_ref(&ids));
+        input.extend([input.len() as u64, 7]);
+
+        assert!(abi.decode_input_from_slice(&input).is_err());
+        let options = DecodeOptions {
+            integer_packing: IntegerPacking::Varint,
+            ..Default::default()
+        };
+        let (f, params) = abi.decode_input_with_options(&input, options).unwrap();
+        assert_eq!((f.name.as_str(), &params[0].value), ("a", &ids));
+    }
+
+    #[test]
+    fn abi_selector_width() {
+        let abi: Abi = serde_json::from_value(serde_json::json!([
+            { "type": "function", "name": "a", "inputs": [{ "name": "x", "type": "u32" }] },
+            { "type": "function", "name": "b", "inputs": [], "selector": 7 },
+            { "type": "function", "name": "c", "inputs": [], "selector": 7 }
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            abi.validate().unwrap_err().to_string(),
+            "method id collisions: 0x00000007 shared by b(), c()"
+        );
+
+        let a = &abi.functions[0];
+        assert_eq!(a.method_id_full().0[0] >> 32, a.computed_method_id());
+
+        let short = abi
+            .encode_input_with_selector("a(u32)", &[Value::U32(5)], SelectorWidth::Short)
+            .unwrap();
+        assert_eq!(
+            short,
+            abi.encode_input_with_signature("a(u32)", &[Value::U32(5)])
+                .unwrap()
+        );
+
+        // the short selectors of b and c collide, the full ones don't
+        let c = abi
+            .encode_input_with_selector("c()", &[], SelectorWidth::Full)
+            .unwrap();
+        assert_eq!(c[1..], abi.functions[2].method_id_full().0);
+        let options = DecodeOptions {
+            strict: true,
+            ..Default::default()
+        };
+        let (f, _) = abi
+            .decode_input_with_selector(&c, SelectorWidth::Full, options)
+            .unwrap();
+        assert_eq!(f.name, "c");
+
+        let mut bad = c.clone();
+        bad[0] = 1;
+        assert!(abi
+            .decode_input_with_selector(&bad, SelectorWidth::Full, options)
+            .is_err());
+        assert!(abi
+            .decode_input_with_selector(&c[1..], SelectorWidth::Full, options)
+            .is_err());
+        assert!(abi
+            .decode_input_with_selector(&short, SelectorWidth::Full, options)
+            .is_err());
+
+        let a = Abi::from_human_readable(&["function a(u32 x)", "function b(bool y)"]).unwrap();
+        assert!(a.validate().is_ok());
+    }
+
+    #[test]
+    fn abi_merge_provenance() {
+        let function = |name: &str| serde_json::json!({ "type": "function", "name": name, "inputs": [], "selector": 7 });
+        let artifact: Abi = serde_json::from_value(serde_json::json!({
+            "Token": [function("transfer")],
+            "Vault": [{ "type": "event", "name": "Deposit", "inputs": [], "anonymous": false }]
+        }))
+        .unwrap();
+        let mut abi = artifact.clone().with_source("token.json");
+
+        assert_eq!(
+            abi.functions[0].provenance.as_ref().unwrap().to_string(),
+            "token.json, contract Token, entry 0"
+        );
+        assert_eq!(
+            abi.contract("Vault").unwrap().events[0].provenance,
+            abi.events[0].provenance
+        );
+        // provenance doesn't affect the serialized ABI
+        assert_eq!(
+            serde_json::to_value(&abi).unwrap(),
+            serde_json::to_value(&artifact).unwrap()
+        );
+
+        let other: Abi = serde_json::from_value(serde_json::json!([function("transfer")])).unwrap();
+        abi.merge(other.with_source("copy.json")).unwrap();
+        assert_eq!(abi.functions.len(), 1);
+
+        let colliding: Abi = serde_json::from_value(serde_json::json!([function("send")])).unwrap();
+        assert_eq!(
+            abi.merge(colliding.with_source("send.json"))
+                .unwrap_err()
+                .to_string(),
+            "selector 0x00000007 of send() (send.json, entry 0) collides with \
+             transfer() (token.json, contract Token, entry 0)"
+        );
+    }
+
+    #[test]
+    fn abi_from_json_permissive() {
+        let json = r#"[
+            { "type": "fallback" },
+            { "type": "function", "name": "f", "inputs": [], "outputs": [] },
+            { "type": "receive", "stateMutability": "payable" }
+        ]"#;
+
+        assert!(serde_json::from_str::<Abi>(json).is_err());
+
+        let abi = Abi::from_json_permissive(json).unwrap();
+        assert_eq!(abi.functions[0].name, "f");
+        assert_eq!(
+            abi.skipped
+                .iter()
+                .map(|entry| (entry.index, entry.kind.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(0, "fallback"), (2, "receive")]
+        );
+        assert_eq!(abi.skipped[1].raw["stateMutability"], "payable");
+
+        let artifact =
+            Abi::from_json_permissive(r#"{ "Token": [{ "type": "fallback", "inputs": [] }] }"#)
+                .unwrap();
+        assert_eq!(artifact.skipped[0].contract.as_deref(), Some("Token"));
+
+        let artifact = Abi::from_json_permissive(&format!(
+            r#"{{ "abi": {}, "bytecode": "0x00", "contractName": "Token" }}"#,
+            json
+        ))
+        .unwrap();
+        assert_eq!(artifact, abi);
+        assert_eq!(artifact.skipped, abi.skipped);
+    }
+
+    #[test]
+    fn abi_constructor() {
+        let json = serde_json::json!([
+            {
+                "type": "constructor",
+                "inputs": [
+                    { "name": "supply", "type": "u32" },
+                    { "name": "symbol", "type": "string" }
+                ],
+                "doc": "Mints the supply."
+            },
+            { "type": "function", "name": "f", "inputs": [], "outputs": [] }
+        ]);
+        let abi: Abi = serde_json::from_value(json.clone()).unwrap();
+
+        let constructor = abi.constructor.as_ref().unwrap();
+        assert_eq!(constructor.signature(), "constructor(u32,string)");
+        assert_eq!(constructor.docs.as_deref(), Some("Mints the supply."));
+        assert_eq!(abi.functions.len(), 1);
+        assert_eq!(serde_json::to_value(&abi).unwrap()[0], json[0]);
+
+        let values = [Value::U32(100), Value::String("OLA".to_string())];
+        let input = abi.encode_constructor_input(&values).unwrap();
+        assert_eq!(input, vec![100, 3, 79, 76, 65, 5]);
+
+        let params = abi.decode_constructor_input(&input).unwrap();
+        assert_eq!(params[0].param.name, "supply");
+        assert_eq!(
+            params.iter().map(|p| p.value.clone()).collect::<Vec<_>>(),
+            values
+        );
+
+        assert!(abi.encode_constructor_input(&values[..1]).is_err());
+        assert!(matches!(
+            abi.decode_constructor_input(&input[..5]),
+            Err(AbiError::LengthMismatch(_))
+        ));
+
+        let abi = Abi::default();
+        assert_eq!(abi.encode_constructor_input(&[]).unwrap(), vec![0]);
+        assert!(abi.decode_constructor_input(&[0]).unwrap().is_empty());
+
+        let duplicate = serde_json::json!([
+            { "type": "constructor", "inputs": [] },
+            { "type": "constructor", "inputs": [] }
+        ]);
+        assert!(serde_json::from_value::<Abi>(duplicate).is_err());
+    }
+
+    #[test]
+    fn abi_decode_error_from_slice() {
+        let abi: Abi = serde_json::from_value(serde_json::json!([{
+            "type": "error",
+            "name": "Unauthorized",
+            "inputs": [{ "name": "caller", "type": "address" }]
+        }]))
+        .unwrap();
+        let selector = abi.errors[0].selector();
+
+        let (error, params) = abi
+            .decode_error_from_slice(&[0, 0, 0, 5, 4, selector])
+            .unwrap();
+        assert_eq!(error.name, "Unauthorized");
+        assert_eq!(params[0].value, Value::Address(FixedArray4([0, 0, 0, 5])));
+
+        assert_eq!(
+            abi.decode_error_from_slice(&[0, 0, 5, 3, selector])
+                .unwrap_err()
+                .to_string(),
+            "reached end of input while decoding Address"
+        );
+        assert_eq!(
+            abi.decode_error_from_slice(&[5, 4, selector])
+                .unwrap_err()
+                .to_string(),
+            "error param-len is 4, but the payload has 1 words"
+        );
+        assert_eq!(
+            abi.decode_error_from_slice(&[0, 7])
+                .unwrap_err()
+                .to_string(),
+            "ABI error not found"
+        );
+        assert!(abi.decode_error_from_slice(&[selector]).is_err());
+    }
+
+    #[test]
+    fn abi_error_kinds() {
+        let abi: Abi = serde_json::from_value(serde_json::json!([{
+            "type": "function",
+            "name": "setName",
+            "inputs": [{ "name": "name", "type": "string" }],
+            "outputs": []
+        }]))
+        .unwrap();
+        let method_id = abi.functions[0].method_id();
+
+        let kind = |input: &[u64]| abi.decode_input_from_slice(input).unwrap_err();
+
+        assert!(matches!(kind(&[1]), AbiError::Truncated(_)));
+        assert!(matches!(kind(&[0, 0]), AbiError::FunctionNotFound));
+        assert!(matches!(
+            kind(&[2, 1, 2, method_id]),
+            AbiError::Truncated(_)
+        ));
+        assert!(matches!(
+            kind(&[1, 0xff, 2, method_id]),
+            AbiError::InvalidUtf8(_)
+        ));
+        assert_eq!(kind(&[1, 0xff, 2, method_id]).code(), "INVALID_UTF8");
+
+        assert!(matches!(
+            abi.decode_log_from_slice(&[FixedArray4::ZERO], &[]),
+            Err(AbiError::EventNotFound)
+        ));
+        assert!(matches!(
+            Value::U32(1).check_type(&Type::Bool),
+            Err(AbiError::TypeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn abi_decode_batches() {
+        let abi = Abi::from_human_readable(&[
+            "function setId(u32 id)",
+            "event Transfer(u32 indexed id, u32 amount)",
+        ])
+        .unwrap();
+        let call = |id| vec![id, 1, abi.functions[0].method_id()];
+
+        let decoded = abi.decode_inputs(vec![call(1), vec![1], call(2)]);
+        assert_eq!(decoded.len(), 3);
+        assert_eq!(decoded[0].as_ref().unwrap().1[0].value, Value::U32(1));
+        assert!(decoded[1].is_err());
+        assert_eq!(decoded[2].as_ref().unwrap().1[0].value, Value::U32(2));
+
+        let log = |amount| RawLog {
+            topics: vec![abi.events[0].topic(), FixedArray4([0, 0, 0, 1])],
+            data: vec![amount],
+        };
+        let logs = [log(5), RawLog::default(), log(6)];
+        let decoded = abi.decode_logs(&logs);
+        assert_eq!(decoded[0].as_ref().unwrap().1[1].value, Value::U32(5));
+        assert!(decoded[1].is_err());
+        assert_eq!(decoded[2].as_ref().unwrap().1[1].value, Value::U32(6));
+    }
+
+    #[test]
+    fn abi_decode_log_extra_topics() {
+        let abi =
+            Abi::from_human_readable(&["event Transfer(u32 indexed id, u32 amount)"]).unwrap();
+        let topics = [
+            abi.events[0].topic(),
+            FixedArray4([0, 0, 0, 1]),
+            FixedArray4([0, 0, 0, 7]),
+        ];
+
+        assert!(abi.decode_log_from_slice(&topics, &[5]).is_err());
+        assert!(abi
+            .decode_log_with(&topics, &[5], ExtraTopics::Strict)
+            .is_err());
+
+        let (event, params, ignored) = abi
+            .decode_log_with(&topics, &[5], ExtraTopics::Ignore)
+            .unwrap();
+        assert_eq!(event.name, "Transfer");
+        assert_eq!(params[1].value, Value::U32(5));
+        assert_eq!(ignored, [FixedArray4([0, 0, 0, 7])]);
+
+        let (_, _, ignored) = abi
+            .decode_log_with(&topics[..2], &[5], ExtraTopics::Ignore)
+            .unwrap();
+        assert!(ignored.is_empty());
+    }
+
+    #[test]
+    fn abi_decode_anonymous_log_candidates() {
+        let abi: Abi = serde_json::from_value(serde_json::json!([
+            {
+                "type": "event",
+                "name": "Named",
+                "inputs": [{ "name": "x", "type": "u32", "indexed": true }],
+                "anonymous": false
+            },
+            {
+                "type": "event",
+                "name": "Moved",
+                "inputs": [
+                    { "name": "to", "type": "address", "indexed": true },
+                    { "name": "amount", "type": "u32" }
+                ],
+                "anonymous": true
+            },
+            {
+                "type": "event",
+                "name": "Noted",
+                "inputs": [
+                    { "name": "id", "type": "u32", "indexed": true },
+                    { "name": "note", "type": "string" }
+                ],
+                "anonymous": true
+            },
+            {
+                "type": "event",
+                "name": "Flagged",
+                "inputs": [{ "name": "flag", "type": "bool", "indexed": true }],
+                "anonymous": true
+            }
+        ]))
+        .unwrap();
+        let names = |candidates: Vec<(&Event, DecodedParams)>| {
+            candidates
+                .into_iter()
+                .map(|(e, _)| e.name.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let named = abi.events[0].topic();
+        assert_eq!(
+            names(
+                abi.decode_log_candidates(&[named, FixedArray4([0, 0, 0, 1])], &[])
+                    .unwrap()
+            ),
+            ["Named"]
+        );
+
+        let topics = [FixedArray4([0, 0, 0, 7])];
+        assert!(abi.decode_log_from_slice(&topics, &[5]).is_err());
+        assert_eq!(
+            names(abi.decode_log_candidates(&topics, &[5]).unwrap()),
+            ["Moved"]
+        );
+        assert_eq!(
+            names(abi.decode_log_candidates(&topics, &[1, 97]).unwrap()),
+            ["Noted"]
+        );
+
+        let (_, params) = abi.decode_log_candidates(&topics, &[]).unwrap().remove(0);
+        assert_eq!(params[0].value, Value::Bool(false));
+        assert!(matches!(
+            abi.decode_log_candidates(&topics, &[1, 2, 3]),
+            Err(AbiError::EventNotFound)
+        ));
+    }
+
+    #[test]
+    fn abi_selector_map() {
+        let mut abi: Abi = serde_json::from_value(serde_json::json!([
+            { "type": "function", "name": "a", "inputs": [] },
+            { "type": "function", "name": "b", "inputs": [{ "name": "x", "type": "u32" }] }
+        ]))
+        .unwrap();
+        let b = abi.functions[1].clone();
+
+        assert_eq!(abi.function_by_selector(b.method_id()), Some(&b));
+        assert_eq!(abi.function_by_signature("b(u32)"), Some(&b));
+        assert_eq!(abi.function_by_signature("b()"), None);
+
+        // added after the index was built
+        let c = Function {
+            name: "c".to_string(),
+            ..b.clone()
+        };
+        abi.push_function(c.clone());
+        assert_eq!(abi.function_by_signature("c(u32)"), Some(&c));
+
+        let d = Function {
+            name: "d".to_string(),
+            ..b.clone()
+        };
+        abi.functions.push(d.clone());
+        assert_eq!(abi.function_by_selector(d.method_id()), Some(&d));
+
+        let (f, _) = abi.decode_input_from_slice(&[7, 1, d.method_id()]).unwrap();
+        assert_eq!(f, &d);
+    }
+
+    #[test]
+    fn abi_docs() {
+        let abi: Abi = serde_json::from_value(serde_json::json!([{
+            "type": "function",
+            "name": "withdraw",
+            "notice": "Withdraws from the vault.",
+            "inputs": [{ "name": "amount", "type": "u32", "doc": "Amount in gwei." }],
+            "outputs": []
+        }]))
+        .unwrap();
+
+        let f = &abi.functions[0];
+        assert_eq!(f.docs.as_deref(), Some("Withdraws from the vault."));
+        assert_eq!(f.inputs[0].docs.as_deref(), Some("Amount in gwei."));
+
+        let json = serde_json::to_value(&abi).unwrap();
+        assert_eq!(json[0]["doc"], "Withdraws from the vault.");
+        assert_eq!(json[0]["inputs"][0]["doc"], "Amount in gwei.");
+        assert_eq!(serde_json::from_value::<Abi>(json).unwrap(), abi);
+    }
+
+    #[test]
+    fn abi_state_mutability() {
+        let json = serde_json::json!([
+            { "type": "function", "name": "balance", "inputs": [], "outputs": [], "stateMutability": "view" },
+            { "type": "function", "name": "hash", "inputs": [], "outputs": [], "stateMutability": "pure" },
+            { "type": "function", "name": "deposit", "inputs": [], "outputs": [], "stateMutability": "payable" },
+            { "type": "function", "name": "withdraw", "inputs": [], "outputs": [] }
+        ]);
+        let abi: Abi = serde_json::from_value(json.clone()).unwrap();
+
+        assert_eq!(
+            abi.functions
+                .iter()
+                .map(|f| (f.state_mutability, f.is_view()))
+                .collect::<Vec<_>>(),
+            [
+                (StateMutability::View, true),
+                (StateMutability::Pure, true),
+                (StateMutability::Payable, false),
+                (StateMutability::NonPayable, false),
+            ]
+        );
+        assert_eq!(serde_json::to_value(&abi).unwrap(), json);
+
+        let explicit: Abi = serde_json::from_value(serde_json::json!([
+            { "type": "function", "name": "withdraw", "inputs": [], "stateMutability": "nonpayable" }
+        ]))
+        .unwrap();
+        assert_eq!(
+            explicit.functions[0].state_mutability,
+            StateMutability::NonPayable
+        );
+        assert!(serde_json::from_value::<Abi>(serde_json::json!([
+            { "type": "function", "name": "f", "inputs": [], "stateMutability": "constant" }
+        ]))
+        .is_err());
+
+        assert_eq!(
+            "payable".parse::<StateMutability>().unwrap(),
+            StateMutability::Payable
+        );
+        assert_eq!(StateMutability::NonPayable.to_string(), "nonpayable");
+    }
+
+    #[test]
+    fn abi_decode_call_result() {
+        let json = serde_json::json!([
+            {
+                "type": "function",
+                "name": "withdraw",
+                "inputs": [{ "name": "amount", "type": "u32" }],
+                "outputs": [{ "name": "left", "type": "u32" }]
+            },
+            {
+                "type": "error",
+                "name": "InsufficientBalance",
+                "inputs": [
+                    { "name": "available", "type": "u32" },
+                    { "name": "required", "type": "u32" }
+                ]
+            }
+        ]);
+        let abi: Abi = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&abi).unwrap(), json);
+
+        let error = &abi.errors[0];
+        assert_eq!(error.signature(), "InsufficientBalance(u32,u32)");
+
+        match abi.decode_call_result("withdraw(u32)", &[40, 1]).unwrap() {
+            CallOutcome::Return(params) => assert_eq!(params[0].value, Value::U32(40)),
+            outcome => panic!("unexpected {:?}", outcome),
+        }
+
+        match abi
+            .decode_call_result("withdraw(u32)", &[10, 60, 2, error.selector()])
+            .unwrap()
+        {
+            CallOutcome::Revert(revert) => {
+                assert_eq!(revert.error, error);
+                assert_eq!(revert.params[1].value, Value::U32(60));
             }
+            outcome => panic!("unexpected {:?}", outcome),
+        }
+
+        // unknown selectors are not taken for reverts
+        assert!(matches!(
+            abi.decode_call_result("withdraw(u32)", &[10, 60, 2, 7]),
+            Ok(CallOutcome::Return(_))
+        ));
+    }
+
+    #[test]
+    fn abi_strict_output_decoding() {
+        let abi: Abi = serde_json::from_value(serde_json::json!([{
+            "type": "function",
+            "name": "withdraw",
+            "inputs": [],
+            "outputs": [{ "name": "left", "type": "u32" }]
+        }]))
+        .unwrap();
+        let strict = DecodeOptions {
+            strict: true,
+            ..Default::default()
+        };
+
+        assert!(abi
+            .decode_output_with_options("withdraw()", &[40, 1], strict)
+            .is_ok());
+        assert!(matches!(
+            abi.decode_output_with_options("withdraw()", &[40, 2], strict),
+            Err(AbiError::LengthMismatch(_))
+        ));
+        assert!(matches!(
+            abi.decode_output_with_options("withdraw()", &[40, 0, 2], strict),
+            Err(AbiError::LengthMismatch(_))
+        ));
+        assert!(abi
+            .decode_output_from_slice("withdraw()", &[40, 0, 2])
+            .is_ok());
+    }
+
+    #[test]
+    fn abi_entry_order() {
+        let json = serde_json::json!([
+            { "type": "event", "name": "Created", "inputs": [], "anonymous": false },
+            { "type": "function", "name": "create", "inputs": [], "outputs": [] },
+            { "type": "event", "name": "Deleted", "inputs": [], "anonymous": false },
+            { "type": "function", "name": "delete", "inputs": [], "outputs": [] }
+        ]);
+        let abi: Abi = serde_json::from_value(json.clone()).unwrap();
+
+        let names = abi
+            .iter_entries()
+            .map(|item| match item {
+                AbiItem::Function(f) => f.name.as_str(),
+                AbiItem::Event(e) => e.name.as_str(),
+                AbiItem::Error(e) => e.name.as_str(),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["Created", "create", "Deleted", "delete"]);
+        assert_eq!(serde_json::to_value(&abi).unwrap(), json);
+
+        // without a (consistent) order, functions come first
+        let mut unordered = abi.clone();
+        unordered.entry_order.clear();
+        assert_eq!(
+            unordered.iter_entries().next(),
+            Some(AbiItem::Function(&abi.functions[0]))
+        );
+        unordered.entry_order = vec![EntryIndex::Event(0); 4];
+        assert_eq!(
+            unordered.iter_entries().next(),
+            Some(AbiItem::Function(&abi.functions[0]))
+        );
+        assert_ne!(unordered, abi);
+    }
+
+    #[test]
+    fn abi_contract_namespaces() {
+        let abi: Abi = serde_json::from_value(serde_json::json!([
+            { "type": "function", "name": "transfer", "inputs": [], "contract": "Token" },
+            { "type": "function", "name": "deposit", "inputs": [], "group": "Vault" },
+            { "type": "event", "name": "Deposited", "inputs": [], "anonymous": false, "contract": "Vault" },
+            { "type": "function", "name": "version", "inputs": [] }
+        ]))
+        .unwrap();
+
+        assert_eq!(abi.functions.len(), 3);
+        assert_eq!(abi.namespaces.len(), 2);
+        assert!(abi
+            .contract("Token")
+            .unwrap()
+            .function("transfer")
+            .is_some());
+        assert!(abi.contract("Token").unwrap().function("deposit").is_none());
+        assert!(abi.contract("Vault").unwrap().event("Deposited").is_some());
+        assert!(abi.contract("Other").is_none());
+        assert!(abi.function("version").is_some());
+
+        let ser_abi = serde_json::to_string(&abi).unwrap();
+        let de_abi: Abi = serde_json::from_str(&ser_abi).unwrap();
+        assert_eq!(de_abi.namespaces, abi.namespaces);
+        assert_eq!(de_abi.functions.len(), 3);
+    }
+
+    #[test]
+    fn abi_contract_namespaces_shared_entry() {
+        let transfer = serde_json::json!({ "type": "function", "name": "transfer", "inputs": [{ "name": "", "type": "u32" }] });
+        let deposit = serde_json::json!({ "type": "function", "name": "deposit", "inputs": [] });
+        let abi: Abi = serde_json::from_value(serde_json::json!({
+            "Token": [transfer],
+            "Vault": [transfer, deposit]
+        }))
+        .unwrap();
+
+        let contracts = |abi: &Abi| {
+            serde_json::to_value(abi)
+                .unwrap()
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|entry| (entry["name"].clone(), entry["contract"].clone()))
+                .collect::<Vec<_>>()
+        };
+        let expected = vec![
+            ("transfer".into(), "Token".into()),
+            ("transfer".into(), "Vault".into()),
+            ("deposit".into(), "Vault".into()),
+        ];
+        assert_eq!(contracts(&abi), expected);
+
+        let de_abi: Abi = serde_json::from_value(serde_json::to_value(&abi).unwrap()).unwrap();
+        assert_eq!(de_abi, abi);
+        assert_eq!(contracts(&de_abi), expected);
+
+        let abi = abi.with_source("artifact.json");
+        let provenances = abi
+            .functions
+            .iter()
+            .map(|f| f.provenance.as_ref().unwrap().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            provenances,
+            vec![
+                "artifact.json, contract Token, entry 0",
+                "artifact.json, contract Vault, entry 0",
+                "artifact.json, contract Vault, entry 1",
+            ]
+        );
+
+        let mut merged = Abi::default();
+        merged.merge(abi).unwrap();
+        assert_eq!(merged.functions.len(), 2);
+        assert_eq!(
+            contracts(&merged),
+            vec![
+                ("transfer".into(), "Token".into()),
+                ("deposit".into(), "Vault".into()),
+                ("transfer".into(), "Vault".into()),
+            ]
         );
     }
 
@@ -0,0 +1,243 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    collections::BTreeMap,
+    ops::{Bound, RangeBounds},
+};
+
+use crate::{
+    error::{AbiError, Result},
+    Abi, FixedArray4,
+};
+
+/// ABI versions of upgradeable contracts by address and block height, to
+/// decode historical transactions with the ABI that was live at the time.
+///
+/// The store serializes as a list of `{ address, from, to, abi }` entries,
+/// `to` being exclusive and `null` for versions still live.
+///
+/// ```
+/// use ola_lang_abi::{Abi, AbiStore, FixedArray4};
+///
+/// let v1 = Abi::from_human_readable(&["function transfer(address to, u32 amount)"]).unwrap();
+/// let v2 = Abi::from_human_readable(&["function transfer(address to, u256 amount)"]).unwrap();
+/// let token = FixedArray4([0, 0, 0, 1]);
+///
+/// let mut store = AbiStore::new();
+/// store.insert(token, 100..500, v1).unwrap();
+/// store.insert(token, 500.., v2).unwrap();
+///
+/// assert_eq!(store.get(token, 99), None);
+/// assert_eq!(store.get(token, 499).unwrap().functions[0].signature(), "transfer(address,u32)");
+/// assert_eq!(store.get(token, 10_000).unwrap().functions[0].signature(), "transfer(address,u256)");
+/// assert!(store.insert(token, 400..600, Abi::default()).is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AbiStore {
+    contracts: BTreeMap<FixedArray4, BTreeMap<u64, Version>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Version {
+    to: Option<u64>,
+    abi: Abi,
+}
+
+impl AbiStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the ABI of a contract for a range of block heights, e.g.
+    /// `100..500`, or `500..` for the live version. Fails if the range is
+    /// empty or overlaps one of another version of the contract.
+    pub fn insert(
+        &mut self,
+        address: FixedArray4,
+        heights: impl RangeBounds<u64>,
+        abi: Abi,
+    ) -> Result<()> {
+        let from = match heights.start_bound() {
+            Bound::Included(&from) => from,
+            Bound::Excluded(&from) => from.checked_add(1).ok_or_else(|| empty(address))?,
+            Bound::Unbounded => 0,
+        };
+        let to = match heights.end_bound() {
+            Bound::Included(&to) => to.checked_add(1),
+            Bound::Excluded(&to) => Some(to),
+            Bound::Unbounded => None,
+        };
+        if to.is_some_and(|to| to <= from) {
+            return Err(empty(address));
+        }
+
+        let overlaps = self.contracts.get(&address).is_some_and(|versions| {
+            let before = versions
+                .range(..=from)
+                .next_back()
+                .is_some_and(|(_, version)| version.to.map_or(true, |end| end > from));
+            let after = match to {
+                Some(to) => versions.range(from..to).next().is_some(),
+                None => versions.range(from..).next().is_some(),
+            };
+            before || after
+        });
+        if overlaps {
+            return Err(AbiError::Ambiguous(format!(
+                "heights {}..{} of {} overlap another ABI version",
+                from,
+                to.map(|to| to.to_string()).unwrap_or_default(),
+                address
+            )));
+        }
+
+        self.contracts
+            .entry(address)
+            .or_default()
+            .insert(from, Version { to, abi });
+
+        Ok(())
+    }
+
+    /// Returns the ABI of a contract at a block height.
+    pub fn get(&self, address: FixedArray4, height: u64) -> Option<&Abi> {
+        let (_, version) = self.contracts.get(&address)?.range(..=height).next_back()?;
+
+        version
+            .to
+            .map_or(true, |to| height < to)
+            .then_some(&version.abi)
+    }
+
+    /// Returns the ABI versions of a contract with their first and, unless
+    /// still live, past-the-end heights, in height order.
+    pub fn versions(
+        &self,
+        address: FixedArray4,
+    ) -> impl Iterator<Item = (u64, Option<u64>, &Abi)> + '_ {
+        self.contracts
+            .get(&address)
+            .into_iter()
+            .flatten()
+            .map(|(&from, version)| (from, version.to, &version.abi))
+    }
+
+    /// Addresses of the contracts in the store, in order.
+    pub fn addresses(&self) -> impl Iterator<Item = FixedArray4> + '_ {
+        self.contracts.keys().copied()
+    }
+}
+
+fn empty(address: FixedArray4) -> AbiError {
+    AbiError::InvalidInput(format!("empty height range for {}", address))
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry<A> {
+    address: FixedArray4,
+    from: u64,
+    to: Option<u64>,
+    abi: A,
+}
+
+impl Serialize for AbiStore {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(self.contracts.iter().flat_map(|(&address, versions)| {
+            versions.iter().map(move |(&from, version)| Entry {
+                address,
+                from,
+                to: version.to,
+                abi: &version.abi,
+            })
+        }))
+    }
+}
+
+impl<'de> Deserialize<'de> for AbiStore {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut store = AbiStore::new();
+        for entry in Vec::<Entry<Abi>>::deserialize(deserializer)? {
+            let from = entry.from;
+            let inserted = match entry.to {
+                Some(to) => store.insert(entry.address, from..to, entry.abi),
+                None => store.insert(entry.address, from.., entry.abi),
+            };
+            inserted.map_err(serde::de::Error::custom)?;
+        }
+
+        Ok(store)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn abi_store() {
+        let abi = |line: &str| Abi::from_human_readable(&[line]).unwrap();
+        let token = FixedArray4([0, 0, 0, 1]);
+        let vault = FixedArray4([0, 0, 0, 2]);
+
+        let mut store = AbiStore::new();
+        store
+            .insert(token, 10..=19, abi("function a(u32 x)"))
+            .unwrap();
+        store.insert(token, 30.., abi("function c(u32 x)")).unwrap();
+        store
+            .insert(token, 20..30, abi("function b(u32 x)"))
+            .unwrap();
+        store.insert(vault, .., abi("function d()")).unwrap();
+
+        let name = |address, height| {
+            store
+                .get(address, height)
+                .map(|abi| abi.functions[0].name.clone())
+        };
+        assert_eq!(name(token, 9), None);
+        assert_eq!(name(token, 10).as_deref(), Some("a"));
+        assert_eq!(name(token, 19).as_deref(), Some("a"));
+        assert_eq!(name(token, 20).as_deref(), Some("b"));
+        assert_eq!(name(token, u64::MAX).as_deref(), Some("c"));
+        assert_eq!(name(vault, 0).as_deref(), Some("d"));
+        assert_eq!(name(FixedArray4([0; 4]), 10), None);
+        assert_eq!(
+            store
+                .versions(token)
+                .map(|(from, to, _)| (from, to))
+                .collect::<Vec<_>>(),
+            [(10, Some(20)), (20, Some(30)), (30, None)]
+        );
+        assert_eq!(store.addresses().collect::<Vec<_>>(), [token, vault]);
+
+        for heights in [0..11, 15..16, 19..25, 5..40] {
+            assert!(store.insert(token, heights, Abi::default()).is_err());
+        }
+        assert!(store.insert(token, 40.., Abi::default()).is_err());
+        assert!(store.insert(token, 5..5, Abi::default()).is_err());
+        assert!(store.insert(vault, 0.., Abi::default()).is_err());
+        assert!(store
+            .insert(FixedArray4([0; 4]), 1..1, Abi::default())
+            .is_err());
+        assert_eq!(store.addresses().count(), 2);
+        store.insert(token, 0..10, Abi::default()).unwrap();
+
+        let json = serde_json::to_string(&store).unwrap();
+        let restored: AbiStore = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, store);
+
+        let overlapping = serde_json::json!([
+            { "address": token, "from": 0, "to": null, "abi": [] },
+            { "address": token, "from": 5, "to": 6, "abi": [] }
+        ]);
+        assert!(serde_json::from_value::<AbiStore>(overlapping).is_err());
+    }
+}
@@ -0,0 +1,129 @@
+use serde_json::{Map, Value as Json};
+
+use crate::{
+    error::{AbiError, Result},
+    Abi,
+};
+
+/// Contract artifact written by the ola-lang compiler: the ABI under an
+/// `"abi"` key, next to the bytecode and compiler metadata.
+///
+/// ```
+/// use ola_lang_abi::Artifact;
+///
+/// let artifact = Artifact::from_json(br#"{
+///     "contractName": "Book",
+///     "abi": [{"type": "function", "name": "f", "inputs": []}],
+///     "bytecode": "0x0102"
+/// }"#).unwrap();
+///
+/// assert_eq!(artifact.abi.functions[0].signature(), "f()");
+/// assert_eq!(artifact.bytecode.as_deref(), Some("0x0102"));
+/// assert_eq!(artifact.metadata["contractName"], "Book");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Artifact {
+    /// Contract ABI.
+    pub abi: Abi,
+    /// Contract bytecode, as the `"bytecode"` string of the artifact.
+    pub bytecode: Option<String>,
+    /// The other fields of the artifact, e.g. the contract name and compiler
+    /// version.
+    pub metadata: Map<String, Json>,
+}
+
+impl Artifact {
+    /// Parses an artifact, or a bare ABI (an array of entries or a map of
+    /// contract name to ABI) as an artifact without bytecode or metadata.
+    ///
+    /// Objects holding an `"abi"` array are taken for artifacts, so a
+    /// multi-contract map can't name a contract `abi`.
+    pub fn from_json(json: &[u8]) -> Result<Artifact> {
+        let invalid = |e: serde_json::Error| AbiError::InvalidAbi(e.to_string());
+        let json: Json = serde_json::from_slice(json).map_err(invalid)?;
+
+        let mut metadata = match json {
+            Json::Object(object) if is_artifact(&object) => object,
+            json => {
+                return Ok(Artifact {
+                    abi: serde_json::from_value(json).map_err(invalid)?,
+                    ..Default::default()
+                })
+            }
+        };
+
+        let abi = serde_json::from_value(metadata.remove("abi").unwrap_or_default())
+            .map_err(|e| AbiError::InvalidAbi(format!("invalid artifact abi: {}", e)))?;
+        let bytecode = match metadata.remove("bytecode") {
+            Some(Json::String(bytecode)) => Some(bytecode),
+            Some(other) => {
+                // not a bytecode string, keep it as is
+                metadata.insert("bytecode".to_string(), other);
+                None
+            }
+            None => None,
+        };
+
+        Ok(Artifact {
+            abi,
+            bytecode,
+            metadata,
+        })
+    }
+}
+
+/// Tells whether a JSON object is an artifact rather than a multi-contract
+/// map, see [`Artifact::from_json`].
+pub(crate) fn is_artifact(object: &Map<String, Json>) -> bool {
+    object.get("abi").is_some_and(Json::is_array)
+}
+
+impl Abi {
+    /// Parses an ABI from either a compiler artifact or a bare ABI, see
+    /// [`Artifact::from_json`].
+    pub fn from_artifact_json(json: &[u8]) -> Result<Abi> {
+        Ok(Artifact::from_json(json)?.abi)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn artifact_or_bare_abi() {
+        let entries =
+            r#"[{"type": "function", "name": "f", "inputs": [{"name": "x", "type": "u32"}]}]"#;
+        let bare: Abi = serde_json::from_str(entries).unwrap();
+
+        assert_eq!(Abi::from_artifact_json(entries.as_bytes()).unwrap(), bare);
+
+        let artifact = format!(
+            r#"{{"abi": {}, "bytecode": "0x00", "compiler": {{"version": "0.1.1"}}}}"#,
+            entries
+        );
+        let artifact = Artifact::from_json(artifact.as_bytes()).unwrap();
+        assert_eq!(artifact.abi, bare);
+        assert_eq!(artifact.bytecode.as_deref(), Some("0x00"));
+        assert_eq!(
+            artifact.metadata["compiler"],
+            serde_json::json!({ "version": "0.1.1" })
+        );
+        assert!(!artifact.metadata.contains_key("abi"));
+
+        // multi-contract maps
+        let contracts = format!(r#"{{"Token": {}}}"#, entries);
+        let abi = Abi::from_artifact_json(contracts.as_bytes()).unwrap();
+        assert_eq!(abi.contract("Token"), Some(&bare));
+
+        let bytecode = format!(r#"{{"abi": {}, "bytecode": {{"object": "00"}}}}"#, entries);
+        let artifact = Artifact::from_json(bytecode.as_bytes()).unwrap();
+        assert_eq!(artifact.bytecode, None);
+        assert_eq!(artifact.metadata["bytecode"]["object"], "00");
+
+        assert!(Abi::from_artifact_json(br#"{"abi": [{"type": "function"}]}"#).is_err());
+        assert!(Abi::from_artifact_json(b"{").is_err());
+    }
+}
@@ -0,0 +1,474 @@
+//! Command line interface, built with the `cli` feature.
+
+use std::{collections::BTreeMap, io::IsTerminal, path::PathBuf, process::ExitCode};
+
+use anyhow::{anyhow, Context, Result};
+use clap::{Args, Parser, Subcommand};
+use ola_lang_abi::{
+    calldata, Abi, AbiError, DecodedParams, ExtraTopics, FieldsEncoding, FixedArray4, Function,
+    Units, Value, ValueFormatter, MAX_DECIMALS,
+};
+
+#[derive(Parser)]
+#[command(
+    name = "ola-abi",
+    version,
+    about = "Ola contract ABI encoding and decoding"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    /// Print errors as `{ "code", "message", "context" }` JSON objects.
+    #[arg(long, global = true)]
+    json_errors: bool,
+}
+
+#[derive(Subcommand)]
+#[allow(clippy::enum_variant_names)]
+enum Command {
+    /// Encode function input calldata, printed as a JSON array.
+    Encode {
+        /// ABI JSON file.
+        abi: PathBuf,
+        /// Function signature, e.g. `createBook(u32,string)`, or unambiguous
+        /// name.
+        signature: String,
+        /// Param values, as a JSON array, e.g. `[60, "ola"]`.
+        values: String,
+        /// Print the calldata as hex instead.
+        #[arg(long)]
+        hex: bool,
+    },
+    /// Decode function input calldata.
+    DecodeInput {
+        /// ABI JSON file.
+        abi: PathBuf,
+        /// Calldata words, as a JSON array, comma separated or hex.
+        data: String,
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Decode function output data.
+    DecodeOutput {
+        /// ABI JSON file.
+        abi: PathBuf,
+        /// Function signature, e.g. `getBook(u32)`, unambiguous name or `0x`
+        /// prefixed method id.
+        signature: String,
+        /// Output words, as a JSON array, comma separated or hex.
+        data: String,
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Decode an event log.
+    DecodeLog {
+        /// ABI JSON file.
+        abi: PathBuf,
+        /// Log topic as a hex string, repeated for every topic.
+        #[arg(long = "topic")]
+        topics: Vec<String>,
+        /// Log data words, as a JSON array, comma separated or hex.
+        data: String,
+        /// Ignore topics beyond the ones of the event, with a warning,
+        /// instead of failing.
+        #[arg(long)]
+        ignore_extra_topics: bool,
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// List the method ids of the functions, the selectors of the errors and
+    /// the topics of the events by signature, as JSON.
+    Selectors {
+        /// ABI JSON file.
+        abi: PathBuf,
+    },
+    /// Check that an ABI parses and that its method ids don't collide.
+    Validate {
+        /// ABI JSON file.
+        abi: PathBuf,
+    },
+    /// Emit encode/decode test vectors for every function, as JSON.
+    Vectors {
+        /// ABI JSON file.
+        abi: PathBuf,
+    },
+    /// List ABI entries of unknown kinds, which the other commands reject,
+    /// as JSON.
+    Skipped {
+        /// ABI JSON file.
+        abi: PathBuf,
+    },
+    /// Render words as the hex calldata nodes accept.
+    Hex {
+        /// Words, as a JSON array or comma separated.
+        data: String,
+        /// Omit the `0x` prefix.
+        #[arg(long)]
+        no_prefix: bool,
+        /// Separator between words, e.g. " ".
+        #[arg(long)]
+        separator: Option<String>,
+    },
+}
+
+#[derive(Args)]
+struct OutputArgs {
+    /// Human readable, type annotated (and colored on terminals) output.
+    #[arg(long)]
+    pretty: bool,
+    /// Don't shorten long hex values in pretty output.
+    #[arg(long)]
+    full: bool,
+    /// Render a param as a decimal scaled amount in pretty output, repeated
+    /// for every param, e.g. `amount=9:OLA`.
+    #[arg(long = "units", value_name = "NAME=DECIMALS[:SYMBOL]")]
+    units: Vec<String>,
+    /// Rendering of fields payloads in pretty output: `array`, `hex` or
+    /// `base64`.
+    #[arg(long, value_parser = parse_fields_encoding, default_value = "array")]
+    fields: FieldsEncoding,
+}
+
+impl OutputArgs {
+    /// Prints the params, as the JSON array of values [`Command::Encode`]
+    /// takes unless pretty, in which case param docs follow their values.
+    fn print(&self, title: &str, docs: Option<&str>, params: &DecodedParams) -> Result<()> {
+        if self.pretty {
+            let color = std::io::stdout().is_terminal();
+            let mut formatter = ValueFormatter::new()
+                .fields(self.fields)
+                .color(color)
+                .full(self.full);
+            for units in &self.units {
+                let (name, units) = parse_units(units)?;
+                formatter = formatter.units(name, units);
+            }
+
+            println!("{}", title);
+            for line in docs.into_iter().flat_map(str::lines) {
+                println!("  // {}", line);
+            }
+            // one line per param
+            let lines = formatter.format_params(params);
+            for (line, decoded) in lines.lines().zip(params.iter()) {
+                match &decoded.param.docs {
+                    Some(docs) => {
+                        let docs = docs.lines().map(str::trim).collect::<Vec<_>>();
+                        println!("  {}  // {}", line, docs.join(" "));
+                    }
+                    None => println!("  {}", line),
+                }
+            }
+        } else {
+            let values = params
+                .iter()
+                .map(|decoded| decoded.value.to_json())
+                .collect::<Vec<_>>();
+
+            println!("{}", serde_json::to_string_pretty(&values)?);
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_fields_encoding(arg: &str) -> Result<FieldsEncoding> {
+    match arg {
+        "array" => Ok(FieldsEncoding::Array),
+        "hex" => Ok(FieldsEncoding::Hex),
+        "base64" => Ok(FieldsEncoding::Base64),
+        _ => Err(anyhow!(
+            "invalid fields encoding {:?}, expected array, hex or base64",
+            arg
+        )),
+    }
+}
+
+fn parse_units(arg: &str) -> Result<(&str, Units)> {
+    let (name, units) = arg
+        .split_once('=')
+        .ok_or_else(|| anyhow!("invalid units {:?}, expected NAME=DECIMALS[:SYMBOL]", arg))?;
+    let (decimals, symbol) = match units.split_once(':') {
+        Some((decimals, symbol)) => (decimals, Some(symbol)),
+        None => (units, None),
+    };
+
+    let decimals = decimals
+        .parse()
+        .map_err(|e| anyhow!("invalid decimals {:?}: {}", decimals, e))?;
+    if decimals > MAX_DECIMALS {
+        return Err(anyhow!(
+            "invalid decimals {}, expected at most {}",
+            decimals,
+            MAX_DECIMALS
+        ));
+    }
+    let units = match symbol {
+        Some(symbol) => Units::new(decimals).symbol(symbol),
+        None => Units::new(decimals),
+    };
+
+    Ok((name, units))
+}
+
+fn parse_words(data: &str) -> Result<Vec<u64>> {
+    let data = data.trim();
+
+    if data.starts_with("0x") {
+        return Ok(calldata::from_hex(data)?);
+    }
+
+    if data.starts_with('[') {
+        return serde_json::from_str(data).context("invalid JSON words array");
+    }
+
+    data.split(',')
+        .filter(|word| !word.trim().is_empty())
+        .map(|word| {
+            word.trim()
+                .parse()
+                .map_err(|e| anyhow!("invalid word {:?}: {}", word, e))
+        })
+        .collect()
+}
+
+/// Looks a function up by signature or, if unambiguous, name.
+fn find_function<'a>(abi: &'a Abi, signature: &str) -> Result<&'a Function> {
+    if signature.contains('(') {
+        return abi
+            .function_by_signature(signature)
+            .ok_or(AbiError::FunctionNotFound)
+            .with_context(|| format!("no function {}", signature));
+    }
+
+    let candidates = abi
+        .functions
+        .iter()
+        .filter(|f| f.name == signature)
+        .collect::<Vec<_>>();
+    match candidates[..] {
+        [f] => Ok(f),
+        [] => Err(AbiError::FunctionNotFound).with_context(|| format!("no function {}", signature)),
+        _ => Err(AbiError::Ambiguous(format!(
+            "ambiguous function name {}, give one of {}",
+            signature,
+            candidates
+                .iter()
+                .map(|f| f.signature())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))
+        .into()),
+    }
+}
+
+/// Parses the values of the function inputs from a JSON array.
+fn parse_values(function: &Function, values: &str) -> Result<Vec<Value>> {
+    let jsons: Vec<serde_json::Value> =
+        serde_json::from_str(values).context("invalid JSON values array")?;
+    if jsons.len() != function.inputs.len() {
+        return Err(anyhow!(
+            "{} takes {} params, got {} values",
+            function.signature(),
+            function.inputs.len(),
+            jsons.len()
+        ));
+    }
+
+    function
+        .inputs
+        .iter()
+        .zip(&jsons)
+        .enumerate()
+        .map(|(i, (param, json))| {
+            Value::from_json(&param.type_, json).with_context(|| match param.name.as_str() {
+                "" => format!("invalid param {}", i),
+                name => format!("invalid param {}", name),
+            })
+        })
+        .collect()
+}
+
+fn run(cli: Cli) -> Result<()> {
+    match cli.command {
+        Command::Encode {
+            abi,
+            signature,
+            values,
+            hex,
+        } => {
+            let abi = Abi::load(&abi)?;
+            let function = find_function(&abi, &signature)?;
+            let values = parse_values(function, &values)?;
+            let input = abi.encode_input_with_signature(&function.signature(), &values)?;
+
+            if hex {
+                println!("{}", calldata::to_hex(&input));
+            } else {
+                println!("{}", serde_json::to_string(&input)?);
+            }
+
+            Ok(())
+        }
+        Command::DecodeInput { abi, data, output } => {
+            let abi = Abi::load(&abi)?;
+            let (function, params) = abi.decode_input_from_slice(&parse_words(&data)?)?;
+
+            output.print(&function.signature(), function.docs.as_deref(), &params)
+        }
+        Command::DecodeOutput {
+            abi,
+            signature,
+            data,
+            output,
+        } => {
+            let abi = Abi::load(&abi)?;
+            let data = parse_words(&data)?;
+            let (function, params) = if signature.contains('(') {
+                abi.decode_output_from_slice(&signature, &data)?
+            } else if let Some(method_id) = signature.strip_prefix("0x") {
+                let method_id = u64::from_str_radix(method_id, 16)
+                    .with_context(|| format!("invalid method id {}", signature))?;
+                abi.decode_output_by_selector(method_id, &data)?
+            } else {
+                abi.decode_output_by_name(&signature, &data)?
+            };
+
+            output.print(&function.signature(), function.docs.as_deref(), &params)
+        }
+        Command::DecodeLog {
+            abi,
+            topics,
+            data,
+            ignore_extra_topics,
+            output,
+        } => {
+            let abi = Abi::load(&abi)?;
+            let topics = topics
+                .iter()
+                .map(|topic| FixedArray4::from_hex(topic))
+                .collect::<Result<Vec<_>, _>>()?;
+            let extra = if ignore_extra_topics {
+                ExtraTopics::Ignore
+            } else {
+                ExtraTopics::Strict
+            };
+            let (event, params, ignored) =
+                abi.decode_log_with(&topics, &parse_words(&data)?, extra)?;
+
+            if !ignored.is_empty() {
+                eprintln!(
+                    "warning: ignored {} extra topics of {}",
+                    ignored.len(),
+                    event.signature()
+                );
+            }
+
+            output.print(&event.signature(), None, &params)
+        }
+        Command::Selectors { abi } => {
+            let abi = Abi::load(&abi)?;
+
+            let hex = |id: u64| format!("{:#010x}", id);
+            let functions = abi
+                .functions
+                .iter()
+                .map(|f| (f.signature(), hex(f.method_id())))
+                .collect::<BTreeMap<_, _>>();
+            let errors = abi
+                .errors
+                .iter()
+                .map(|e| (e.signature(), hex(e.selector())))
+                .collect::<BTreeMap<_, _>>();
+            let events = abi
+                .events
+                .iter()
+                .map(|e| (e.signature(), e.topic().to_hex_string()))
+                .collect::<BTreeMap<_, _>>();
+
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&serde_json::json!({
+                    "functions": functions,
+                    "errors": errors,
+                    "events": events,
+                }))?
+            );
+
+            Ok(())
+        }
+        Command::Validate { abi: path } => {
+            let abi = Abi::load(&path)?;
+            abi.validate()?;
+
+            println!(
+                "{}: {} functions, {} events, {} errors",
+                path.display(),
+                abi.functions.len(),
+                abi.events.len(),
+                abi.errors.len()
+            );
+
+            Ok(())
+        }
+        Command::Vectors { abi } => {
+            let abi = Abi::load(&abi)?;
+
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&ola_lang_abi::test_vectors(&abi)?)?
+            );
+
+            Ok(())
+        }
+        Command::Skipped { abi } => {
+            let json = std::fs::read_to_string(&abi)
+                .with_context(|| format!("failed to open {}", abi.display()))?;
+            let abi = Abi::from_json_permissive(&json)
+                .with_context(|| format!("failed to parse {}", abi.display()))?;
+
+            println!("{}", serde_json::to_string_pretty(&abi.skipped)?);
+
+            Ok(())
+        }
+        Command::Hex {
+            data,
+            no_prefix,
+            separator,
+        } => {
+            let mut format = calldata::HexFormat::new().prefix(!no_prefix);
+            if let Some(separator) = separator {
+                format = format.separator(&separator);
+            }
+
+            println!("{}", format.format(&parse_words(&data)?));
+
+            Ok(())
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let json_errors = cli.json_errors;
+
+    match run(cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) if json_errors => {
+            // keep the context added to the error, if any, in the message
+            let message = match err.downcast_ref::<AbiError>() {
+                Some(abi_err) if abi_err.to_string() == err.to_string() => err.to_string(),
+                _ => format!("{:#}", err),
+            };
+            let mut json = AbiError::from(err).to_json();
+            json["message"] = message.into();
+
+            eprintln!("{}", json);
+            ExitCode::FAILURE
+        }
+        Err(err) => {
+            eprintln!("error: {:#}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,265 @@
+//! Decode result caching, enabled with the `cache` feature.
+
+use lru::LruCache;
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    num::NonZeroUsize,
+    sync::Mutex,
+};
+
+use crate::{error::Result, Abi, DecodedParams, Function};
+
+/// Cache hit/miss counters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheMetrics {
+    /// Number of decodes answered from the cache.
+    pub hits: u64,
+    /// Number of decodes that had to run the decoder.
+    pub misses: u64,
+    /// Number of cached entries.
+    pub len: usize,
+    /// Maximum number of cached entries.
+    pub capacity: usize,
+}
+
+impl CacheMetrics {
+    /// Ratio of hits over all lookups, `0.0` before the first lookup.
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+struct CacheEntry {
+    input: Vec<u64>,
+    function: usize,
+    params: DecodedParams,
+}
+
+struct CacheState {
+    entries: LruCache<(u64, u64), CacheEntry>,
+    hits: u64,
+    misses: u64,
+}
+
+/// [`Abi`] wrapper memoizing decoded function inputs.
+///
+/// Results are keyed by (selector, payload hash) and kept in a bounded LRU,
+/// which pays off for the identical calldata indexers see over and over
+/// (approvals, transfers). Failed decodes aren't cached.
+pub struct CachedAbi {
+    abi: Abi,
+    state: Mutex<CacheState>,
+}
+
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<CachedAbi>();
+};
+
+impl CachedAbi {
+    /// Wraps an ABI with a cache holding up to `capacity` decoded inputs.
+    pub fn new(abi: Abi, capacity: NonZeroUsize) -> Self {
+        Self {
+            abi,
+            state: Mutex::new(CacheState {
+                entries: LruCache::new(capacity),
+                hits: 0,
+                misses: 0,
+            }),
+        }
+    }
+
+    /// Returns the wrapped ABI.
+    pub fn abi(&self) -> &Abi {
+        &self.abi
+    }
+
+    /// Decode function input from slice, see [`Abi::decode_input_from_slice`].
+    pub fn decode_input_from_slice(&self, input: &[u64]) -> Result<(&Function, DecodedParams)> {
+        let key = (input.last().copied().unwrap_or_default(), hash_input(input));
+
+        {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            let cached = state
+                .entries
+                .get(&key)
+                .filter(|entry| entry.input == input)
+                .map(|entry| (entry.function, entry.params.clone()));
+
+            if let Some((function, params)) = cached {
+                state.hits += 1;
+                return Ok((&self.abi.functions[function], params));
+            }
+
+            state.misses += 1;
+        }
+
+        let (function, params) = self.abi.decode_input_from_slice(input)?;
+        let index = self
+            .abi
+            .functions
+            .iter()
+            .position(|f| std::ptr::eq(f, function))
+            .expect("decoded function belongs to the ABI");
+
+        self.state
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entries
+            .put(
+                key,
+                CacheEntry {
+                    input: input.to_vec(),
+                    function: index,
+                    params: params.clone(),
+                },
+            );
+
+        Ok((function, params))
+    }
+
+    /// Returns the cache counters.
+    pub fn metrics(&self) -> CacheMetrics {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+
+        CacheMetrics {
+            hits: state.hits,
+            misses: state.misses,
+            len: state.entries.len(),
+            capacity: state.entries.cap().get(),
+        }
+    }
+
+    /// Drops all cached entries and resets the counters.
+    pub fn clear(&self) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.entries.clear();
+        state.hits = 0;
+        state.misses = 0;
+    }
+}
+
+fn hash_input(input: &[u64]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    input.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::{AbiError, Value};
+
+    fn test_cached_abi(capacity: usize) -> CachedAbi {
+        let abi: Abi = serde_json::from_str(
+            r#"[{
+                "type": "function",
+                "name": "transfer",
+                "inputs": [{ "name": "amount", "type": "u32" }],
+                "outputs": []
+            }]"#,
+        )
+        .unwrap();
+
+        CachedAbi::new(abi, NonZeroUsize::new(capacity).unwrap())
+    }
+
+    fn transfer(cached: &CachedAbi, amount: u64) -> Vec<u64> {
+        cached
+            .abi()
+            .encode_input_with_signature("transfer(u32)", &[Value::U32(amount)])
+            .unwrap()
+    }
+
+    #[test]
+    fn cached_decode_input() {
+        let cached = test_cached_abi(2);
+        let input = transfer(&cached, 5);
+
+        let first = cached.decode_input_from_slice(&input).unwrap();
+        let second = cached.decode_input_from_slice(&input).unwrap();
+
+        assert_eq!(first, second);
+        assert_eq!(first, cached.abi().decode_input_from_slice(&input).unwrap());
+        assert_eq!(
+            cached.metrics(),
+            CacheMetrics {
+                hits: 1,
+                misses: 1,
+                len: 1,
+                capacity: 2,
+            }
+        );
+        assert_eq!(cached.metrics().hit_rate(), 0.5);
+    }
+
+    #[test]
+    fn cached_decode_evicts_least_recently_used() {
+        let cached = test_cached_abi(2);
+        let (a, b, c) = (
+            transfer(&cached, 1),
+            transfer(&cached, 2),
+            transfer(&cached, 3),
+        );
+
+        for input in [&a, &b, &a, &c, &a, &b] {
+            cached.decode_input_from_slice(input).unwrap();
+        }
+
+        // b was evicted by c, then c by b
+        assert_eq!(cached.metrics().hits, 2);
+        assert_eq!(cached.metrics().misses, 4);
+        assert_eq!(cached.metrics().len, 2);
+    }
+
+    #[test]
+    fn cached_decode_errors_are_not_cached() {
+        let cached = test_cached_abi(2);
+
+        assert!(matches!(
+            cached.decode_input_from_slice(&[0, 1]),
+            Err(AbiError::FunctionNotFound)
+        ));
+        assert_eq!(cached.metrics().len, 0);
+
+        cached.clear();
+        assert_eq!(
+            cached.metrics(),
+            CacheMetrics {
+                capacity: 2,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn cached_decode_input_shared_across_threads() {
+        let cached = test_cached_abi(4);
+        let inputs = (0..4).map(|i| transfer(&cached, i)).collect::<Vec<_>>();
+
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    for input in &inputs {
+                        let (f, params) = cached.decode_input_from_slice(input).unwrap();
+                        assert_eq!(f.name, "transfer");
+                        assert_eq!(params[0].value, Value::U32(input[0]));
+                    }
+                });
+            }
+        });
+
+        let metrics = cached.metrics();
+        assert_eq!(metrics.hits + metrics.misses, 32);
+        assert_eq!(metrics.len, 4);
+    }
+}
@@ -0,0 +1,262 @@
+//! Hex, base64 and byte rendering of calldata words.
+//!
+//! The canonical form nodes accept is a `0x` prefixed string of 16 hex digits
+//! per word, most significant digit first. Base64 packs the big-endian bytes
+//! of the words, for compact JSON. Raw bytes pack 8 bytes per word in the
+//! given [`ByteOrder`].
+//!
+//! ```
+//! use ola_lang_abi::calldata;
+//!
+//! let hex = calldata::to_hex(&[1, 0xff]);
+//! assert_eq!(hex, "0x000000000000000100000000000000ff");
+//! assert_eq!(calldata::from_hex(&hex).unwrap(), vec![1, 0xff]);
+//! ```
+
+use crate::alloc_prelude::*;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use core::fmt::Write;
+
+use crate::error::{AbiError, Result};
+
+/// Number of hex digits per word.
+const WORD_DIGITS: usize = 16;
+
+/// Hex rendering options.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HexFormat {
+    prefix: bool,
+    separator: Option<String>,
+}
+
+impl Default for HexFormat {
+    fn default() -> Self {
+        Self {
+            prefix: true,
+            separator: None,
+        }
+    }
+}
+
+impl HexFormat {
+    /// Creates the canonical format: `0x` prefixed, without separators.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enables the `0x` prefix.
+    pub fn prefix(mut self, prefix: bool) -> Self {
+        self.prefix = prefix;
+        self
+    }
+
+    /// Separates words, e.g. with `" "` or `"\n"` for debugging.
+    pub fn separator(mut self, separator: &str) -> Self {
+        self.separator = Some(separator.to_string());
+        self
+    }
+
+    /// Renders words as hex.
+    pub fn format(&self, words: &[u64]) -> String {
+        let separator = self.separator.as_deref().unwrap_or_default();
+        let mut out = String::with_capacity(2 + words.len() * (WORD_DIGITS + separator.len()));
+
+        if self.prefix {
+            out.push_str("0x");
+        }
+
+        for (i, word) in words.iter().enumerate() {
+            if i > 0 {
+                out.push_str(separator);
+            }
+            write!(out, "{:016x}", word).unwrap();
+        }
+
+        out
+    }
+}
+
+/// Renders words in the canonical hex form.
+pub fn to_hex(words: &[u64]) -> String {
+    HexFormat::new().format(words)
+}
+
+/// Parses hex rendered words, with or without `0x` prefix.
+///
+/// Whitespace, `_`, `:` and `,` separators are ignored; the remaining digits
+/// must make up whole words.
+pub fn from_hex(hex: &str) -> Result<Vec<u64>> {
+    let hex = hex.trim();
+    let hex = hex.strip_prefix("0x").unwrap_or(hex);
+
+    let digits = hex
+        .chars()
+        .filter(|c| !(c.is_whitespace() || matches!(c, '_' | ':' | ',')))
+        .collect::<String>();
+
+    if digits.len() % WORD_DIGITS != 0 {
+        return Err(AbiError::InvalidInput(format!(
+            "hex calldata has {} digits, not a multiple of {}",
+            digits.len(),
+            WORD_DIGITS
+        )));
+    }
+
+    (0..digits.len())
+        .step_by(WORD_DIGITS)
+        .map(|at| {
+            let word = digits
+                .get(at..at + WORD_DIGITS)
+                .ok_or_else(|| AbiError::InvalidInput("invalid hex calldata".to_string()))?;
+
+            u64::from_str_radix(word, 16)
+                .map_err(|e| AbiError::InvalidInput(format!("invalid hex word {:?}: {}", word, e)))
+        })
+        .collect()
+}
+
+/// Renders the big-endian bytes of the words as standard, padded base64.
+pub fn to_base64(words: &[u64]) -> String {
+    BASE64.encode(to_bytes(words, ByteOrder::BigEndian))
+}
+
+/// Parses words rendered by [`to_base64`].
+pub fn from_base64(base64: &str) -> Result<Vec<u64>> {
+    let bytes = BASE64
+        .decode(base64.trim())
+        .map_err(|e| AbiError::InvalidInput(format!("invalid base64 calldata: {}", e)))?;
+
+    from_bytes(&bytes, ByteOrder::BigEndian)
+        .map_err(|e| AbiError::InvalidInput(format!("base64 {}", e)))
+}
+
+/// Order of the 8 bytes of a word in byte-oriented calldata.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ByteOrder {
+    /// Most significant byte first, as in the hex and base64 forms.
+    #[default]
+    BigEndian,
+    /// Least significant byte first.
+    LittleEndian,
+}
+
+/// Packs words into 8 bytes each.
+///
+/// ```
+/// use ola_lang_abi::calldata::{self, ByteOrder};
+///
+/// assert_eq!(calldata::to_bytes(&[0x0102], ByteOrder::BigEndian), [0, 0, 0, 0, 0, 0, 1, 2]);
+/// assert_eq!(calldata::to_bytes(&[0x0102], ByteOrder::LittleEndian), [2, 1, 0, 0, 0, 0, 0, 0]);
+/// ```
+pub fn to_bytes(words: &[u64], order: ByteOrder) -> Vec<u8> {
+    words
+        .iter()
+        .flat_map(|word| match order {
+            ByteOrder::BigEndian => word.to_be_bytes(),
+            ByteOrder::LittleEndian => word.to_le_bytes(),
+        })
+        .collect()
+}
+
+/// Parses words packed by [`to_bytes`], failing unless the bytes make up
+/// whole words.
+pub fn from_bytes(bytes: &[u8], order: ByteOrder) -> Result<Vec<u64>> {
+    if bytes.len() % 8 != 0 {
+        return Err(AbiError::InvalidInput(format!(
+            "calldata has {} bytes, not a multiple of 8",
+            bytes.len()
+        )));
+    }
+
+    Ok(bytes
+        .chunks_exact(8)
+        .map(|chunk| {
+            let chunk = chunk.try_into().unwrap();
+            match order {
+                ByteOrder::BigEndian => u64::from_be_bytes(chunk),
+                ByteOrder::LittleEndian => u64::from_le_bytes(chunk),
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn hex_formats() {
+        let words = [1, u64::MAX];
+
+        assert_eq!(
+            HexFormat::new().prefix(false).separator(" ").format(&words),
+            "0000000000000001 ffffffffffffffff"
+        );
+        assert_eq!(to_hex(&[]), "0x");
+        assert_eq!(
+            HexFormat::new().separator("_").format(&[7]),
+            "0x0000000000000007"
+        );
+    }
+
+    #[test]
+    fn hex_round_trip() {
+        let words = [0, 1, 0x0123456789abcdef, u64::MAX];
+
+        assert_eq!(from_hex(&to_hex(&words)).unwrap(), words);
+        assert_eq!(
+            from_hex(
+                &HexFormat::new()
+                    .prefix(false)
+                    .separator("\n")
+                    .format(&words)
+            )
+            .unwrap(),
+            words
+        );
+        assert_eq!(from_hex("0x").unwrap(), Vec::<u64>::new());
+
+        assert!(from_hex("0x01").is_err());
+        assert!(from_hex("0x000000000000000g").is_err());
+        assert!(from_hex("0x000000000000000é000000000000000").is_err());
+    }
+
+    #[test]
+    fn base64_round_trip() {
+        let words = [0, 1, u64::MAX];
+
+        assert_eq!(to_base64(&[1]), "AAAAAAAAAAE=");
+        assert_eq!(from_base64(&to_base64(&words)).unwrap(), words);
+        assert_eq!(from_base64("").unwrap(), Vec::<u64>::new());
+
+        assert!(from_base64("AAAA").is_err());
+        assert!(from_base64("!").is_err());
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        let words = [0, 1, 0x0123456789abcdef, u64::MAX];
+
+        for order in [ByteOrder::BigEndian, ByteOrder::LittleEndian] {
+            let bytes = to_bytes(&words, order);
+            assert_eq!(bytes.len(), 32);
+            assert_eq!(from_bytes(&bytes, order).unwrap(), words);
+        }
+        assert_eq!(
+            to_bytes(&[0x0123456789abcdef], ByteOrder::LittleEndian),
+            [0xef, 0xcd, 0xab, 0x89, 0x67, 0x45, 0x23, 0x01]
+        );
+        assert_eq!(
+            from_bytes(&[1, 0, 0, 0, 0, 0, 0, 0], ByteOrder::LittleEndian).unwrap(),
+            [1]
+        );
+
+        assert!(from_bytes(&[0; 7], ByteOrder::BigEndian).is_err());
+        assert_eq!(
+            from_bytes(&[], ByteOrder::BigEndian).unwrap(),
+            Vec::<u64>::new()
+        );
+    }
+}
@@ -0,0 +1,178 @@
+//! Types-only encoding and decoding, without function or event framing.
+//!
+//! Meant for protocols defining their own message layouts with Ola types
+//! that aren't contract calls: no param length or method id words are added
+//! or expected, and values are checked against the types.
+//!
+//! ```
+//! use ola_lang_abi::{codec, Type, Value};
+//!
+//! let tys = [Type::U32, Type::String];
+//! let values = [Value::U32(7), Value::String("ola".to_string())];
+//!
+//! let words = codec::encode(&tys, &values).unwrap();
+//! assert_eq!(words, vec![7, 3, 111, 108, 97]);
+//! assert_eq!(codec::decode(&tys, &words).unwrap(), values);
+//! ```
+//!
+//! With the `json` feature, [`encode_values`] and [`decode_values`] take the
+//! type as a string and the values as JSON, for scripts.
+
+use crate::alloc_prelude::*;
+
+use crate::{
+    error::{AbiError, Result},
+    Type, Value,
+};
+
+/// Encodes values of the given types.
+///
+/// Fails if the values don't match the types.
+pub fn encode(tys: &[Type], values: &[Value]) -> Result<Vec<u64>> {
+    if tys.len() != values.len() {
+        return Err(AbiError::LengthMismatch(format!(
+            "expected {} values, got {}",
+            tys.len(),
+            values.len()
+        )));
+    }
+
+    for (i, (ty, value)) in tys.iter().zip(values).enumerate() {
+        value
+            .check_type(ty)
+            .map_err(|e| AbiError::InvalidInput(format!("value {}: {}", i, e)))?;
+    }
+
+    Ok(Value::encode(values))
+}
+
+/// Decodes values of the given types.
+///
+/// Fails unless the words hold exactly the values, without trailing words.
+pub fn decode(tys: &[Type], words: &[u64]) -> Result<Vec<Value>> {
+    let mut iter = Value::decode_iter(words, tys);
+    let values = iter.by_ref().collect::<Result<Vec<_>, _>>()?;
+
+    if iter.consumed() != words.len() {
+        return Err(AbiError::LengthMismatch(format!(
+            "{} trailing words after decoded values",
+            words.len() - iter.consumed()
+        )));
+    }
+
+    Ok(values)
+}
+
+/// Encodes JSON values of a type given in its canonical form, as parsed by
+/// the `FromStr` impl of [`Type`]. Values follow [`Value::from_json`], so the
+/// members of a tuple are given as an array.
+///
+/// ```
+/// let words = ola_lang_abi::encode_values("(u32,string)", &serde_json::json!([7, "ola"]));
+/// assert_eq!(words.unwrap(), vec![7, 3, 111, 108, 97]);
+/// ```
+#[cfg(feature = "json")]
+pub fn encode_values(ty: &str, values: &serde_json::Value) -> Result<Vec<u64>> {
+    let ty = ty.parse::<Type>()?;
+    let value = Value::from_json(&ty, values)?;
+
+    Ok(Value::encode(&[value]))
+}
+
+/// Decodes words holding exactly a value of a type given in its canonical
+/// form, rendering it with [`Value::to_json`].
+///
+/// ```
+/// let json = ola_lang_abi::decode_values("(u32,string)", &[7, 3, 111, 108, 97]);
+/// assert_eq!(json.unwrap(), serde_json::json!([7, "ola"]));
+/// ```
+#[cfg(feature = "json")]
+pub fn decode_values(ty: &str, words: &[u64]) -> Result<serde_json::Value> {
+    let ty = ty.parse::<Type>()?;
+    let value = decode(core::slice::from_ref(&ty), words)?.remove(0);
+
+    Ok(value.to_json())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn encode_checks_types() {
+        let tys = [
+            Type::FixedArray(Box::new(Type::U32), 2),
+            Type::Tuple(vec![("a".to_string(), Type::Bool)]),
+        ];
+
+        let fixed = Value::FixedArray(vec![Value::U32(1), Value::U32(2)], Type::U32);
+        let tuple = Value::Tuple(vec![("b".to_string(), Value::Bool(true))]);
+        assert_eq!(
+            encode(&tys, &[fixed.clone(), tuple]).unwrap(),
+            vec![1, 2, 1]
+        );
+
+        assert_eq!(
+            encode(&tys, core::slice::from_ref(&fixed))
+                .unwrap_err()
+                .to_string(),
+            "expected 2 values, got 1"
+        );
+        assert_eq!(
+            encode(&tys, &[fixed, Value::Bool(true)])
+                .unwrap_err()
+                .to_string(),
+            "value 1: expected (bool), got bool"
+        );
+        assert_eq!(
+            encode(
+                &tys[..1],
+                &[Value::FixedArray(vec![Value::U32(1)], Type::U32)]
+            )
+            .unwrap_err()
+            .to_string(),
+            "value 0: expected 2 elements of u32, got 1"
+        );
+    }
+
+    #[test]
+    fn decode_rejects_trailing_words() {
+        let tys = [Type::U32];
+
+        assert_eq!(decode(&tys, &[7]).unwrap(), vec![Value::U32(7)]);
+        assert_eq!(
+            decode(&tys, &[7, 8]).unwrap_err().to_string(),
+            "1 trailing words after decoded values"
+        );
+        assert!(matches!(decode(&tys, &[]), Err(AbiError::Truncated(_))));
+        assert!(matches!(
+            decode(&tys, &[7, 8]),
+            Err(AbiError::LengthMismatch(_))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn values_keyed_by_type_string() {
+        let json = serde_json::json!({ "1": ["a", null], "2": [] });
+        let words = encode_values("map(u32, string?[])", &json).unwrap();
+        assert_eq!(words, vec![2, 1, 2, 1, 1, 97, 0, 2, 0]);
+        assert_eq!(decode_values("map(u32,string?[])", &words).unwrap(), json);
+
+        assert_eq!(
+            encode_values("u32[", &serde_json::json!(1))
+                .unwrap_err()
+                .to_string(),
+            r#"expected "]", got end of type at offset 4 of "u32[""#
+        );
+        assert_eq!(
+            encode_values("(u32,bool)", &serde_json::json!([1, 2]))
+                .unwrap_err()
+                .to_string(),
+            r#"expected bool at "/1", got 2"#
+        );
+        assert!(decode_values("u32", &[1, 2]).is_err());
+    }
+}
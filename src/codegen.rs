@@ -0,0 +1,650 @@
+//! Rust source generation from ABIs, enabled with the `codegen` feature.
+//!
+//! Meant for build scripts, writing the generated source to `OUT_DIR` for
+//! the crate to `include!`:
+//!
+//! ```no_run
+//! // build.rs
+//! use ola_lang_abi::{codegen, Abi};
+//!
+//! let abi = Abi::load("abi/Token.json").unwrap();
+//! let out = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("token_filters.rs");
+//! std::fs::write(out, codegen::event_filters(&abi)).unwrap();
+//! ```
+//!
+//! [`contract_bindings`] generates typed call encoders and output decoders
+//! the same way.
+//!
+//! The generated code refers to this crate as `ola_lang_abi`.
+
+use std::{collections::HashMap, fmt::Write};
+
+use crate::{Abi, Event, Function, Param, Type};
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "box", "break", "const", "continue", "do", "dyn", "else", "enum",
+    "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+    "mut", "pub", "ref", "return", "static", "struct", "trait", "true", "try", "type", "unsafe",
+    "use", "where", "while", "yield",
+];
+
+/// Generates a topic filter builder per event, e.g. `TransferFilter`, with
+/// the event topic computed at generation time:
+///
+/// ```ignore
+/// let topics = TransferFilter::new().from(sender).topics();
+/// ```
+///
+/// Each indexed param gets a setter taking its value, encoded with
+/// [`Event::encode_topic`]; `topics()` returns the topics to query, `None`
+/// matching any value. Overloaded events are numbered, e.g.
+/// `Transfer2Filter`.
+pub fn event_filters(abi: &Abi) -> String {
+    let mut out = String::from("// Generated by ola-lang-abi, do not edit.\n");
+    let mut overloads = HashMap::<&str, usize>::new();
+
+    for event in &abi.events {
+        let n = overloads.entry(&event.name).or_default();
+        *n += 1;
+
+        let name = if *n == 1 {
+            format!("{}Filter", event.name)
+        } else {
+            format!("{}{}Filter", event.name, n)
+        };
+
+        out.push('\n');
+        event_filter(&mut out, &name, event);
+    }
+
+    out
+}
+
+fn event_filter(out: &mut String, name: &str, event: &Event) {
+    let indexed = event
+        .inputs
+        .iter()
+        .enumerate()
+        .filter(|(_, input)| input.indexed.unwrap_or(false))
+        .map(|(i, input)| (field_name(&input.name, i, "topic"), input))
+        .collect::<Vec<_>>();
+
+    let signature = event.signature();
+    let topic = event.topic();
+
+    writeln!(out, "/// Topic filter of the `{}` event.", signature).unwrap();
+    writeln!(out, "#[derive(Debug, Clone, Default, PartialEq, Eq)]").unwrap();
+    writeln!(out, "pub struct {} {{", name).unwrap();
+    for (field, _) in &indexed {
+        writeln!(out, "    {}: Option<ola_lang_abi::FixedArray4>,", field).unwrap();
+    }
+    writeln!(out, "}}\n").unwrap();
+
+    writeln!(out, "impl {} {{", name).unwrap();
+    writeln!(out, "    /// Signature of the event.").unwrap();
+    writeln!(out, "    pub const SIGNATURE: &str = {:?};", signature).unwrap();
+    writeln!(out, "    /// Topic of the event.").unwrap();
+    writeln!(
+        out,
+        "    pub const TOPIC: ola_lang_abi::FixedArray4 = ola_lang_abi::FixedArray4(["
+    )
+    .unwrap();
+    for word in topic.0 {
+        writeln!(out, "        {:#018x},", word).unwrap();
+    }
+    writeln!(out, "    ]);\n").unwrap();
+
+    writeln!(
+        out,
+        "    /// Creates a filter matching every `{}` log.",
+        event.name
+    )
+    .unwrap();
+    writeln!(out, "    pub fn new() -> Self {{").unwrap();
+    writeln!(out, "        Self::default()").unwrap();
+    writeln!(out, "    }}\n").unwrap();
+
+    for (field, input) in &indexed {
+        let (arg, value) = setter_value(&input.type_);
+
+        writeln!(
+            out,
+            "    /// Matches logs whose `{}` is `value`.",
+            input.name
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "    pub fn {}(mut self, value: {}) -> Self {{",
+            field, arg
+        )
+        .unwrap();
+        writeln!(out, "        let value = {};", value).unwrap();
+        writeln!(
+            out,
+            "        self.{} = Some(ola_lang_abi::Event::encode_topic(&value));",
+            field
+        )
+        .unwrap();
+        writeln!(out, "        self").unwrap();
+        writeln!(out, "    }}\n").unwrap();
+    }
+
+    let mut topics = indexed
+        .iter()
+        .map(|(field, _)| format!("self.{}", field))
+        .collect::<Vec<_>>();
+    if !event.anonymous {
+        topics.insert(0, "Some(Self::TOPIC)".to_string());
+    }
+
+    writeln!(
+        out,
+        "    /// Returns the topics to query, `None` matching any value."
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "    pub fn topics(&self) -> Vec<Option<ola_lang_abi::FixedArray4>> {{"
+    )
+    .unwrap();
+    writeln!(out, "        vec![{}]", topics.join(", ")).unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out, "}}").unwrap();
+}
+
+/// Generates a unit struct named `name` with typed bindings of the ABI
+/// functions:
+///
+/// ```ignore
+/// let calldata = BookExampleContract::create_book(60, "ola".to_string());
+/// let output = BookExampleContract::decode_create_book_output(&output)?;
+/// ```
+///
+/// Each function gets an associated function taking its inputs and
+/// returning the calldata, `[params..., param-len, method_id]`, and a
+/// `decode_*_output` function returning a `*Output` struct with a field per
+/// output, or per member of a single unnamed tuple output. Integers, bools, strings, `fields`, addresses, hashes and `u256`
+/// map to Rust types; arrays and tuples are passed as
+/// `ola_lang_abi::Value`, encoded as given. Overloaded functions are
+/// numbered, e.g. `transfer2`. Function and param docs of the ABI are
+/// kept as doc comments of the functions and output fields.
+pub fn contract_bindings(abi: &Abi, name: &str) -> String {
+    let mut out = String::from("// Generated by ola-lang-abi, do not edit.\n\n");
+    let mut overloads = HashMap::<&str, usize>::new();
+    let mut outputs = String::new();
+
+    writeln!(
+        out,
+        "/// Typed bindings of the `{}` contract functions.",
+        name
+    )
+    .unwrap();
+    writeln!(out, "#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]").unwrap();
+    writeln!(out, "pub struct {};\n", name).unwrap();
+    writeln!(out, "impl {} {{", name).unwrap();
+
+    for (i, function) in abi.functions.iter().enumerate() {
+        let n = overloads.entry(&function.name).or_default();
+        *n += 1;
+
+        let mut method = snake_case(&function.name);
+        let mut output = pascal_case(&function.name);
+        if *n > 1 {
+            write!(method, "{}", n).unwrap();
+            write!(output, "{}", n).unwrap();
+        }
+        output.push_str("Output");
+
+        if i > 0 {
+            out.push('\n');
+        }
+        function_binding(&mut out, &mut outputs, &method, &output, function);
+    }
+
+    writeln!(out, "}}").unwrap();
+    out.push_str(&outputs);
+
+    out
+}
+
+fn function_binding(
+    out: &mut String,
+    outputs: &mut String,
+    method: &str,
+    output: &str,
+    function: &Function,
+) {
+    let signature = function.signature();
+    let inputs = function
+        .inputs
+        .iter()
+        .enumerate()
+        .map(|(i, input)| (field_name(&input.name, i, "arg"), input))
+        .collect::<Vec<_>>();
+
+    writeln!(out, "    /// Encodes a call to `{}`.", signature).unwrap();
+    if let Some(docs) = &function.docs {
+        writeln!(out, "    ///").unwrap();
+        write_docs(out, "    ", docs);
+    }
+    if inputs.iter().any(|(_, input)| input.docs.is_some()) {
+        writeln!(out, "    ///").unwrap();
+        for (arg, input) in &inputs {
+            if let Some(docs) = &input.docs {
+                let docs = docs.lines().map(str::trim).collect::<Vec<_>>().join(" ");
+                writeln!(out, "    /// - `{}`: {}", arg, docs).unwrap();
+            }
+        }
+    }
+    writeln!(
+        out,
+        "    pub fn {}({}) -> Vec<u64> {{",
+        raw_ident(method),
+        inputs
+            .iter()
+            .map(|(arg, input)| format!("{}: {}", arg, rust_type(&input.type_)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "        let mut calldata = ola_lang_abi::Value::encode(&["
+    )
+    .unwrap();
+    for (arg, input) in &inputs {
+        writeln!(out, "            {},", to_value(&input.type_, arg)).unwrap();
+    }
+    writeln!(out, "        ]);").unwrap();
+    writeln!(out, "        calldata.push(calldata.len() as u64);").unwrap();
+    writeln!(
+        out,
+        "        calldata.push({:#010x});",
+        function.method_id()
+    )
+    .unwrap();
+    writeln!(out, "        calldata").unwrap();
+    writeln!(out, "    }}").unwrap();
+
+    if function.outputs.is_empty() {
+        return;
+    }
+
+    // a single unnamed tuple, as returned by struct returning functions, is
+    // flattened into the output struct
+    let members = match &function.outputs[..] {
+        [Param {
+            name,
+            type_: Type::Tuple(members),
+            ..
+        }] if name.is_empty() => Some(members),
+        _ => None,
+    };
+    let fields = match members {
+        Some(members) => members
+            .iter()
+            .enumerate()
+            .map(|(i, (name, ty))| (field_name(name, i, "output"), ty, None))
+            .collect::<Vec<_>>(),
+        None => function
+            .outputs
+            .iter()
+            .enumerate()
+            .map(|(i, output)| {
+                (
+                    field_name(&output.name, i, "output"),
+                    &output.type_,
+                    output.docs.as_deref(),
+                )
+            })
+            .collect(),
+    };
+
+    writeln!(out, "\n    /// Decodes the output of `{}`.", signature).unwrap();
+    writeln!(
+        out,
+        "    pub fn decode_{}_output(output: &[u64]) -> Result<{}, ola_lang_abi::AbiError> {{",
+        method, output
+    )
+    .unwrap();
+    writeln!(out, "        // output = [param1, param2, .. , param-len]").unwrap();
+    writeln!(
+        out,
+        "        let (_, params) = output.split_last().ok_or_else(|| {{"
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "            ola_lang_abi::AbiError::Truncated(\"missing output param-len\".to_string())"
+    )
+    .unwrap();
+    writeln!(out, "        }})?;").unwrap();
+    writeln!(out, "        let tys = [").unwrap();
+    for param in &function.outputs {
+        writeln!(out, "            {},", type_expr(&param.type_)).unwrap();
+    }
+    writeln!(out, "        ];").unwrap();
+    writeln!(
+        out,
+        "        let mut values = ola_lang_abi::codec::decode(&tys, params)?.into_iter();"
+    )
+    .unwrap();
+    if members.is_some() {
+        writeln!(
+            out,
+            "        let mut values = match values.next().unwrap() {{\n\
+             \x20           ola_lang_abi::Value::Tuple(members) => members.into_iter().map(|(_, value)| value),\n\
+             \x20           _ => unreachable!(\"decoded as a tuple\"),\n\
+             \x20       }};"
+        )
+        .unwrap();
+    }
+    writeln!(out, "\n        Ok({} {{", output).unwrap();
+    for (field, ty, _) in &fields {
+        writeln!(out, "            {}: {},", field, from_value(ty)).unwrap();
+    }
+    writeln!(out, "        }})").unwrap();
+    writeln!(out, "    }}").unwrap();
+
+    writeln!(outputs, "\n/// Output of `{}`.", signature).unwrap();
+    writeln!(outputs, "#[derive(Debug, Clone, PartialEq, Eq)]").unwrap();
+    writeln!(outputs, "pub struct {} {{", output).unwrap();
+    for (field, ty, docs) in &fields {
+        if let Some(docs) = docs {
+            write_docs(outputs, "    ", docs);
+        }
+        writeln!(outputs, "    pub {}: {},", field, rust_type(ty)).unwrap();
+    }
+    writeln!(outputs, "}}").unwrap();
+}
+
+/// Writes ABI docs as `///` lines, indented by `indent`.
+fn write_docs(out: &mut String, indent: &str, docs: &str) {
+    for line in docs.lines().map(str::trim_end) {
+        if line.is_empty() {
+            writeln!(out, "{}///", indent).unwrap();
+        } else {
+            writeln!(out, "{}/// {}", indent, line).unwrap();
+        }
+    }
+}
+
+/// Rust type of a binding argument or output field.
+fn rust_type(ty: &Type) -> &'static str {
+    match ty {
+        Type::U32 => "u32",
+        Type::Field => "u64",
+        Type::I32 => "i32",
+        Type::I64 => "i64",
+        Type::Bool => "bool",
+        Type::Address | Type::Hash => "ola_lang_abi::FixedArray4",
+        Type::U256 => "ola_lang_abi::FixedArray8",
+        Type::String => "String",
+        Type::Fields => "Vec<u64>",
+        Type::FixedArray(..)
+        | Type::Array(_)
+        | Type::Tuple(_)
+        | Type::Option(_)
+        | Type::Map(..) => "ola_lang_abi::Value",
+    }
+}
+
+/// Expression building the `ola_lang_abi::Value` of the argument `arg`.
+fn to_value(ty: &Type, arg: &str) -> String {
+    let variant = match ty {
+        Type::U32 => return format!("ola_lang_abi::Value::U32({}.into())", arg),
+        Type::FixedArray(..)
+        | Type::Array(_)
+        | Type::Tuple(_)
+        | Type::Option(_)
+        | Type::Map(..) => return arg.to_string(),
+        Type::Field => "Field",
+        Type::I32 => "I32",
+        Type::I64 => "I64",
+        Type::Bool => "Bool",
+        Type::Address => "Address",
+        Type::Hash => "Hash",
+        Type::U256 => "U256",
+        Type::String => "String",
+        Type::Fields => "Fields",
+    };
+
+    format!("ola_lang_abi::Value::{}({})", variant, arg)
+}
+
+/// Expression taking the next decoded value of `values` as an output field.
+fn from_value(ty: &Type) -> &'static str {
+    match ty {
+        Type::Fields => {
+            "match values.next().unwrap() { ola_lang_abi::Value::Fields(fields) => fields, \
+             _ => unreachable!(\"decoded as fields\") }"
+        }
+        Type::FixedArray(..)
+        | Type::Array(_)
+        | Type::Tuple(_)
+        | Type::Option(_)
+        | Type::Map(..) => "values.next().unwrap()",
+        _ => "ola_lang_abi::FromValue::from_value(&values.next().unwrap())?",
+    }
+}
+
+/// Expression building the `ola_lang_abi::Type`.
+fn type_expr(ty: &Type) -> String {
+    let variant = match ty {
+        Type::U32 => "U32",
+        Type::Field => "Field",
+        Type::I32 => "I32",
+        Type::I64 => "I64",
+        Type::Bool => "Bool",
+        Type::Address => "Address",
+        Type::Hash => "Hash",
+        Type::U256 => "U256",
+        Type::String => "String",
+        Type::Fields => "Fields",
+        Type::FixedArray(elem, size) => {
+            return format!(
+                "ola_lang_abi::Type::FixedArray(Box::new({}), {})",
+                type_expr(elem),
+                size
+            )
+        }
+        Type::Array(elem) => {
+            return format!("ola_lang_abi::Type::Array(Box::new({}))", type_expr(elem))
+        }
+        Type::Option(inner) => {
+            return format!("ola_lang_abi::Type::Option(Box::new({}))", type_expr(inner))
+        }
+        Type::Map(key, value) => {
+            return format!(
+                "ola_lang_abi::Type::Map(Box::new({}), Box::new({}))",
+                type_expr(key),
+                type_expr(value)
+            )
+        }
+        Type::Tuple(members) => {
+            return format!(
+                "ola_lang_abi::Type::Tuple(vec![{}])",
+                members
+                    .iter()
+                    .map(|(name, ty)| format!("({:?}.to_string(), {})", name, type_expr(ty)))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        }
+    };
+
+    format!("ola_lang_abi::Type::{}", variant)
+}
+
+/// Setter argument type and expression building the `ola_lang_abi::Value`.
+fn setter_value(ty: &Type) -> (&'static str, &'static str) {
+    match ty {
+        Type::U32 => ("u32", "ola_lang_abi::Value::U32(value.into())"),
+        Type::Field => ("u64", "ola_lang_abi::Value::Field(value)"),
+        Type::I32 => ("i32", "ola_lang_abi::Value::I32(value)"),
+        Type::I64 => ("i64", "ola_lang_abi::Value::I64(value)"),
+        Type::Bool => ("bool", "ola_lang_abi::Value::Bool(value)"),
+        Type::Address => (
+            "ola_lang_abi::FixedArray4",
+            "ola_lang_abi::Value::Address(value)",
+        ),
+        Type::Hash => (
+            "ola_lang_abi::FixedArray4",
+            "ola_lang_abi::Value::Hash(value)",
+        ),
+        Type::U256 => (
+            "ola_lang_abi::FixedArray8",
+            "ola_lang_abi::Value::U256(value)",
+        ),
+        Type::String => ("&str", "ola_lang_abi::Value::String(value.to_string())"),
+        Type::Fields => ("&[u64]", "ola_lang_abi::Value::Fields(value.to_vec())"),
+        Type::FixedArray(..)
+        | Type::Array(_)
+        | Type::Tuple(_)
+        | Type::Option(_)
+        | Type::Map(..) => ("ola_lang_abi::Value", "value"),
+    }
+}
+
+/// Snake case field name of the `i`th param, `{unnamed}{i}` if unnamed.
+fn field_name(name: &str, i: usize, unnamed: &str) -> String {
+    if name.is_empty() {
+        return format!("{}{}", unnamed, i);
+    }
+
+    raw_ident(&snake_case(name))
+}
+
+/// Escapes keywords, with `r#` or a trailing `_` for those that can't be raw
+/// identifiers.
+fn raw_ident(name: &str) -> String {
+    match name {
+        // keywords that can't be raw identifiers
+        "crate" | "self" | "super" => format!("{}_", name),
+        _ if KEYWORDS.contains(&name) => format!("r#{}", name),
+        _ => name.to_string(),
+    }
+}
+
+/// Converts a camel case name to snake case.
+fn snake_case(camel: &str) -> String {
+    let mut name = String::new();
+    for (j, c) in camel.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if j > 0 && !name.ends_with('_') {
+                name.push('_');
+            }
+            name.push(c.to_ascii_lowercase());
+        } else {
+            name.push(c);
+        }
+    }
+    name
+}
+
+/// Converts a camel case name to Pascal case.
+fn pascal_case(camel: &str) -> String {
+    let mut chars = camel.chars();
+    chars
+        .next()
+        .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn event_filter_names() {
+        let abi: Abi = serde_json::from_value(serde_json::json!([
+            {
+                "type": "event",
+                "name": "Moved",
+                "inputs": [
+                    { "name": "tokenId", "type": "u32", "indexed": true },
+                    { "name": "type", "type": "u32", "indexed": true },
+                    { "name": "", "type": "bool", "indexed": true },
+                    { "name": "note", "type": "string" }
+                ],
+                "anonymous": true
+            },
+            { "type": "event", "name": "Moved", "inputs": [], "anonymous": false }
+        ]))
+        .unwrap();
+
+        let source = event_filters(&abi);
+
+        assert!(source.contains("pub struct MovedFilter {"));
+        assert!(source.contains("pub struct Moved2Filter {"));
+        assert!(source.contains("pub fn token_id(mut self, value: u32) -> Self {"));
+        assert!(source.contains("pub fn r#type(mut self, value: u32) -> Self {"));
+        assert!(source.contains("pub fn topic2(mut self, value: bool) -> Self {"));
+        assert!(!source.contains("fn note("));
+        assert!(source.contains("vec![self.token_id, self.r#type, self.topic2]"));
+        assert!(source.contains("vec![Some(Self::TOPIC)]"));
+    }
+
+    #[test]
+    fn contract_binding_names() {
+        let abi: Abi = serde_json::from_value(serde_json::json!([
+            {
+                "type": "function",
+                "name": "move",
+                "inputs": [
+                    { "name": "tokenId", "type": "u32" },
+                    { "name": "", "type": "fields" }
+                ],
+                "outputs": [{ "name": "ok", "type": "bool" }, { "name": "", "type": "u32[]" }]
+            },
+            { "type": "function", "name": "move", "inputs": [], "outputs": [] }
+        ]))
+        .unwrap();
+
+        let source = contract_bindings(&abi, "Mover");
+
+        assert!(source.contains("pub struct Mover;"));
+        assert!(source.contains("pub fn r#move(token_id: u32, arg1: Vec<u64>) -> Vec<u64> {"));
+        assert!(source.contains("pub fn move2() -> Vec<u64> {"));
+        assert!(source.contains(
+            "pub fn decode_move_output(output: &[u64]) -> Result<MoveOutput, ola_lang_abi::AbiError> {"
+        ));
+        assert!(source.contains("    pub ok: bool,\n    pub output1: ola_lang_abi::Value,\n"));
+        assert!(!source.contains("Move2Output"));
+    }
+
+    #[test]
+    fn contract_binding_docs() {
+        let abi: Abi = serde_json::from_value(serde_json::json!([{
+            "type": "function",
+            "name": "withdraw",
+            "notice": "Withdraws from the vault.\n\nFails when paused.",
+            "inputs": [
+                { "name": "amount", "type": "u32", "doc": "Amount in gwei." },
+                { "name": "to", "type": "address" }
+            ],
+            "outputs": [
+                { "name": "left", "type": "u32", "doc": "Remaining balance." },
+                { "name": "ok", "type": "bool" }
+            ]
+        }]))
+        .unwrap();
+
+        let source = contract_bindings(&abi, "Vault");
+
+        assert!(source.contains(
+            "    /// Encodes a call to `withdraw(u32,address)`.\n\
+             \x20   ///\n\
+             \x20   /// Withdraws from the vault.\n\
+             \x20   ///\n\
+             \x20   /// Fails when paused.\n\
+             \x20   ///\n\
+             \x20   /// - `amount`: Amount in gwei.\n\
+             \x20   pub fn withdraw("
+        ));
+        assert!(
+            source.contains("    /// Remaining balance.\n    pub left: u32,\n    pub ok: bool,\n")
+        );
+    }
+}
@@ -0,0 +1,611 @@
+use crate::alloc_prelude::*;
+use alloc::collections::BTreeMap;
+use anyhow::{anyhow, Result};
+
+use crate::{FixedArray4, FixedArray8, Type, Value};
+
+/// Conversion of plain Rust values into ABI values.
+///
+/// `u32` maps to [`Value::U32`], `u128` to [`Value::U256`], signed integers
+/// to [`Value::I32`] and [`Value::I64`], strings to [`Value::String`],
+/// [`FixedArray4`] to [`Value::Address`], `Vec`s and arrays to
+/// [`Value::Array`] and [`Value::FixedArray`], and `BTreeMap`s to
+/// [`Value::Map`]. `u64`, which may not fit in a `u32`, converts with
+/// `Value::try_from` instead.
+pub trait ToValue {
+    /// Converts `self` into an ABI value.
+    fn to_value(&self) -> Value;
+}
+
+impl ToValue for Value {
+    fn to_value(&self) -> Value {
+        self.clone()
+    }
+}
+
+impl ToValue for u32 {
+    fn to_value(&self) -> Value {
+        Value::U32(*self as u64)
+    }
+}
+
+impl ToValue for u128 {
+    fn to_value(&self) -> Value {
+        Value::U256(FixedArray8::from(*self))
+    }
+}
+
+impl ToValue for i32 {
+    fn to_value(&self) -> Value {
+        Value::I32(*self)
+    }
+}
+
+impl ToValue for i64 {
+    fn to_value(&self) -> Value {
+        Value::I64(*self)
+    }
+}
+
+impl ToValue for bool {
+    fn to_value(&self) -> Value {
+        Value::Bool(*self)
+    }
+}
+
+impl ToValue for str {
+    fn to_value(&self) -> Value {
+        Value::String(self.to_string())
+    }
+}
+
+impl ToValue for String {
+    fn to_value(&self) -> Value {
+        Value::String(self.clone())
+    }
+}
+
+impl ToValue for FixedArray4 {
+    fn to_value(&self) -> Value {
+        Value::Address(*self)
+    }
+}
+
+impl ToValue for FixedArray8 {
+    fn to_value(&self) -> Value {
+        Value::U256(*self)
+    }
+}
+
+/// `u64` has no [`ToValue`] impl, it may not fit in [`Value::U32`]: it
+/// converts only when it does.
+///
+/// ```
+/// use ola_lang_abi::Value;
+///
+/// assert_eq!(Value::try_from(60u64).unwrap(), Value::U32(60));
+/// assert!(Value::try_from(u64::MAX).is_err());
+/// ```
+impl TryFrom<u64> for Value {
+    type Error = anyhow::Error;
+
+    fn try_from(n: u64) -> Result<Self> {
+        u32::try_from(n)
+            .map(|n| n.to_value())
+            .map_err(|_| anyhow!("{} doesn't fit in u32", n))
+    }
+}
+
+impl<T: ToValue + ?Sized> ToValue for &T {
+    fn to_value(&self) -> Value {
+        (**self).to_value()
+    }
+}
+
+impl<T: ToValue + AbiType> ToValue for Vec<T> {
+    fn to_value(&self) -> Value {
+        Value::Array(self.iter().map(T::to_value).collect(), T::abi_type())
+    }
+}
+
+impl<T: ToValue + AbiType, const N: usize> ToValue for [T; N] {
+    fn to_value(&self) -> Value {
+        Value::FixedArray(self.iter().map(T::to_value).collect(), T::abi_type())
+    }
+}
+
+impl<T: ToValue + AbiType> ToValue for Option<T> {
+    fn to_value(&self) -> Value {
+        match self {
+            Some(value) => Value::Some(Box::new(value.to_value())),
+            None => Value::None(T::abi_type()),
+        }
+    }
+}
+
+impl<K: ToValue + AbiType, V: ToValue + AbiType> ToValue for BTreeMap<K, V> {
+    fn to_value(&self) -> Value {
+        Value::Map(
+            self.iter()
+                .map(|(key, value)| (key.to_value(), value.to_value()))
+                .collect(),
+            K::abi_type(),
+            V::abi_type(),
+        )
+    }
+}
+
+/// Conversion of plain Rust values into the ABI values of a list of params,
+/// e.g. the inputs of a function call.
+///
+/// Tuples convert member by member with [`ToValue`], `()` to no values, and
+/// slices, arrays and `Vec`s of [`Value`]s as they are.
+///
+/// ```
+/// use ola_lang_abi::{ToValues, Value};
+///
+/// assert_eq!(
+///     (60u32, "olavm").to_values(),
+///     [Value::U32(60), Value::String("olavm".to_string())]
+/// );
+/// ```
+pub trait ToValues {
+    /// Converts `self` into ABI values.
+    fn to_values(&self) -> Vec<Value>;
+}
+
+impl ToValues for () {
+    fn to_values(&self) -> Vec<Value> {
+        vec![]
+    }
+}
+
+impl ToValues for [Value] {
+    fn to_values(&self) -> Vec<Value> {
+        self.to_vec()
+    }
+}
+
+impl<const N: usize> ToValues for [Value; N] {
+    fn to_values(&self) -> Vec<Value> {
+        self.to_vec()
+    }
+}
+
+impl ToValues for Vec<Value> {
+    fn to_values(&self) -> Vec<Value> {
+        self.clone()
+    }
+}
+
+impl<T: ToValues + ?Sized> ToValues for &T {
+    fn to_values(&self) -> Vec<Value> {
+        (**self).to_values()
+    }
+}
+
+/// ABI type of the values a Rust type converts to with [`ToValue`].
+///
+/// Implemented along with [`ToValue`] by `#[derive(AbiEncode)]` (with the
+/// `derive` feature), which maps structs to tuples of their fields:
+///
+/// ```ignore
+/// use ola_lang_abi::{AbiDecode, AbiEncode, AbiType, FromValue, ToValue, Type};
+///
+/// #[derive(AbiEncode, AbiDecode)]
+/// struct Book {
+///     id: u32,
+///     #[abi(rename = "bookName")]
+///     name: String,
+/// }
+///
+/// let book = Book { id: 60, name: "olavm".to_string() };
+/// assert_eq!(Book::abi_type().to_string(), "(u32,string)");
+/// assert_eq!(Book::from_value(&book.to_value()).unwrap().id, 60);
+/// ```
+pub trait AbiType {
+    /// Returns the ABI type.
+    fn abi_type() -> Type;
+}
+
+macro_rules! impl_abi_type {
+    ($($ty:ty => $abi:expr),+ $(,)?) => {
+        $(impl AbiType for $ty {
+            fn abi_type() -> Type {
+                $abi
+            }
+        })+
+    };
+}
+
+impl_abi_type!(
+    u32 => Type::U32,
+    u128 => Type::U256,
+    i32 => Type::I32,
+    i64 => Type::I64,
+    bool => Type::Bool,
+    str => Type::String,
+    String => Type::String,
+    FixedArray4 => Type::Address,
+    FixedArray8 => Type::U256,
+);
+
+impl<T: AbiType + ?Sized> AbiType for &T {
+    fn abi_type() -> Type {
+        T::abi_type()
+    }
+}
+
+impl<T: AbiType> AbiType for Vec<T> {
+    fn abi_type() -> Type {
+        Type::Array(Box::new(T::abi_type()))
+    }
+}
+
+impl<T: AbiType, const N: usize> AbiType for [T; N] {
+    fn abi_type() -> Type {
+        Type::FixedArray(Box::new(T::abi_type()), N as u64)
+    }
+}
+
+impl<T: AbiType> AbiType for Option<T> {
+    fn abi_type() -> Type {
+        Type::Option(Box::new(T::abi_type()))
+    }
+}
+
+impl<K: AbiType, V: AbiType> AbiType for BTreeMap<K, V> {
+    fn abi_type() -> Type {
+        Type::Map(Box::new(K::abi_type()), Box::new(V::abi_type()))
+    }
+}
+
+/// Conversion of ABI values back into plain Rust values.
+///
+/// The inverse of [`ToValue`]: [`Value::U32`] and [`Value::Field`] convert to
+/// unsigned integers, [`Value::I32`] and [`Value::I64`] to signed ones, [`Value::Address`] and [`Value::Hash`] to [`FixedArray4`], arrays
+/// to `Vec`s and tuples, whatever their member names, to Rust tuples.
+pub trait FromValue: Sized {
+    /// Converts an ABI value into `Self`.
+    fn from_value(value: &Value) -> Result<Self>;
+}
+
+fn mismatch<T>(expected: &str, value: &Value) -> Result<T> {
+    Err(anyhow!("expected {}, got {}", expected, value.type_of()))
+}
+
+impl FromValue for Value {
+    fn from_value(value: &Value) -> Result<Self> {
+        Ok(value.clone())
+    }
+}
+
+impl FromValue for u32 {
+    fn from_value(value: &Value) -> Result<Self> {
+        match value {
+            Value::U32(n) | Value::Field(n) => {
+                u32::try_from(*n).map_err(|_| anyhow!("{} doesn't fit in u32", n))
+            }
+            _ => mismatch("u32", value),
+        }
+    }
+}
+
+impl FromValue for u64 {
+    fn from_value(value: &Value) -> Result<Self> {
+        match value {
+            Value::U32(n) | Value::Field(n) => Ok(*n),
+            _ => mismatch("u32 or field", value),
+        }
+    }
+}
+
+impl FromValue for u128 {
+    fn from_value(value: &Value) -> Result<Self> {
+        match value {
+            Value::U32(n) | Value::Field(n) => Ok((*n).into()),
+            Value::U256(n) => n
+                .to_u128()
+                .ok_or_else(|| anyhow!("{} doesn't fit in u128", n)),
+            _ => mismatch("u32, field or u256", value),
+        }
+    }
+}
+
+impl FromValue for i32 {
+    fn from_value(value: &Value) -> Result<Self> {
+        match value {
+            Value::I32(n) => Ok(*n),
+            Value::I64(n) => i32::try_from(*n).map_err(|_| anyhow!("{} doesn't fit in i32", n)),
+            _ => mismatch("i32", value),
+        }
+    }
+}
+
+impl FromValue for i64 {
+    fn from_value(value: &Value) -> Result<Self> {
+        match value {
+            Value::I32(n) => Ok((*n).into()),
+            Value::I64(n) => Ok(*n),
+            _ => mismatch("i32 or i64", value),
+        }
+    }
+}
+
+impl FromValue for bool {
+    fn from_value(value: &Value) -> Result<Self> {
+        match value {
+            Value::Bool(b) => Ok(*b),
+            _ => mismatch("bool", value),
+        }
+    }
+}
+
+impl FromValue for String {
+    fn from_value(value: &Value) -> Result<Self> {
+        match value {
+            Value::String(s) => Ok(s.clone()),
+            _ => mismatch("string", value),
+        }
+    }
+}
+
+impl FromValue for FixedArray4 {
+    fn from_value(value: &Value) -> Result<Self> {
+        match value {
+            Value::Address(v) | Value::Hash(v) => Ok(*v),
+            _ => mismatch("address or hash", value),
+        }
+    }
+}
+
+impl FromValue for FixedArray8 {
+    fn from_value(value: &Value) -> Result<Self> {
+        match value {
+            Value::U256(v) => Ok(*v),
+            _ => mismatch("u256", value),
+        }
+    }
+}
+
+impl<T: FromValue, const N: usize> FromValue for [T; N] {
+    fn from_value(value: &Value) -> Result<Self> {
+        match value {
+            Value::Array(values, _) | Value::FixedArray(values, _) if values.len() == N => values
+                .iter()
+                .map(T::from_value)
+                .collect::<Result<Vec<_>>>()
+                .map(|values| values.try_into().ok().expect("length checked")),
+            _ => mismatch(&format!("array of {} elements", N), value),
+        }
+    }
+}
+
+impl<T: FromValue> FromValue for Option<T> {
+    fn from_value(value: &Value) -> Result<Self> {
+        match value {
+            Value::None(_) => Ok(None),
+            Value::Some(value) => T::from_value(value).map(Some),
+            _ => mismatch("optional value", value),
+        }
+    }
+}
+
+impl<K: FromValue + Ord, V: FromValue> FromValue for BTreeMap<K, V> {
+    fn from_value(value: &Value) -> Result<Self> {
+        match value {
+            Value::Map(entries, ..) => entries
+                .iter()
+                .map(|(key, value)| Ok((K::from_value(key)?, V::from_value(value)?)))
+                .collect(),
+            _ => mismatch("map", value),
+        }
+    }
+}
+
+/// Members of a tuple value decoded into the struct `name`, used by
+/// `#[derive(AbiDecode)]`.
+#[doc(hidden)]
+pub fn struct_members<'a>(
+    value: &'a Value,
+    name: &str,
+    len: usize,
+) -> Result<&'a [(String, Value)]> {
+    match value {
+        Value::Tuple(members) if members.len() == len => Ok(members),
+        _ => mismatch(&format!("{} tuple of {} members", name, len), value),
+    }
+}
+
+impl<T: FromValue> FromValue for Vec<T> {
+    fn from_value(value: &Value) -> Result<Self> {
+        match value {
+            Value::Array(values, _) | Value::FixedArray(values, _) => {
+                values.iter().map(T::from_value).collect()
+            }
+            _ => mismatch("array", value),
+        }
+    }
+}
+
+/// Implements [`ToValue`], [`AbiType`] and `From` for tuples, naming each
+/// member by its position ("0", "1", ...), [`FromValue`] for tuples of the
+/// same length, and [`ToValues`] converting each member.
+macro_rules! impl_tuple_to_value {
+    ($($name:ident: $idx:tt),+) => {
+        impl<$($name: ToValue),+> ToValue for ($($name,)+) {
+            fn to_value(&self) -> Value {
+                Value::Tuple(vec![$(($idx.to_string(), self.$idx.to_value())),+])
+            }
+        }
+
+        impl<$($name: ToValue),+> From<($($name,)+)> for Value {
+            fn from(tuple: ($($name,)+)) -> Self {
+                tuple.to_value()
+            }
+        }
+
+        impl<$($name: ToValue),+> ToValues for ($($name,)+) {
+            fn to_values(&self) -> Vec<Value> {
+                vec![$(self.$idx.to_value()),+]
+            }
+        }
+
+        impl<$($name: AbiType),+> AbiType for ($($name,)+) {
+            fn abi_type() -> Type {
+                Type::Tuple(vec![$(($idx.to_string(), $name::abi_type())),+])
+            }
+        }
+
+        impl<$($name: FromValue),+> FromValue for ($($name,)+) {
+            fn from_value(value: &Value) -> Result<Self> {
+                const LEN: usize = [$($idx),+].len();
+
+                match value {
+                    Value::Tuple(members) if members.len() == LEN => {
+                        Ok(($($name::from_value(&members[$idx].1)?,)+))
+                    }
+                    _ => mismatch(&format!("tuple of {} members", LEN), value),
+                }
+            }
+        }
+    };
+}
+
+impl_tuple_to_value!(A: 0);
+impl_tuple_to_value!(A: 0, B: 1);
+impl_tuple_to_value!(A: 0, B: 1, C: 2);
+impl_tuple_to_value!(A: 0, B: 1, C: 2, D: 3);
+impl_tuple_to_value!(A: 0, B: 1, C: 2, D: 3, E: 4);
+impl_tuple_to_value!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5);
+impl_tuple_to_value!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6);
+impl_tuple_to_value!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7);
+impl_tuple_to_value!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7, I: 8);
+impl_tuple_to_value!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7, I: 8, J: 9);
+impl_tuple_to_value!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7, I: 8, J: 9, K: 10);
+impl_tuple_to_value!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7, I: 8, J: 9, K: 10, L: 11);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn tuple_into_value() {
+        let value: Value = (60u32, "olavm").into();
+
+        assert_eq!(
+            value,
+            Value::Tuple(vec![
+                ("0".to_string(), Value::U32(60)),
+                ("1".to_string(), Value::String("olavm".to_string())),
+            ])
+        );
+        assert_eq!(Value::encode(&[value]), vec![60, 5, 111, 108, 97, 118, 109]);
+    }
+
+    #[test]
+    fn nested_tuple_into_value() {
+        let addr = FixedArray4([1, 2, 3, 4]);
+        let value = Value::from((true, (7u32, addr)));
+
+        assert_eq!(
+            value,
+            Value::Tuple(vec![
+                ("0".to_string(), Value::Bool(true)),
+                (
+                    "1".to_string(),
+                    Value::Tuple(vec![
+                        ("0".to_string(), Value::U32(7)),
+                        ("1".to_string(), Value::Address(addr)),
+                    ])
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn twelve_element_tuple_into_value() {
+        let value = Value::from((
+            0u32, 1u32, 2u32, 3u32, 4u32, 5u32, 6u32, 7u32, 8u32, 9u32, 10u32, 11u32,
+        ));
+
+        assert_eq!(Value::encode(&[value]), (0..12).collect::<Vec<u64>>());
+    }
+
+    #[test]
+    fn value_into_rust() {
+        let addr = FixedArray4([1, 2, 3, 4]);
+        let value = Value::from((60u32, ("olavm", addr)));
+
+        let (id, (title, owner)) = <(u32, (String, FixedArray4))>::from_value(&value).unwrap();
+        assert_eq!((id, title.as_str(), owner), (60, "olavm", addr));
+
+        let ids = Value::Array(vec![Value::U32(1), Value::U32(2)], crate::Type::U32);
+        assert_eq!(Vec::<u64>::from_value(&ids).unwrap(), vec![1, 2]);
+
+        assert_eq!(
+            bool::from_value(&Value::U32(1)).unwrap_err().to_string(),
+            "expected bool, got u32"
+        );
+        assert!(u32::from_value(&Value::U32(u64::MAX)).is_err());
+        assert!(<(u32,)>::from_value(&value).is_err());
+
+        let big = u128::MAX.to_value();
+        assert_eq!(u128::from_value(&big).unwrap(), u128::MAX);
+        assert_eq!(i64::from_value(&(-5i32).to_value()).unwrap(), -5);
+    }
+
+    #[test]
+    fn collection_values_and_types() {
+        let ids = vec![1u32, 2];
+        assert_eq!(
+            ids.to_value(),
+            Value::Array(vec![Value::U32(1), Value::U32(2)], Type::U32)
+        );
+        assert_eq!(<Vec<u32>>::abi_type(), Type::Array(Box::new(Type::U32)));
+
+        let pair = [true, false];
+        assert_eq!(<[bool; 2]>::from_value(&pair.to_value()).unwrap(), pair);
+        assert!(<[bool; 3]>::from_value(&pair.to_value()).is_err());
+
+        assert_eq!(
+            <(u32, Vec<String>, [i64; 3])>::abi_type().to_string(),
+            "(u32,string[],i64[3])"
+        );
+
+        let fee = Some(5u32);
+        assert_eq!(fee.to_value(), Value::Some(Box::new(Value::U32(5))));
+        assert_eq!(None::<u32>.to_value(), Value::None(Type::U32));
+        assert_eq!(<Option<u32>>::from_value(&fee.to_value()).unwrap(), fee);
+        assert_eq!(
+            <Option<u32>>::from_value(&None::<u32>.to_value()).unwrap(),
+            None
+        );
+        assert!(<Option<u32>>::from_value(&Value::U32(5)).is_err());
+        assert_eq!(<Option<Vec<u32>>>::abi_type().to_string(), "u32[]?");
+
+        let labels = BTreeMap::from([(2u32, "two".to_string()), (1, "one".to_string())]);
+        assert_eq!(
+            labels.to_value(),
+            Value::Map(
+                vec![
+                    (Value::U32(1), Value::String("one".to_string())),
+                    (Value::U32(2), Value::String("two".to_string())),
+                ],
+                Type::U32,
+                Type::String
+            )
+        );
+        assert_eq!(
+            <BTreeMap<u32, String>>::from_value(&labels.to_value()).unwrap(),
+            labels
+        );
+        assert!(<BTreeMap<u32, String>>::from_value(&Value::U32(5)).is_err());
+        assert_eq!(
+            <BTreeMap<u32, Vec<bool>>>::abi_type().to_string(),
+            "map(u32,bool[])"
+        );
+    }
+}
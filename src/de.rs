@@ -0,0 +1,301 @@
+use serde::{
+    de::{
+        value::{MapDeserializer, SeqDeserializer},
+        DeserializeOwned, IntoDeserializer, Visitor,
+    },
+    forward_to_deserialize_any, Deserializer,
+};
+
+use crate::{
+    error::{AbiError, Result},
+    Function, Value,
+};
+
+/// Deserializes the input params of a function call straight into a serde
+/// type, e.g. a `#[derive(Deserialize)]` struct whose fields are named after
+/// the params, or a tuple of the params in order.
+///
+/// `calldata` is the whole call, `[params..., param-len, method_id]`.
+///
+/// ```
+/// use ola_lang_abi::{from_calldata, Abi, Value};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct CreateBook {
+///     id: u32,
+///     name: String,
+/// }
+///
+/// let abi: Abi = serde_json::from_str(r#"[{
+///     "type": "function",
+///     "name": "createBook",
+///     "inputs": [{"name": "id", "type": "u32"}, {"name": "name", "type": "string"}],
+///     "outputs": []
+/// }]"#).unwrap();
+/// let calldata = abi
+///     .encode_input_with_signature(
+///         "createBook(u32,string)",
+///         &[Value::U32(60), Value::String("ola".to_string())],
+///     )
+///     .unwrap();
+///
+/// let args: CreateBook = from_calldata(&abi.functions[0], &calldata).unwrap();
+/// assert_eq!((args.id, args.name.as_str()), (60, "ola"));
+/// ```
+///
+/// Integers deserialize from `u32`, `field`, `i32` and `i64` values,
+/// addresses, hashes and `u256` from their words or their hex string, arrays
+/// and `fields` from sequences, and tuples from structs or Rust tuples.
+pub fn from_calldata<T: DeserializeOwned>(function: &Function, calldata: &[u64]) -> Result<T> {
+    // calldata = [param1, param2, .. , param-len, method_id]
+    let (params, len, method_id) = match calldata {
+        [params @ .., len, method_id] => (params, *len, *method_id),
+        _ => {
+            return Err(AbiError::Truncated(
+                "missing function param-len and method_id".to_string(),
+            ))
+        }
+    };
+
+    if method_id != function.method_id() {
+        return Err(AbiError::InvalidInput(format!(
+            "method id {:#x} is not the one of {}",
+            method_id,
+            function.signature()
+        )));
+    }
+    if len != params.len() as u64 {
+        return Err(AbiError::LengthMismatch(format!(
+            "param-len is {}, but {} words were given",
+            len,
+            params.len()
+        )));
+    }
+
+    let params = function.decode_input_from_slice(params)?;
+
+    T::deserialize(ValueDeserializer(Value::Tuple(
+        params
+            .iter()
+            .map(|decoded| (decoded.param.name.clone(), decoded.value.clone()))
+            .collect(),
+    )))
+}
+
+impl serde::de::Error for AbiError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        AbiError::InvalidInput(msg.to_string())
+    }
+}
+
+struct ValueDeserializer(Value);
+
+impl<'de> IntoDeserializer<'de, AbiError> for ValueDeserializer {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+impl ValueDeserializer {
+    fn words<'de, V: Visitor<'de>>(words: Vec<u64>, visitor: V) -> Result<V::Value> {
+        visitor.visit_seq(SeqDeserializer::new(words.into_iter()))
+    }
+
+    fn values<'de, V: Visitor<'de>>(values: Vec<Value>, visitor: V) -> Result<V::Value> {
+        visitor.visit_seq(SeqDeserializer::new(
+            values.into_iter().map(ValueDeserializer),
+        ))
+    }
+}
+
+impl<'de> Deserializer<'de> for ValueDeserializer {
+    type Error = AbiError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            Value::U32(n) | Value::Field(n) => visitor.visit_u64(n),
+            Value::I32(n) => visitor.visit_i32(n),
+            Value::I64(n) => visitor.visit_i64(n),
+            Value::Bool(b) => visitor.visit_bool(b),
+            Value::String(s) => visitor.visit_string(s),
+            Value::U256(n) => Self::words(n.0.to_vec(), visitor),
+            Value::Address(words) | Value::Hash(words) => Self::words(words.0.to_vec(), visitor),
+            Value::Fields(words) => Self::words(words, visitor),
+            Value::FixedArray(values, _) | Value::Array(values, _) => Self::values(values, visitor),
+            Value::Tuple(members) => visitor.visit_map(MapDeserializer::new(
+                members
+                    .into_iter()
+                    .map(|(name, value)| (name, ValueDeserializer(value))),
+            )),
+            Value::None(_) => visitor.visit_none(),
+            Value::Some(value) => visitor.visit_some(ValueDeserializer(*value)),
+            Value::Map(entries, ..) => visitor.visit_map(MapDeserializer::new(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (ValueDeserializer(key), ValueDeserializer(value))),
+            )),
+            Value::Placeholder(name, _) => Err(AbiError::InvalidInput(format!(
+                "unfilled placeholder {}",
+                name
+            ))),
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            Value::U256(n) => visitor.visit_string(n.to_hex_string()),
+            Value::Address(words) | Value::Hash(words) => {
+                visitor.visit_string(words.to_hex_string())
+            }
+            value => ValueDeserializer(value).deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            Value::None(_) => visitor.visit_none(),
+            Value::Some(value) => visitor.visit_some(ValueDeserializer(*value)),
+            value => visitor.visit_some(ValueDeserializer(value)),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            Value::Tuple(members) => Self::values(
+                members.into_iter().map(|(_, value)| value).collect(),
+                visitor,
+            ),
+            value => ValueDeserializer(value).deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char
+        bytes byte_buf unit unit_struct map struct enum identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use serde::Deserialize;
+
+    use crate::{Abi, FixedArray4};
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Order {
+        id: u32,
+        owner: String,
+        amounts: Vec<i64>,
+        meta: Meta,
+        note: Option<String>,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Meta {
+        paid: bool,
+        hash: FixedArray4,
+    }
+
+    #[test]
+    fn deserialize_calldata() {
+        let abi: Abi = serde_json::from_value(serde_json::json!([{
+            "type": "function",
+            "name": "order",
+            "inputs": [
+                { "name": "id", "type": "u32" },
+                { "name": "owner", "type": "address" },
+                { "name": "amounts", "type": "i64[]" },
+                {
+                    "name": "meta",
+                    "type": "tuple",
+                    "components": [
+                        { "name": "paid", "type": "bool" },
+                        { "name": "hash", "type": "hash" }
+                    ]
+                },
+                { "name": "note", "type": "string" }
+            ],
+            "outputs": []
+        }]))
+        .unwrap();
+        let f = &abi.functions[0];
+
+        let calldata = abi
+            .encode_input_with_signature(
+                &f.signature(),
+                &[
+                    Value::U32(7),
+                    Value::Address(FixedArray4([0, 0, 0, 1])),
+                    Value::Array(vec![Value::I64(-5), Value::I64(5)], crate::Type::I64),
+                    Value::Tuple(vec![
+                        ("paid".to_string(), Value::Bool(true)),
+                        ("hash".to_string(), Value::Hash(FixedArray4([1, 2, 3, 4]))),
+                    ]),
+                    Value::String("ola".to_string()),
+                ],
+            )
+            .unwrap();
+
+        let order: Order = from_calldata(f, &calldata).unwrap();
+        assert_eq!(
+            order,
+            Order {
+                id: 7,
+                owner: FixedArray4([0, 0, 0, 1]).to_hex_string(),
+                amounts: vec![-5, 5],
+                meta: Meta {
+                    paid: true,
+                    hash: FixedArray4([1, 2, 3, 4]),
+                },
+                note: Some("ola".to_string()),
+            }
+        );
+
+        let (id, _, _, (paid, _), note): (u64, FixedArray4, Vec<i32>, (bool, String), String) =
+            from_calldata(f, &calldata).unwrap();
+        assert_eq!((id, paid, note.as_str()), (7, true, "ola"));
+
+        let mut wrong_selector = calldata.clone();
+        *wrong_selector.last_mut().unwrap() += 1;
+        assert!(from_calldata::<Order>(f, &wrong_selector).is_err());
+        assert!(matches!(
+            from_calldata::<Order>(f, &calldata[1..]),
+            Err(AbiError::LengthMismatch(_))
+        ));
+        assert!(matches!(
+            from_calldata::<(String,)>(f, &calldata),
+            Err(AbiError::InvalidInput(_))
+        ));
+    }
+}
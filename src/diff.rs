@@ -0,0 +1,261 @@
+use std::fmt;
+
+use crate::{DecodedParams, Value, ValueFormatter};
+
+/// Differences between two decoded params lists, see
+/// [`DecodedParams::diff`].
+///
+/// Renders one line per change, e.g. `/book/name: "ola" -> "olavm"`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParamsDiff {
+    /// Changed values, in param order.
+    pub changes: Vec<ParamChange>,
+}
+
+/// Value differing between two decoded params lists.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParamChange {
+    /// Path of the value, e.g. `/book/tags/1`: param and tuple member names
+    /// (indexes when unnamed), array indexes and rendered map keys.
+    pub path: String,
+    /// Original value, `None` if the value was added.
+    pub old: Option<Value>,
+    /// Other value, `None` if the value was removed.
+    pub new: Option<Value>,
+}
+
+impl DecodedParams {
+    /// Compares the params with `other`, e.g. the outputs of a transaction
+    /// and of its replay, down to the innermost differing values: tuples,
+    /// arrays, optional values and maps are compared member by member,
+    /// anything else as a whole.
+    ///
+    /// ```
+    /// use ola_lang_abi::{DecodedParams, Param, Type, Value};
+    ///
+    /// let book = |name: &str| {
+    ///     let ty = Type::Tuple(vec![("id".to_string(), Type::U32), ("name".to_string(), Type::String)]);
+    ///     let value = Value::Tuple(vec![
+    ///         ("id".to_string(), Value::U32(60)),
+    ///         ("name".to_string(), Value::String(name.to_string())),
+    ///     ]);
+    ///     DecodedParams::from(vec![(
+    ///         Param { name: "book".to_string(), type_: ty, indexed: None, docs: None },
+    ///         value,
+    ///     )])
+    /// };
+    ///
+    /// let diff = book("ola").diff(&book("olavm"));
+    /// assert_eq!(diff.to_string(), "/book/name: \"ola\" -> \"olavm\"\n");
+    /// assert!(book("ola").diff(&book("ola")).is_empty());
+    /// ```
+    pub fn diff(&self, other: &DecodedParams) -> ParamsDiff {
+        let mut changes = vec![];
+
+        for i in 0..self.len().max(other.len()) {
+            let (old, new) = (self.nth(i), other.nth(i));
+            let name = match old.or(new) {
+                Some(decoded) if !decoded.param.name.is_empty() => decoded.param.name.clone(),
+                _ => i.to_string(),
+            };
+            let path = format!("/{}", name);
+
+            match (old, new) {
+                (Some(old), Some(new)) => diff_values(&old.value, &new.value, path, &mut changes),
+                (old, new) => changes.push(ParamChange {
+                    path,
+                    old: old.map(|decoded| decoded.value.clone()),
+                    new: new.map(|decoded| decoded.value.clone()),
+                }),
+            }
+        }
+
+        ParamsDiff { changes }
+    }
+}
+
+impl ParamsDiff {
+    /// Returns whether the params are the same.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Renders the changes with `formatter`, e.g. to color them or scale
+    /// amounts, one line per change.
+    pub fn format(&self, formatter: &ValueFormatter) -> String {
+        let mut out = String::new();
+
+        for change in &self.changes {
+            let name = change.path.rsplit('/').next().unwrap_or_default();
+            let value = |value: &Value| formatter.format_named(name, value);
+
+            let line = match (&change.old, &change.new) {
+                (Some(old), Some(new)) => format!("{} -> {}", value(old), value(new)),
+                (Some(old), None) => format!("removed {}", value(old)),
+                (None, Some(new)) => format!("added {}", value(new)),
+                (None, None) => continue,
+            };
+            out.push_str(&format!("{}: {}\n", change.path, line));
+        }
+
+        out
+    }
+}
+
+impl fmt::Display for ParamsDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // shortened hex values could hide the difference
+        f.write_str(&self.format(&ValueFormatter::new().full(true)))
+    }
+}
+
+fn diff_values(old: &Value, new: &Value, path: String, changes: &mut Vec<ParamChange>) {
+    if old == new {
+        return;
+    }
+
+    let change = |path: String, old: Option<&Value>, new: Option<&Value>| ParamChange {
+        path,
+        old: old.cloned(),
+        new: new.cloned(),
+    };
+
+    match (old, new) {
+        (Value::Tuple(old), Value::Tuple(new)) if old.len() == new.len() => {
+            for (i, ((name, old), (_, new))) in old.iter().zip(new).enumerate() {
+                let path = if name.is_empty() {
+                    format!("{}/{}", path, i)
+                } else {
+                    format!("{}/{}", path, name)
+                };
+                diff_values(old, new, path, changes);
+            }
+        }
+        (Value::FixedArray(old, old_ty), Value::FixedArray(new, new_ty))
+        | (Value::Array(old, old_ty), Value::Array(new, new_ty))
+            if old_ty == new_ty =>
+        {
+            for i in 0..old.len().max(new.len()) {
+                let path = format!("{}/{}", path, i);
+                match (old.get(i), new.get(i)) {
+                    (Some(old), Some(new)) => diff_values(old, new, path, changes),
+                    (old, new) => changes.push(change(path, old, new)),
+                }
+            }
+        }
+        (Value::Some(old), Value::Some(new)) => diff_values(old, new, path, changes),
+        (Value::Map(old, old_key, old_value), Value::Map(new, new_key, new_value))
+            if old_key == new_key && old_value == new_value =>
+        {
+            let key_formatter = ValueFormatter::new().full(true);
+            let path_of = |key: &Value| format!("{}/{}", path, key_formatter.format_value(key));
+
+            for (key, old) in old {
+                match new.iter().find(|(other, _)| other == key) {
+                    Some((_, new)) => diff_values(old, new, path_of(key), changes),
+                    None => changes.push(change(path_of(key), Some(old), None)),
+                }
+            }
+            for (key, new) in new {
+                if !old.iter().any(|(other, _)| other == key) {
+                    changes.push(change(path_of(key), None, Some(new)));
+                }
+            }
+        }
+        _ => changes.push(change(path, Some(old), Some(new))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::{Param, Type};
+
+    fn params(values: Vec<(&str, Value)>) -> DecodedParams {
+        DecodedParams::from(
+            values
+                .into_iter()
+                .map(|(name, value)| {
+                    let param = Param {
+                        name: name.to_string(),
+                        type_: value.type_of(),
+                        indexed: None,
+                        docs: None,
+                    };
+                    (param, value)
+                })
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    #[test]
+    fn params_diff() {
+        let tags = |tags: &[&str]| {
+            Value::Array(
+                tags.iter()
+                    .map(|tag| Value::String(tag.to_string()))
+                    .collect(),
+                Type::String,
+            )
+        };
+        let balances = |balances: &[(&str, u64)]| {
+            Value::Map(
+                balances
+                    .iter()
+                    .map(|(owner, n)| (Value::String(owner.to_string()), Value::U32(*n)))
+                    .collect(),
+                Type::String,
+                Type::U32,
+            )
+        };
+
+        let old = params(vec![
+            ("tags", tags(&["a", "b", "c"])),
+            ("", Value::Tuple(vec![("".to_string(), Value::U32(1))])),
+            ("owner", Value::Some(Box::new(Value::U32(1)))),
+            ("balances", balances(&[("alice", 1), ("bob", 2)])),
+            ("hash", Value::Hash(crate::FixedArray4([1, 2, 3, 4]))),
+        ]);
+        let new = params(vec![
+            ("tags", tags(&["a", "x"])),
+            ("", Value::Tuple(vec![("".to_string(), Value::U32(2))])),
+            ("owner", Value::None(Type::U32)),
+            ("balances", balances(&[("bob", 3), ("carol", 4)])),
+            ("hash", Value::Hash(crate::FixedArray4([1, 2, 3, 5]))),
+            ("extra", Value::Bool(true)),
+        ]);
+
+        let diff = old.diff(&new);
+        assert_eq!(
+            diff.changes[0],
+            ParamChange {
+                path: "/tags/1".to_string(),
+                old: Some(Value::String("b".to_string())),
+                new: Some(Value::String("x".to_string())),
+            }
+        );
+        assert_eq!(
+            diff.to_string(),
+            [
+                "/tags/1: \"b\" -> \"x\"",
+                "/tags/2: removed \"c\"",
+                "/1/0: 1 -> 2",
+                "/owner: 1 -> none",
+                "/balances/\"alice\": removed 1",
+                "/balances/\"bob\": 2 -> 3",
+                "/balances/\"carol\": added 4",
+                "/hash: 0x0000000000000001000000000000000200000000000000030000000000000004 -> \
+                 0x0000000000000001000000000000000200000000000000030000000000000005",
+                "/extra: added true",
+                "",
+            ]
+            .join("\n")
+        );
+
+        assert!(old.diff(&old).is_empty());
+        assert_eq!(old.diff(&old).to_string(), "");
+    }
+}
@@ -0,0 +1,317 @@
+use crate::alloc_prelude::*;
+use crate::{values::signed_to_word, varint, Type, Value};
+
+/// Sink for encoded words.
+///
+/// Implemented for every `Extend<u64>` collection; implement it directly to
+/// encode into buffers that aren't collections (mmap'd regions, network
+/// frames, ...).
+pub trait Write64 {
+    /// Writes a single word.
+    fn write_word(&mut self, word: u64);
+
+    /// Writes a sequence of words.
+    fn write_words(&mut self, words: &[u64]) {
+        for word in words {
+            self.write_word(*word);
+        }
+    }
+}
+
+impl<T: Extend<u64>> Write64 for T {
+    fn write_word(&mut self, word: u64) {
+        self.extend(core::iter::once(word));
+    }
+
+    fn write_words(&mut self, words: &[u64]) {
+        self.extend(words.iter().copied());
+    }
+}
+
+/// How strings are laid out in words.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum StringPacking {
+    /// Length word followed by one word per UTF-8 byte.
+    ///
+    /// TODO Currently, Ola can only encode strings into arrays based on fields
+    /// and does not support encoding into u8 type arrays.
+    #[default]
+    WordPerByte,
+}
+
+/// How sequences of `u32` and `field` integers are laid out in words: the
+/// elements of `u32` and `field` arrays and of `fields` values.
+///
+/// Both ends of a call have to agree on the packing, see
+/// [`EncodeOptions`] and [`DecodeOptions`](crate::DecodeOptions).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum IntegerPacking {
+    /// One word per integer.
+    #[default]
+    WordPerInteger,
+    /// LEB128 varints packed 7 bytes per word, after the length word of
+    /// dynamic sequences, shrinking sequences of small integers up to 7
+    /// times. Integers wider than 49 bits take more words than unpacked.
+    ///
+    /// ```
+    /// use ola_lang_abi::{
+    ///     DecodeOptions, Decoder, EncodeOptions, Encoder, IntegerPacking, Type, Value,
+    /// };
+    ///
+    /// let ids = Value::Array((1..=10).map(Value::U32).collect(), Type::U32);
+    /// let options = EncodeOptions { integer_packing: IntegerPacking::Varint, ..Default::default() };
+    ///
+    /// let mut words = vec![];
+    /// Encoder::with_options(&mut words, options).encode(&[ids.clone()]);
+    /// assert_eq!(words.len(), 3);
+    ///
+    /// let decoder = Decoder::new().with_options(DecodeOptions {
+    ///     integer_packing: IntegerPacking::Varint,
+    ///     ..Default::default()
+    /// });
+    /// assert_eq!(decoder.decode(&words, &[ids.type_of()]).unwrap(), [ids]);
+    /// ```
+    Varint,
+}
+
+/// Encoding options.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EncodeOptions {
+    /// String layout.
+    pub string_packing: StringPacking,
+    /// Integer sequence layout.
+    pub integer_packing: IntegerPacking,
+}
+
+/// Encodes values into a word sink.
+///
+/// ```
+/// use ola_lang_abi::{Encoder, Value};
+///
+/// let mut buf = vec![];
+/// Encoder::new(&mut buf).encode(&[Value::U32(7), Value::String("ola".to_string())]);
+///
+/// assert_eq!(buf, vec![7, 3, 111, 108, 97]);
+/// ```
+pub struct Encoder<'s, W: Write64 + ?Sized> {
+    sink: &'s mut W,
+    options: EncodeOptions,
+}
+
+impl<'s, W: Write64 + ?Sized> Encoder<'s, W> {
+    /// Creates an encoder writing to `sink` with default options.
+    pub fn new(sink: &'s mut W) -> Self {
+        Self::with_options(sink, EncodeOptions::default())
+    }
+
+    /// Creates an encoder writing to `sink` with the given options.
+    pub fn with_options(sink: &'s mut W, options: EncodeOptions) -> Self {
+        Self { sink, options }
+    }
+
+    /// Encodes values into the sink.
+    pub fn encode(&mut self, values: &[Value]) {
+        for value in values {
+            self.encode_value(value);
+        }
+    }
+
+    fn encode_value(&mut self, value: &Value) {
+        match value {
+            Value::U32(i) | Value::Field(i) => self.sink.write_word(*i),
+
+            Value::I32(i) => self.sink.write_word(signed_to_word((*i).into())),
+            Value::I64(i) => self.sink.write_word(signed_to_word(*i)),
+
+            // big-endian limbs
+            Value::U256(num) => self.sink.write_words(&num.0),
+
+            Value::Address(addr) | Value::Hash(addr) => self.sink.write_words(&addr.0),
+
+            Value::Bool(b) => self.sink.write_word(*b as u64),
+
+            Value::FixedArray(values, ty) => match self.packed_integers(values, ty) {
+                Some(integers) => varint::pack(&integers, self.sink),
+                None => self.encode(values),
+            },
+
+            Value::Tuple(values) => {
+                for (_, value) in values {
+                    self.encode_value(value);
+                }
+            }
+
+            Value::String(value) => self.encode_string(value),
+
+            Value::Fields(value) => {
+                self.sink.write_word(value.len() as u64);
+                match self.options.integer_packing {
+                    IntegerPacking::WordPerInteger => self.sink.write_words(value),
+                    IntegerPacking::Varint => varint::pack(value, self.sink),
+                }
+            }
+
+            Value::Array(values, ty) => {
+                self.sink.write_word(values.len() as u64);
+                match self.packed_integers(values, ty) {
+                    Some(integers) => varint::pack(&integers, self.sink),
+                    None => self.encode(values),
+                }
+            }
+
+            Value::None(_) => self.sink.write_word(0),
+
+            Value::Some(value) => {
+                self.sink.write_word(1);
+                self.encode_value(value);
+            }
+
+            Value::Map(entries, ..) => {
+                self.sink.write_word(entries.len() as u64);
+                for (key, value) in entries {
+                    self.encode_value(key);
+                    self.encode_value(value);
+                }
+            }
+
+            // the default values of every type encode to zero words
+            Value::Placeholder(_, ty) => {
+                const ZEROS: [u64; 64] = [0; 64];
+
+                let mut len = default_len(ty, self.options.integer_packing);
+                while len > 0 {
+                    let words = len.min(ZEROS.len() as u64);
+                    self.sink.write_words(&ZEROS[..words as usize]);
+                    len -= words;
+                }
+            }
+        }
+    }
+
+    /// Integers of the elements of a `u32` or `field` array to varint pack,
+    /// or `None` to encode the elements one by one.
+    fn packed_integers(&self, values: &[Value], ty: &Type) -> Option<Vec<u64>> {
+        if self.options.integer_packing != IntegerPacking::Varint
+            || !matches!(ty, Type::U32 | Type::Field)
+        {
+            return None;
+        }
+
+        values
+            .iter()
+            .map(|value| match value {
+                Value::U32(n) | Value::Field(n) => Some(*n),
+                Value::Placeholder(..) => Some(0),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn encode_string(&mut self, value: &str) {
+        match self.options.string_packing {
+            StringPacking::WordPerByte => {
+                self.sink.write_word(value.len() as u64);
+                for byte in value.as_bytes() {
+                    self.sink.write_word(*byte as u64);
+                }
+            }
+        }
+    }
+}
+
+/// Number of words the default value of a type encodes to, saturating,
+/// without building it, see [`Value::default_for`].
+pub(crate) fn default_len(ty: &Type, packing: IntegerPacking) -> u64 {
+    match ty {
+        Type::FixedArray(elem, size)
+            if packing == IntegerPacking::Varint && matches!(**elem, Type::U32 | Type::Field) =>
+        {
+            // a byte per zero integer
+            size.div_ceil(7)
+        }
+        Type::FixedArray(elem, size) => default_len(elem, packing).saturating_mul(*size),
+        Type::Tuple(tys) => tys.iter().fold(0, |len, (_, ty)| {
+            len.saturating_add(default_len(ty, packing))
+        }),
+        // empty, or absent: the length or presence word
+        Type::String | Type::Fields | Type::Array(_) | Type::Option(_) | Type::Map(..) => 1,
+        ty => ty.static_size().unwrap_or(1),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    /// Fixed-size sink that isn't a collection.
+    struct Frame {
+        words: [u64; 8],
+        len: usize,
+    }
+
+    impl Write64 for Frame {
+        fn write_word(&mut self, word: u64) {
+            self.words[self.len] = word;
+            self.len += 1;
+        }
+    }
+
+    #[test]
+    fn encode_into_custom_sink() {
+        let mut frame = Frame {
+            words: [0; 8],
+            len: 0,
+        };
+
+        Encoder::new(&mut frame).encode(&[
+            Value::Bool(true),
+            Value::Array(vec![Value::U32(1), Value::U32(2)], crate::Type::U32),
+        ]);
+
+        assert_eq!(&frame.words[..frame.len], &[1, 2, 1, 2]);
+    }
+
+    #[test]
+    fn encode_into_extend_sink() {
+        let mut buf = std::collections::VecDeque::new();
+
+        Encoder::new(&mut buf).encode(&[Value::String("ola".to_string())]);
+
+        assert_eq!(buf, vec![3, 111, 108, 97]);
+    }
+
+    #[test]
+    fn placeholders_encode_as_default_values() {
+        for ty in [
+            "u256",
+            "u32[9]",
+            "field[2][3]",
+            "(address,string,u32[10],bool?)[2]",
+            "map(u32,string)",
+        ] {
+            let ty: Type = ty.parse().unwrap();
+            let placeholder = [Value::Placeholder("x".to_string(), ty.clone())];
+            let default = [Value::default_for(&ty).unwrap()];
+
+            for integer_packing in [IntegerPacking::WordPerInteger, IntegerPacking::Varint] {
+                let options = EncodeOptions {
+                    integer_packing,
+                    ..Default::default()
+                };
+                let (mut expected, mut words) = (vec![], vec![]);
+                Encoder::with_options(&mut expected, options).encode(&default);
+                Encoder::with_options(&mut words, options).encode(&placeholder);
+
+                assert_eq!(words, expected, "{} {:?}", ty, integer_packing);
+            }
+        }
+
+        // counted from the type, not built
+        let huge = Value::Placeholder("ids".to_string(), "u32[4294967295]".parse().unwrap());
+        assert_eq!(Value::encoded_len(&[huge]), 4294967295);
+    }
+}
@@ -0,0 +1,149 @@
+use crate::{
+    error::{AbiError, Result},
+    Abi, DecodedParams, FixedArray4, Function, Value,
+};
+
+/// Cross-contract call, framed as the callee's address followed by the
+/// calldata the callee receives:
+///
+/// ```text
+/// [target (4 words), payload..., payload-len, selector]
+/// ```
+///
+/// ```
+/// use ola_lang_abi::{Envelope, FixedArray4};
+///
+/// let envelope = Envelope {
+///     target: FixedArray4([0, 0, 0, 1]),
+///     selector: 0xf146ff09,
+///     payload: vec![60],
+/// };
+///
+/// let words = envelope.encode();
+/// assert_eq!(words, vec![0, 0, 0, 1, 60, 1, 0xf146ff09]);
+/// assert_eq!(Envelope::decode(&words).unwrap(), envelope);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Envelope {
+    /// Called contract address.
+    pub target: FixedArray4,
+    /// Method id of the called function.
+    pub selector: u64,
+    /// Encoded call params.
+    pub payload: Vec<u64>,
+}
+
+impl Envelope {
+    /// Creates the envelope of a call of `function` with the given params,
+    /// checked against its inputs, see [`Function::validate_inputs`].
+    pub fn new(target: FixedArray4, function: &Function, params: &[Value]) -> Result<Self> {
+        function.validate_inputs(params)?;
+
+        Ok(Self {
+            target,
+            selector: function.method_id(),
+            payload: Value::encode(params),
+        })
+    }
+
+    /// Returns the calldata received by the callee.
+    pub fn calldata(&self) -> Vec<u64> {
+        let mut calldata = self.payload.clone();
+        calldata.push(self.payload.len() as u64);
+        calldata.push(self.selector);
+        calldata
+    }
+
+    /// Encodes the envelope into words.
+    pub fn encode(&self) -> Vec<u64> {
+        let mut words = self.target.0.to_vec();
+        words.extend(self.calldata());
+        words
+    }
+
+    /// Decodes an envelope, checking its payload length word.
+    pub fn decode(words: &[u64]) -> Result<Self> {
+        if words.len() < 6 {
+            return Err(AbiError::Truncated(format!(
+                "envelope needs at least 6 words, got {}",
+                words.len()
+            )));
+        }
+
+        let (target, calldata) = words.split_at(4);
+        let (payload, framing) = calldata.split_at(calldata.len() - 2);
+
+        if framing[0] != payload.len() as u64 {
+            return Err(AbiError::LengthMismatch(format!(
+                "envelope payload length word is {}, but the payload has {} words",
+                framing[0],
+                payload.len()
+            )));
+        }
+
+        Ok(Self {
+            target: FixedArray4(target.try_into().unwrap()),
+            selector: framing[1],
+            payload: payload.to_vec(),
+        })
+    }
+
+    /// Decodes the call against the callee's ABI.
+    pub fn decode_call<'a>(&self, abi: &'a Abi) -> Result<(&'a Function, DecodedParams)> {
+        abi.decode_input_from_slice(&self.calldata())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn envelope_call_round_trip() {
+        let abi: Abi = serde_json::from_str(
+            r#"[{
+                "type": "function",
+                "name": "createBook",
+                "inputs": [
+                    { "name": "id", "type": "u32" },
+                    { "name": "name", "type": "string" }
+                ],
+                "outputs": []
+            }]"#,
+        )
+        .unwrap();
+
+        let params = [Value::U32(60), Value::String("ola".to_string())];
+        let envelope =
+            Envelope::new(FixedArray4([1, 2, 3, 4]), &abi.functions[0], &params).unwrap();
+
+        let decoded = Envelope::decode(&envelope.encode()).unwrap();
+        assert_eq!(decoded, envelope);
+
+        let (function, decoded_params) = decoded.decode_call(&abi).unwrap();
+        assert_eq!(function.name, "createBook");
+        assert_eq!(
+            decoded_params
+                .iter()
+                .map(|param| param.value.clone())
+                .collect::<Vec<_>>(),
+            params
+        );
+
+        let swapped = [Value::String("ola".to_string()), Value::U32(60)];
+        assert!(Envelope::new(FixedArray4::ZERO, &abi.functions[0], &swapped).is_err());
+    }
+
+    #[test]
+    fn envelope_decode_errors() {
+        assert!(Envelope::decode(&[0, 0, 0, 1, 0]).is_err());
+        assert_eq!(
+            Envelope::decode(&[0, 0, 0, 1, 7, 2, 9])
+                .unwrap_err()
+                .to_string(),
+            "envelope payload length word is 2, but the payload has 1 words"
+        );
+    }
+}
@@ -0,0 +1,187 @@
+use crate::alloc_prelude::*;
+#[cfg(feature = "std")]
+use std::path::PathBuf;
+
+use crate::{DecodeError, Type};
+
+/// Result of the ABI, value and event APIs.
+pub(crate) type Result<T, E = AbiError> = core::result::Result<T, E>;
+
+/// Error of the ABI, value and event APIs.
+///
+/// The other APIs return `anyhow` errors, into which this converts; use
+/// `err.downcast_ref::<AbiError>()` to tell their kind apart.
+///
+/// ```
+/// use ola_lang_abi::{AbiError, Type, Value};
+///
+/// let err = Value::decode_from_slice(&[3, 0xff], &[Type::String]).unwrap_err();
+/// assert!(matches!(err, AbiError::Truncated(_)));
+/// assert_eq!(err.code(), "TRUNCATED");
+/// ```
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum AbiError {
+    /// No ABI function has the method id, signature or name.
+    #[error("ABI function not found")]
+    FunctionNotFound,
+    /// No ABI event has the topic.
+    #[error("ABI event not found")]
+    EventNotFound,
+    /// No ABI error has the selector.
+    #[error("ABI error not found")]
+    ErrorNotFound,
+    /// Several ABI entries match, or their selectors collide.
+    #[error("{0}")]
+    Ambiguous(String),
+    /// The input ended before all values were decoded.
+    #[error("{0}")]
+    Truncated(String),
+    /// A length or count of the input doesn't match the ABI.
+    #[error("{0}")]
+    LengthMismatch(String),
+    /// A decoded string isn't valid UTF-8.
+    #[error("invalid UTF-8 string: {0}")]
+    InvalidUtf8(#[from] alloc::string::FromUtf8Error),
+    /// A value doesn't have the expected type.
+    #[error("expected {expected}, got {found}")]
+    TypeMismatch { expected: Type, found: Type },
+    /// A length or offset of the input overflows.
+    #[error(transparent)]
+    Overflow(#[from] DecodeError),
+    /// Decoding ran out of word budget or was cancelled, see
+    /// [`Decoder`](crate::Decoder).
+    #[error("{0}")]
+    Aborted(String),
+    /// Malformed input, e.g. an invalid hex string.
+    #[error("{0}")]
+    InvalidInput(String),
+    /// Malformed ABI JSON.
+    #[error("{0}")]
+    InvalidAbi(String),
+    /// An ABI file couldn't be read.
+    #[cfg(feature = "std")]
+    #[error("failed to read {}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// Any other error.
+    #[error(transparent)]
+    Other(anyhow::Error),
+}
+
+impl AbiError {
+    /// Returns a stable code of the error kind, e.g. `"FUNCTION_NOT_FOUND"`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            AbiError::FunctionNotFound => "FUNCTION_NOT_FOUND",
+            AbiError::EventNotFound => "EVENT_NOT_FOUND",
+            AbiError::ErrorNotFound => "ERROR_NOT_FOUND",
+            AbiError::Ambiguous(_) => "AMBIGUOUS",
+            AbiError::Truncated(_) => "TRUNCATED",
+            AbiError::LengthMismatch(_) => "LENGTH_MISMATCH",
+            AbiError::InvalidUtf8(_) => "INVALID_UTF8",
+            AbiError::TypeMismatch { .. } => "TYPE_MISMATCH",
+            AbiError::Overflow(_) => "OVERFLOW",
+            AbiError::Aborted(_) => "ABORTED",
+            AbiError::InvalidInput(_) => "INVALID_INPUT",
+            AbiError::InvalidAbi(_) => "INVALID_ABI",
+            #[cfg(feature = "std")]
+            AbiError::Io { .. } => "IO",
+            AbiError::Other(_) => "OTHER",
+        }
+    }
+
+    /// Renders the error as `{ "code", "message", "context" }`, `code` being
+    /// [`AbiError::code`] and `context` the structured details of the error
+    /// kind, if any: `expected` and `found` types of type mismatches, the
+    /// `at` and `len` or `base` and `offset` words of overflows, and the
+    /// `path` of IO errors.
+    ///
+    /// ```
+    /// use ola_lang_abi::{AbiError, Type};
+    ///
+    /// let err = AbiError::TypeMismatch { expected: Type::U32, found: Type::Bool };
+    /// assert_eq!(
+    ///     err.to_json(),
+    ///     serde_json::json!({
+    ///         "code": "TYPE_MISMATCH",
+    ///         "message": "expected u32, got bool",
+    ///         "context": { "expected": "u32", "found": "bool" }
+    ///     })
+    /// );
+    /// ```
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> serde_json::Value {
+        use serde_json::json;
+
+        let context = match self {
+            AbiError::TypeMismatch { expected, found } => json!({
+                "expected": expected.to_string(),
+                "found": found.to_string(),
+            }),
+            AbiError::Overflow(DecodeError::LengthOverflow { at, len }) => {
+                json!({ "at": at, "len": len })
+            }
+            AbiError::Overflow(DecodeError::OffsetOverflow { base, offset }) => {
+                json!({ "base": base, "offset": offset })
+            }
+            AbiError::Io { path, .. } => json!({ "path": path.display().to_string() }),
+            _ => json!({}),
+        };
+
+        json!({
+            "code": self.code(),
+            "message": self.to_string(),
+            "context": context,
+        })
+    }
+}
+
+/// Recovers the `AbiError` an `anyhow` error was created from, if any.
+impl From<anyhow::Error> for AbiError {
+    fn from(err: anyhow::Error) -> Self {
+        err.downcast().unwrap_or_else(AbiError::Other)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn abi_error_from_anyhow() {
+        let err = anyhow::Error::from(AbiError::FunctionNotFound);
+        assert_eq!(AbiError::from(err).code(), "FUNCTION_NOT_FOUND");
+
+        let err = AbiError::from(anyhow::anyhow!("boom"));
+        assert_eq!((err.code(), err.to_string()), ("OTHER", "boom".to_string()));
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn abi_error_to_json() {
+        assert_eq!(
+            AbiError::FunctionNotFound.to_json(),
+            serde_json::json!({
+                "code": "FUNCTION_NOT_FOUND",
+                "message": "ABI function not found",
+                "context": {}
+            })
+        );
+        assert_eq!(
+            AbiError::Overflow(DecodeError::LengthOverflow { at: 2, len: 9 }).to_json()["context"],
+            serde_json::json!({ "at": 2, "len": 9 })
+        );
+
+        let err = AbiError::Io {
+            path: PathBuf::from("abi.json"),
+            source: std::io::Error::from(std::io::ErrorKind::NotFound),
+        };
+        assert_eq!(err.to_json()["code"], "IO");
+        assert_eq!(err.to_json()["context"]["path"], "abi.json");
+    }
+}
@@ -0,0 +1,297 @@
+//! Conversions to and from [`ethabi`] tokens, enabled with the `ethabi-compat`
+//! feature.
+//!
+//! Types map one to one where both sides agree:
+//!
+//! | Ola                 | ethabi            |
+//! |---------------------|-------------------|
+//! | `u32`               | `uint32`          |
+//! | `field`             | `uint64`          |
+//! | `u256`              | `uint256`         |
+//! | `address`           | `address`         |
+//! | `hash`              | `bytes32`         |
+//! | `bool`, `string`    | `bool`, `string`  |
+//! | `fields`            | `uint64[]`        |
+//! | arrays and tuples   | arrays and tuples |
+//!
+//! Ola addresses are 32 bytes wide and only convert to EVM addresses when
+//! their 12 leading bytes are zero. Tuple member names don't exist on the
+//! ethabi side and come back as `"0"`, `"1"`, ...
+//!
+//! Tokens don't carry enough information to tell `u32` from `u256` or an
+//! empty array's element type, hence [`Value::from_token`] takes the
+//! expected type.
+
+use anyhow::{anyhow, Result};
+use ethabi::{ParamType, Token};
+
+use crate::{FixedArray4, FixedArray8, Type, Value};
+
+impl TryFrom<&Type> for ParamType {
+    type Error = anyhow::Error;
+
+    fn try_from(ty: &Type) -> Result<Self> {
+        Ok(match ty {
+            Type::U32 => ParamType::Uint(32),
+            Type::U256 => ParamType::Uint(256),
+            Type::Field => ParamType::Uint(64),
+            Type::I32 => ParamType::Int(32),
+            Type::I64 => ParamType::Int(64),
+            Type::Address => ParamType::Address,
+            Type::Hash => ParamType::FixedBytes(32),
+            Type::Bool => ParamType::Bool,
+            Type::String => ParamType::String,
+            Type::Fields => ParamType::Array(Box::new(ParamType::Uint(64))),
+            Type::FixedArray(ty, size) => ParamType::FixedArray(
+                Box::new(ParamType::try_from(ty.as_ref())?),
+                usize::try_from(*size)?,
+            ),
+            Type::Array(ty) => ParamType::Array(Box::new(ParamType::try_from(ty.as_ref())?)),
+            Type::Tuple(tys) => ParamType::Tuple(
+                tys.iter()
+                    .map(|(_, ty)| ParamType::try_from(ty))
+                    .collect::<Result<_>>()?,
+            ),
+            Type::Option(_) => return Err(anyhow!("no ethabi type for optional type {}", ty)),
+            Type::Map(..) => return Err(anyhow!("no ethabi type for map type {}", ty)),
+        })
+    }
+}
+
+impl TryFrom<&ParamType> for Type {
+    type Error = anyhow::Error;
+
+    fn try_from(param_type: &ParamType) -> Result<Self> {
+        Ok(match param_type {
+            ParamType::Uint(32) => Type::U32,
+            ParamType::Uint(64) => Type::Field,
+            ParamType::Uint(256) => Type::U256,
+            ParamType::Int(32) => Type::I32,
+            ParamType::Int(64) => Type::I64,
+            ParamType::Address => Type::Address,
+            ParamType::FixedBytes(32) => Type::Hash,
+            ParamType::Bool => Type::Bool,
+            ParamType::String => Type::String,
+            ParamType::FixedArray(param_type, size) => {
+                Type::FixedArray(Box::new(Type::try_from(param_type.as_ref())?), *size as u64)
+            }
+            ParamType::Array(param_type) => {
+                Type::Array(Box::new(Type::try_from(param_type.as_ref())?))
+            }
+            ParamType::Tuple(param_types) => Type::Tuple(
+                param_types
+                    .iter()
+                    .enumerate()
+                    .map(|(i, param_type)| Ok((i.to_string(), Type::try_from(param_type)?)))
+                    .collect::<Result<_>>()?,
+            ),
+            _ => return Err(anyhow!("no Ola type for ethabi type {}", param_type)),
+        })
+    }
+}
+
+impl TryFrom<&Value> for Token {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &Value) -> Result<Self> {
+        Ok(match value {
+            Value::U32(n) | Value::Field(n) => Token::Uint((*n).into()),
+            Value::U256(n) => Token::Uint(ethabi::Uint::from_big_endian(&n.to_bytes32())),
+            Value::I32(n) => Token::Int(int_to_token((*n).into())),
+            Value::I64(n) => Token::Int(int_to_token(*n)),
+            Value::Address(addr) => {
+                let bytes = addr.to_bytes32();
+                if bytes[..12].iter().any(|b| *b != 0) {
+                    return Err(anyhow!("address {} doesn't fit in 20 bytes", addr));
+                }
+                Token::Address(ethabi::Address::from_slice(&bytes[12..]))
+            }
+            Value::Hash(hash) => Token::FixedBytes(hash.to_bytes32().to_vec()),
+            Value::Bool(b) => Token::Bool(*b),
+            Value::String(s) => Token::String(s.clone()),
+            Value::Fields(fields) => {
+                Token::Array(fields.iter().map(|n| Token::Uint((*n).into())).collect())
+            }
+            Value::FixedArray(values, _) => Token::FixedArray(tokens(values.iter())?),
+            Value::Array(values, _) => Token::Array(tokens(values.iter())?),
+            Value::Tuple(values) => Token::Tuple(tokens(values.iter().map(|(_, v)| v))?),
+            Value::None(_) | Value::Some(_) | Value::Map(..) => {
+                return Err(anyhow!("no ethabi token for {} values", value.type_of()))
+            }
+            Value::Placeholder(name, _) => return Err(anyhow!("unfilled placeholder {}", name)),
+        })
+    }
+}
+
+impl Value {
+    /// Converts an ethabi token into a value of the given type.
+    pub fn from_token(token: Token, ty: &Type) -> Result<Value> {
+        Ok(match (token, ty) {
+            (Token::Uint(n), Type::U32) if n <= u32::MAX.into() => Value::U32(n.as_u64()),
+            (Token::Uint(n), Type::Field) if n <= u64::MAX.into() => Value::Field(n.as_u64()),
+            (Token::Uint(n), Type::U256) => {
+                let mut bytes = [0u8; 32];
+                n.to_big_endian(&mut bytes);
+
+                Value::U256(FixedArray8::from_bytes32(&bytes))
+            }
+            (Token::Int(n), Type::I32) => match token_to_int(n).map(i32::try_from) {
+                Some(Ok(n)) => Value::I32(n),
+                _ => return Err(anyhow!("ethabi int {} doesn't fit in i32", n)),
+            },
+            (Token::Int(n), Type::I64) => match token_to_int(n) {
+                Some(n) => Value::I64(n),
+                None => return Err(anyhow!("ethabi int {} doesn't fit in i64", n)),
+            },
+            (Token::Address(addr), Type::Address) => {
+                Value::Address(FixedArray4::from_slice(addr.as_bytes())?)
+            }
+            (Token::FixedBytes(bytes), Type::Hash) if bytes.len() == 32 => {
+                Value::Hash(FixedArray4::from_slice(&bytes)?)
+            }
+            (Token::Bool(b), Type::Bool) => Value::Bool(b),
+            (Token::String(s), Type::String) => Value::String(s),
+            (Token::Array(tokens), Type::Fields) => Value::Fields(
+                tokens
+                    .into_iter()
+                    .map(|token| match Value::from_token(token, &Type::Field)? {
+                        Value::Field(n) => Ok(n),
+                        _ => unreachable!(),
+                    })
+                    .collect::<Result<_>>()?,
+            ),
+            (Token::FixedArray(tokens), Type::FixedArray(ty, size))
+                if tokens.len() as u64 == *size =>
+            {
+                Value::FixedArray(values(tokens, ty)?, *ty.clone())
+            }
+            (Token::Array(tokens), Type::Array(ty)) => {
+                Value::Array(values(tokens, ty)?, *ty.clone())
+            }
+            (Token::Tuple(tokens), Type::Tuple(tys)) if tokens.len() == tys.len() => Value::Tuple(
+                tokens
+                    .into_iter()
+                    .zip(tys)
+                    .map(|(token, (name, ty))| Ok((name.clone(), Value::from_token(token, ty)?)))
+                    .collect::<Result<_>>()?,
+            ),
+            (token, ty) => return Err(anyhow!("can't convert ethabi token {} to {}", token, ty)),
+        })
+    }
+}
+
+/// Two's complement 256-bit int.
+fn int_to_token(n: i64) -> ethabi::Int {
+    if n >= 0 {
+        ethabi::Int::from(n as u64)
+    } else {
+        ethabi::Int::MAX - ethabi::Int::from(!(n as u64))
+    }
+}
+
+fn token_to_int(n: ethabi::Int) -> Option<i64> {
+    if !n.bit(255) {
+        i64::try_from(n).ok()
+    } else {
+        // n = 2^256 - |x|, hence MAX - n = |x| - 1
+        let magnitude = u64::try_from(ethabi::Int::MAX - n).ok()?;
+        (magnitude <= i64::MAX as u64).then(|| -(magnitude as i64) - 1)
+    }
+}
+
+fn tokens<'a>(values: impl Iterator<Item = &'a Value>) -> Result<Vec<Token>> {
+    values.map(Token::try_from).collect()
+}
+
+fn values(tokens: Vec<Token>, ty: &Type) -> Result<Vec<Value>> {
+    tokens
+        .into_iter()
+        .map(|token| Value::from_token(token, ty))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn type_round_trip() {
+        let ty = Type::Tuple(vec![
+            ("0".to_string(), Type::U32),
+            ("1".to_string(), Type::U256),
+            ("2".to_string(), Type::Address),
+            (
+                "3".to_string(),
+                Type::FixedArray(Box::new(Type::Array(Box::new(Type::Hash))), 2),
+            ),
+        ]);
+
+        let param_type = ParamType::try_from(&ty).unwrap();
+        assert_eq!(
+            param_type.to_string(),
+            "(uint32,uint256,address,bytes32[][2])"
+        );
+        assert_eq!(Type::try_from(&param_type).unwrap(), ty);
+
+        assert_eq!(Type::try_from(&ParamType::Int(64)).unwrap(), Type::I64);
+        assert!(Type::try_from(&ParamType::Int(8)).is_err());
+        assert!(Type::try_from(&ParamType::Bytes).is_err());
+    }
+
+    #[test]
+    fn value_round_trip() {
+        let value = Value::Tuple(vec![
+            ("a".to_string(), Value::U32(7)),
+            (
+                "b".to_string(),
+                Value::U256(FixedArray8([0, 0, 0, 0, 0, 0, 1, 2])),
+            ),
+            ("c".to_string(), Value::Address(FixedArray4([0, 0, 0, 1]))),
+            ("d".to_string(), Value::Fields(vec![1, 2])),
+            (
+                "e".to_string(),
+                Value::Array(vec![Value::String("ola".to_string())], Type::String),
+            ),
+        ]);
+
+        let token = Token::try_from(&value).unwrap();
+        assert_eq!(
+            token,
+            Token::Tuple(vec![
+                Token::Uint(7.into()),
+                Token::Uint((1u64 << 32 | 2).into()),
+                Token::Address(ethabi::Address::from_low_u64_be(1)),
+                Token::Array(vec![Token::Uint(1.into()), Token::Uint(2.into())]),
+                Token::Array(vec![Token::String("ola".to_string())]),
+            ])
+        );
+        assert_eq!(Value::from_token(token, &value.type_of()).unwrap(), value);
+    }
+
+    #[test]
+    fn conversion_errors() {
+        let wide = Value::Address(FixedArray4([1, 0, 0, 0]));
+        assert!(Token::try_from(&wide).is_err());
+
+        let too_big = Token::Uint(ethabi::Uint::from(u32::MAX) + 1);
+        assert!(Value::from_token(too_big, &Type::U32).is_err());
+        assert!(Value::from_token(Token::Bool(true), &Type::U32).is_err());
+    }
+
+    #[test]
+    fn signed_round_trip() {
+        for n in [0, 1, -1, i64::MIN, i64::MAX] {
+            let token = Token::try_from(&Value::I64(n)).unwrap();
+            assert_eq!(Value::from_token(token, &Type::I64).unwrap(), Value::I64(n));
+        }
+
+        assert_eq!(
+            Token::try_from(&Value::I32(-1)).unwrap(),
+            Token::Int(ethabi::Int::MAX)
+        );
+        let too_small = Token::try_from(&Value::I64(i32::MIN as i64 - 1)).unwrap();
+        assert!(Value::from_token(too_small, &Type::I32).is_err());
+    }
+}
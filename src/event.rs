@@ -1,8 +1,11 @@
-use anyhow::{anyhow, Result};
-use mini_goldilocks::poseidon::unsafe_poseidon_bytes_auto_padded;
 use std::collections::VecDeque;
 
-use crate::{DecodedParams, FixedArray4, Param, Type, Value};
+use crate::{
+    error::{AbiError, Result},
+    hash::{method_id_of, poseidon_words, topic_of},
+    values::signed_to_word,
+    DecodedParams, FixedArray4, Param, Provenance, Type, Value,
+};
 
 /// Contract Error Definition
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -13,6 +16,102 @@ pub struct Error {
     pub inputs: Vec<Param>,
 }
 
+impl Error {
+    /// Returns the error's signature.
+    pub fn signature(&self) -> String {
+        format!(
+            "{}({})",
+            self.name,
+            self.inputs
+                .iter()
+                .map(|param| param.type_.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        )
+    }
+
+    /// Returns the error selector, computed like a function method id.
+    pub fn selector(&self) -> u64 {
+        method_id_of(&self.signature())
+    }
+
+    /// Decodes the error params, failing on trailing words.
+    pub fn decode_params_from_slice(&self, data: &[u64]) -> Result<DecodedParams> {
+        let tys = self
+            .inputs
+            .iter()
+            .map(|param| param.type_.clone())
+            .collect::<Vec<_>>();
+
+        Ok(DecodedParams::from(
+            self.inputs
+                .iter()
+                .cloned()
+                .zip(crate::codec::decode(&tys, data)?)
+                .collect::<Vec<_>>(),
+        ))
+    }
+
+    /// Encodes a revert payload of the error,
+    /// `[params..., param-len, selector]`, checking the values against the
+    /// error inputs.
+    pub fn encode(&self, params: &[Value]) -> Result<Vec<u64>> {
+        let tys = self
+            .inputs
+            .iter()
+            .map(|param| param.type_.clone())
+            .collect::<Vec<_>>();
+
+        let mut data = crate::codec::encode(&tys, params)?;
+        data.push(data.len() as u64);
+        data.push(self.selector());
+
+        Ok(data)
+    }
+
+    /// Decodes a revert payload of the error,
+    /// `[params..., param-len, selector]`, the inverse of [`Error::encode`].
+    pub fn decode(&self, data: &[u64]) -> Result<DecodedParams> {
+        let (payload, param_len, selector) = match data {
+            [payload @ .., param_len, selector] => (payload, *param_len, *selector),
+            _ => {
+                return Err(AbiError::Truncated(
+                    "missing error param-len and selector".to_string(),
+                ))
+            }
+        };
+
+        if selector != self.selector() {
+            return Err(AbiError::InvalidInput(format!(
+                "selector {:#x} is not the one of {}",
+                selector,
+                self.signature()
+            )));
+        }
+        if param_len != payload.len() as u64 {
+            return Err(AbiError::LengthMismatch(format!(
+                "error param-len is {}, but the payload has {} words",
+                param_len,
+                payload.len()
+            )));
+        }
+
+        self.decode_params_from_slice(payload)
+    }
+}
+
+/// Policy for log topics beyond the ones of the event, which some nodes
+/// append, see [`Event::split_topics`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExtraTopics {
+    /// Logs with extra topics fail to decode.
+    #[default]
+    Strict,
+    /// Extra trailing topics are set aside, and the log decoded with the
+    /// leading ones.
+    Ignore,
+}
+
 /// Contract event definition.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Event {
@@ -22,6 +121,8 @@ pub struct Event {
     pub inputs: Vec<Param>,
     /// Whether the event is anonymous or not.
     pub anonymous: bool,
+    /// Where the event was loaded from, see [`Abi::load`](crate::Abi::load).
+    pub provenance: Option<Provenance>,
 }
 
 impl Event {
@@ -38,27 +139,106 @@ impl Event {
         )
     }
 
-    /// Compute the event's topic hash
-
+    /// Compute the event's topic hash, the poseidon hash words of the
+    /// signature in output order, as compared with the first topic of logs.
     pub fn topic(&self) -> FixedArray4 {
-        FixedArray4(unsafe_poseidon_bytes_auto_padded(
-            &self.signature().as_bytes(),
-        ))
+        topic_of(&self.signature())
+    }
+
+    /// Returns the number of topics of the event's logs: one per indexed
+    /// param, plus the event topic unless the event is anonymous.
+    pub fn topics_len(&self) -> usize {
+        let indexed = self.indexed().count();
+
+        if self.anonymous {
+            indexed
+        } else {
+            indexed + 1
+        }
+    }
+
+    /// Checks that a log has exactly the number of topics of the event.
+    pub fn validate_topics(&self, topics: &[FixedArray4]) -> Result<()> {
+        let expected = self.topics_len();
+
+        if topics.len() != expected {
+            return Err(AbiError::LengthMismatch(format!(
+                "event {} expects {} topics ({} indexed params{}), got {}",
+                self.signature(),
+                expected,
+                expected - usize::from(!self.anonymous),
+                if self.anonymous { "" } else { " + event topic" },
+                topics.len()
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Creates a builder of the topics to query for the event's logs.
+    ///
+    /// ```
+    /// use ola_lang_abi::{Event, Param, Type, Value};
+    ///
+    /// let event = Event {
+    ///     name: "Approve".to_string(),
+    ///     inputs: vec![
+    ///         Param { name: "x".to_string(), type_: Type::U32, indexed: Some(true), docs: None },
+    ///         Param { name: "y".to_string(), type_: Type::String, indexed: Some(true), docs: None },
+    ///     ],
+    ///     anonymous: false,
+    ///     provenance: None,
+    /// };
+    ///
+    /// let topics = event.filter().topic1(Value::U32(10)).build().unwrap();
+    /// assert_eq!(topics, vec![Some(event.topic()), Some(Event::encode_topic(&Value::U32(10))), None]);
+    /// ```
+    pub fn filter(&self) -> EventFilter<'_> {
+        EventFilter {
+            event: self,
+            values: vec![],
+        }
+    }
+
+    /// Splits a log's topics into the event topics and the extra trailing
+    /// ones, which are only allowed with [`ExtraTopics::Ignore`].
+    ///
+    /// Fails on missing topics whatever the policy, see
+    /// [`Event::validate_topics`].
+    pub fn split_topics<'t>(
+        &self,
+        topics: &'t [FixedArray4],
+        extra: ExtraTopics,
+    ) -> Result<(&'t [FixedArray4], &'t [FixedArray4])> {
+        let expected = self.topics_len();
+
+        match extra {
+            ExtraTopics::Ignore if topics.len() > expected => Ok(topics.split_at(expected)),
+            _ => {
+                self.validate_topics(topics)?;
+                Ok((topics, &[]))
+            }
+        }
     }
 
     /// Decode event params from a log's topics and data.
+    ///
+    /// Fails if the number of topics doesn't match, see
+    /// [`Event::validate_topics`].
     pub fn decode_data_from_slice(
         &self,
         mut topics: &[FixedArray4],
         data: &[u64],
     ) -> Result<DecodedParams> {
+        self.validate_topics(topics)?;
+
         // strip event topic from the topics array
         // so that we end up with only the values we
         // need to decode
         if !self.anonymous {
             topics = topics
                 .get(1..)
-                .ok_or_else(|| anyhow!("missing event topic"))?;
+                .ok_or_else(|| AbiError::Truncated("missing event topic".to_string()))?;
         }
 
         let mut topics_values = VecDeque::from(topics.to_vec());
@@ -76,37 +256,38 @@ impl Event {
         let mut decoded = vec![];
         for input in self.inputs.iter().cloned() {
             let decoded_value = if input.indexed.unwrap_or(false) {
-                let val = topics_values
-                    .pop_front()
-                    .ok_or_else(|| anyhow!("insufficient topics entries"))?;
+                let val = topics_values.pop_front().ok_or_else(|| {
+                    AbiError::Truncated("insufficient topics entries".to_string())
+                })?;
 
                 if Self::is_encoded_to_hash(&input.type_) {
                     Ok(Value::Hash(val))
-                } else if input.type_ == Type::U32
-                    || input.type_ == Type::Bool
-                    || input.type_ == Type::Field
-                {
+                } else if matches!(
+                    input.type_,
+                    Type::U32 | Type::Bool | Type::Field | Type::I32 | Type::I64
+                ) {
                     // decode value from topics entry, using the input type
                     //  If the input type is hash or address, take the value directly.
                     //  If the input type is u32, bool, field, take the last value (big-endian).
 
-                    Value::decode_from_slice(
-                        &[val.0.get(3).unwrap().clone()],
-                        &[input.type_.clone()],
-                    )?
-                    .first()
-                    .ok_or_else(|| anyhow!("no value decoded from topics entry"))
-                    .map(Clone::clone)
+                    Value::decode_from_slice(&val.0[3..], std::slice::from_ref(&input.type_))?
+                        .first()
+                        .ok_or_else(|| {
+                            AbiError::Truncated("no value decoded from topics entry".to_string())
+                        })
+                        .cloned()
                 } else {
-                    Value::decode_from_slice(&val.0, &[input.type_.clone()])?
+                    Value::decode_from_slice(&val.0, std::slice::from_ref(&input.type_))?
                         .first()
-                        .ok_or_else(|| anyhow!("no value decoded from topics entry"))
-                        .map(Clone::clone)
+                        .ok_or_else(|| {
+                            AbiError::Truncated("no value decoded from topics entry".to_string())
+                        })
+                        .cloned()
                 }
             } else {
                 data_values
                     .pop_front()
-                    .ok_or_else(|| anyhow!("insufficient data values"))
+                    .ok_or_else(|| AbiError::Truncated("insufficient data values".to_string()))
             };
 
             decoded.push((input, decoded_value?));
@@ -115,6 +296,29 @@ impl Event {
         Ok(DecodedParams::from(decoded))
     }
 
+    /// Encodes the value of an indexed param as a log topic.
+    ///
+    /// `u32`, `field`, `i32`, `i64` and `bool` values take the last word, addresses and
+    /// hashes all four words. Other values are replaced by the poseidon hash
+    /// of their encoding, and decode as [`Value::Hash`].
+    pub fn encode_topic(value: &Value) -> FixedArray4 {
+        match value {
+            Value::U32(word) | Value::Field(word) => FixedArray4([0, 0, 0, *word]),
+            Value::I32(n) => FixedArray4([0, 0, 0, signed_to_word((*n).into())]),
+            Value::I64(n) => FixedArray4([0, 0, 0, signed_to_word(*n)]),
+            Value::Bool(b) => FixedArray4([0, 0, 0, u64::from(*b)]),
+            Value::Address(words) | Value::Hash(words) => *words,
+            _ => poseidon_words(&Value::encode(std::slice::from_ref(value))),
+        }
+    }
+
+    /// Indexed params, in topic order.
+    fn indexed(&self) -> impl Iterator<Item = &Param> {
+        self.inputs
+            .iter()
+            .filter(|input| input.indexed.unwrap_or(false))
+    }
+
     fn is_encoded_to_hash(ty: &Type) -> bool {
         matches!(
             ty,
@@ -124,13 +328,115 @@ impl Event {
                 | Type::Fields
                 | Type::String
                 | Type::Tuple(_)
+                | Type::Option(_)
+                | Type::Map(..)
         )
     }
 }
 
+/// Builder of the topics to query for an event's logs, see
+/// [`Event::filter`].
+///
+/// Topics are numbered as in logs: `topic0` is the event topic, unless the
+/// event is anonymous, followed by one topic per indexed param. Values are
+/// encoded with [`Event::encode_topic`], so strings, arrays, tuples, `fields`
+/// and `u256` match by the hash of their encoding.
+#[derive(Debug, Clone)]
+pub struct EventFilter<'e> {
+    event: &'e Event,
+    values: Vec<(usize, Value)>,
+}
+
+impl EventFilter<'_> {
+    /// Matches logs whose `index`th topic is the encoding of `value`.
+    pub fn topic(mut self, index: usize, value: Value) -> Self {
+        self.values.retain(|(i, _)| *i != index);
+        self.values.push((index, value));
+        self
+    }
+
+    /// Matches logs whose first topic is `value`, for anonymous events.
+    pub fn topic0(self, value: Value) -> Self {
+        self.topic(0, value)
+    }
+
+    /// Matches logs whose second topic is `value`.
+    pub fn topic1(self, value: Value) -> Self {
+        self.topic(1, value)
+    }
+
+    /// Matches logs whose third topic is `value`.
+    pub fn topic2(self, value: Value) -> Self {
+        self.topic(2, value)
+    }
+
+    /// Matches logs whose fourth topic is `value`.
+    pub fn topic3(self, value: Value) -> Self {
+        self.topic(3, value)
+    }
+
+    /// Returns the topics to query, `None` matching any value.
+    ///
+    /// Fails if a topic is out of range, is the event topic, or its value
+    /// doesn't match the type of the indexed param.
+    pub fn build(&self) -> Result<Vec<Option<FixedArray4>>> {
+        let event = self.event;
+        let offset = usize::from(!event.anonymous);
+
+        let mut topics = vec![None; event.topics_len()];
+        if !event.anonymous {
+            topics[0] = Some(event.topic());
+        }
+
+        for (index, value) in &self.values {
+            let param = index
+                .checked_sub(offset)
+                .and_then(|i| event.indexed().nth(i))
+                .ok_or_else(|| {
+                    AbiError::InvalidInput(format!(
+                        "topic{} is not an indexed param of {}, which has {} topics",
+                        index,
+                        event.signature(),
+                        topics.len()
+                    ))
+                })?;
+
+            value.check_type(&param.type_).map_err(|e| {
+                AbiError::InvalidInput(format!("topic{} ({}): {}", index, param.name, e))
+            })?;
+
+            topics[*index] = Some(Event::encode_topic(value));
+        }
+
+        Ok(topics)
+    }
+
+    /// Returns the log filter object of Ola node log subscriptions,
+    /// `{ "address": "0x...", "topics": ["0x...", null, ...] }`, `null`
+    /// topics matching any value; `address` is left out when `None`, matching
+    /// logs of any contract.
+    pub fn to_json(&self, address: Option<FixedArray4>) -> Result<serde_json::Value> {
+        let topics = self
+            .build()?
+            .into_iter()
+            .map(|topic| topic.map(|topic| topic.to_hex_string()))
+            .collect::<Vec<_>>();
+
+        let mut filter = serde_json::Map::new();
+        if let Some(address) = address {
+            filter.insert("address".to_string(), address.to_hex_string().into());
+        }
+        filter.insert("topics".to_string(), topics.into());
+
+        Ok(filter.into())
+    }
+}
+
 #[cfg(test)]
 mod test {
 
+    use mini_goldilocks::poseidon::unsafe_poseidon_bytes_auto_padded;
+
     use crate::{Abi, DecodedParams, Type};
 
     use super::*;
@@ -145,14 +451,17 @@ mod test {
                     name: "x".to_string(),
                     type_: Type::U32,
                     indexed: Some(true),
+                    docs: None,
                 },
                 Param {
                     name: "y".to_string(),
                     type_: Type::String,
                     indexed: Some(true),
+                    docs: None,
                 },
             ],
             anonymous: false,
+            provenance: None,
         }
     }
 
@@ -182,7 +491,9 @@ mod test {
         let evt = test_event();
         assert_eq!(
             evt.topic(),
-            FixedArray4::from("0xF9C165D12ACC9776822FF3684D676F567781B3609185E4A01ED1EA5138EAF215")
+            "0xF9C165D12ACC9776822FF3684D676F567781B3609185E4A01ED1EA5138EAF215"
+                .parse::<FixedArray4>()
+                .unwrap()
         );
     }
 
@@ -205,37 +516,43 @@ mod test {
             name: "x".to_string(),
             type_: Type::U32,
             indexed: None,
+            docs: None,
         };
         let y = Param {
             name: "y".to_string(),
             type_: Type::U32,
             indexed: Some(true),
+            docs: None,
         };
         let x1 = Param {
             name: "x1".to_string(),
             type_: Type::U32,
             indexed: None,
+            docs: None,
         };
         let y1 = Param {
             name: "y1".to_string(),
             type_: Type::U32,
             indexed: Some(true),
+            docs: None,
         };
         let s = Param {
             name: "s".to_string(),
             type_: Type::String,
             indexed: None,
+            docs: None,
         };
 
         let evt = Event {
             name: "Test".to_string(),
             inputs: vec![x.clone(), y.clone(), x1.clone(), y1.clone(), s.clone()],
             anonymous: false,
+            provenance: None,
         };
 
         let abi = Abi {
-            functions: vec![],
             events: vec![evt],
+            ..Default::default()
         };
 
         assert_eq!(
@@ -253,4 +570,159 @@ mod test {
             )
         );
     }
+
+    #[test]
+    fn test_encode_topic() {
+        let evt = test_event();
+        let y = Value::String("ola".to_string());
+
+        let topics = [
+            evt.topic(),
+            Event::encode_topic(&Value::U32(10)),
+            Event::encode_topic(&y),
+        ];
+        let decoded = evt.decode_data_from_slice(&topics, &[]).unwrap();
+
+        assert_eq!(decoded[0].value, Value::U32(10));
+        assert_eq!(decoded[1].value, Value::Hash(Event::encode_topic(&y)));
+        assert_eq!(
+            Event::encode_topic(&Value::Bool(true)),
+            FixedArray4([0, 0, 0, 1])
+        );
+    }
+
+    #[test]
+    fn test_validate_topics() {
+        let evt = test_event();
+        let topic = FixedArray4([0, 0, 0, 1]);
+
+        assert_eq!(evt.topics_len(), 3);
+        assert!(evt.validate_topics(&[topic; 3]).is_ok());
+
+        let err = evt.decode_data_from_slice(&[topic; 2], &[]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "event Approve(u32,string) expects 3 topics (2 indexed params + event topic), got 2"
+        );
+        assert!(evt.decode_data_from_slice(&[topic; 4], &[]).is_err());
+
+        let topics = [topic, topic, topic, FixedArray4::ZERO];
+        assert!(evt.split_topics(&topics, ExtraTopics::Strict).is_err());
+        assert_eq!(
+            evt.split_topics(&topics, ExtraTopics::Ignore).unwrap(),
+            (&topics[..3], &topics[3..])
+        );
+        assert!(evt.split_topics(&topics[..2], ExtraTopics::Ignore).is_err());
+
+        let anonymous = Event {
+            anonymous: true,
+            ..test_event()
+        };
+        assert_eq!(anonymous.topics_len(), 2);
+        assert_eq!(
+            anonymous.validate_topics(&[topic]).unwrap_err().to_string(),
+            "event Approve(u32,string) expects 2 topics (2 indexed params), got 1"
+        );
+    }
+
+    #[test]
+    fn test_error_encode() {
+        let error = Error {
+            name: "InsufficientBalance".to_string(),
+            inputs: vec![
+                Param {
+                    name: "needed".to_string(),
+                    type_: Type::U32,
+                    indexed: None,
+                    docs: None,
+                },
+                Param {
+                    name: "reason".to_string(),
+                    type_: Type::String,
+                    indexed: None,
+                    docs: None,
+                },
+            ],
+        };
+        let values = [Value::U32(10), Value::String("ola".to_string())];
+
+        let data = error.encode(&values).unwrap();
+        assert_eq!(data, vec![10, 3, 111, 108, 97, 5, error.selector()]);
+
+        let params = error.decode(&data).unwrap();
+        assert_eq!(
+            params.iter().map(|p| p.value.clone()).collect::<Vec<_>>(),
+            values
+        );
+
+        assert!(error.encode(&values[..1]).is_err());
+        assert!(matches!(
+            error.decode(&data[..data.len() - 1]),
+            Err(AbiError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            error.decode(&data[1..]),
+            Err(AbiError::LengthMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn event_filter_topics() {
+        let evt = test_event();
+
+        assert_eq!(
+            evt.filter().build().unwrap(),
+            vec![Some(evt.topic()), None, None]
+        );
+
+        let y = Value::String("abc".to_string());
+        let topics = evt
+            .filter()
+            .topic1(Value::U32(10))
+            .topic2(y.clone())
+            .build()
+            .unwrap();
+        assert_eq!(
+            topics,
+            vec![
+                Some(evt.topic()),
+                Some(FixedArray4([0, 0, 0, 10])),
+                Some(Event::encode_topic(&y)),
+            ]
+        );
+
+        // the filter topics are the ones decoded from logs
+        let topics = topics.into_iter().map(Option::unwrap).collect::<Vec<_>>();
+        let decoded = evt.decode_data_from_slice(&topics, &[]).unwrap();
+        assert_eq!(decoded[0].value, Value::U32(10));
+        assert_eq!(decoded[1].value, Value::Hash(Event::encode_topic(&y)));
+
+        assert_eq!(
+            evt.filter()
+                .topic2(y.clone())
+                .to_json(Some(FixedArray4([0, 0, 0, 1])))
+                .unwrap(),
+            serde_json::json!({
+                "address": FixedArray4([0, 0, 0, 1]).to_hex_string(),
+                "topics": [
+                    evt.topic().to_hex_string(),
+                    null,
+                    Event::encode_topic(&y).to_hex_string(),
+                ],
+            })
+        );
+
+        assert!(evt.filter().topic0(Value::U32(10)).build().is_err());
+        assert!(evt.filter().topic3(Value::U32(10)).build().is_err());
+        assert!(evt.filter().topic2(Value::U32(10)).build().is_err());
+
+        let anonymous = Event {
+            anonymous: true,
+            ..test_event()
+        };
+        assert_eq!(
+            anonymous.filter().topic0(Value::U32(1)).build().unwrap(),
+            vec![Some(FixedArray4([0, 0, 0, 1])), None]
+        );
+    }
 }
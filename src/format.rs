@@ -0,0 +1,814 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write,
+};
+
+use crate::{calldata, DecodedParams, Value};
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const TYPE: &str = "\x1b[36m";
+const NUMBER: &str = "\x1b[33m";
+const STRING: &str = "\x1b[32m";
+const HEX: &str = "\x1b[35m";
+
+/// Hex strings longer than this are shortened unless formatting in full.
+const MAX_HEX_LEN: usize = 18;
+/// Fields payloads longer than this are shortened unless formatting in full.
+const MAX_FIELDS_LEN: usize = 8;
+
+/// Human readable, type annotated rendering of decoded values.
+///
+/// ```
+/// use ola_lang_abi::{DecodedParams, Param, Type, Value, ValueFormatter};
+///
+/// let params = DecodedParams::from(vec![(
+///     Param { name: "id".to_string(), type_: Type::U32, indexed: None, docs: None },
+///     Value::U32(60),
+/// )]);
+///
+/// assert_eq!(ValueFormatter::new().format_params(&params), "id: u32 = 60\n");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValueFormatter {
+    color: bool,
+    full: bool,
+    units: HashMap<String, Units>,
+    fields: FieldsEncoding,
+}
+
+/// Rendering of `fields` payloads.
+///
+/// Packed renderings are parsed back when deserializing a [`Value`], so
+/// they can be fed to the encoding side as well.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FieldsEncoding {
+    /// Array of words.
+    #[default]
+    Array,
+    /// `0x` prefixed hex, 16 digits per word.
+    Hex,
+    /// Base64 of the big-endian bytes of the words.
+    Base64,
+}
+
+impl FieldsEncoding {
+    /// Renders a payload as JSON.
+    #[cfg(feature = "json")]
+    pub fn to_json(&self, fields: &[u64]) -> serde_json::Value {
+        match self {
+            FieldsEncoding::Array => fields.into(),
+            FieldsEncoding::Hex => calldata::to_hex(fields).into(),
+            FieldsEncoding::Base64 => calldata::to_base64(fields).into(),
+        }
+    }
+}
+
+/// Most decimals rendered in full, the digits of the largest `u256`.
+pub const MAX_DECIMALS: u32 = 77;
+
+/// Decimal scaling of integer amounts, e.g. 9 decimals and `OLA` render
+/// `1500000000` as `1.5 OLA`.
+///
+/// Amounts scaled by more than [`MAX_DECIMALS`] decimals are rendered in
+/// scientific notation, e.g. `5e-80`, rather than padded with zeros.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Units {
+    /// Number of decimals of the amount.
+    pub decimals: u32,
+    /// Unit symbol appended to the amount.
+    pub symbol: Option<String>,
+}
+
+impl Units {
+    /// Creates units with the given number of decimals and no symbol.
+    pub fn new(decimals: u32) -> Self {
+        Self {
+            decimals,
+            symbol: None,
+        }
+    }
+
+    /// Sets the unit symbol.
+    pub fn symbol(mut self, symbol: &str) -> Self {
+        self.symbol = Some(symbol.to_string());
+        self
+    }
+
+    /// Renders a decimal integer string scaled down by the decimals.
+    fn scale(&self, digits: &str) -> String {
+        let mut out = if self.decimals > MAX_DECIMALS {
+            scientific(digits, self.decimals)
+        } else {
+            let decimals = self.decimals as usize;
+            let digits = format!("{:0>width$}", digits, width = decimals + 1);
+            let (int, frac) = digits.split_at(digits.len() - decimals);
+            let frac = frac.trim_end_matches('0');
+
+            let mut out = int.to_string();
+            if !frac.is_empty() {
+                write!(out, ".{}", frac).unwrap();
+            }
+            out
+        };
+        if let Some(symbol) = &self.symbol {
+            write!(out, " {}", symbol).unwrap();
+        }
+        out
+    }
+}
+
+/// Renders a decimal integer string scaled down by more decimals than it has
+/// digits, e.g. `15` by 80 decimals as `1.5e-79`.
+fn scientific(digits: &str, decimals: u32) -> String {
+    let digits = digits.trim_start_matches('0');
+    let Some((first, rest)) = digits.split_at_checked(1) else {
+        return "0".to_string();
+    };
+
+    let exponent = u64::from(decimals) - (digits.len() as u64 - 1);
+    match rest.trim_end_matches('0') {
+        "" => format!("{}e-{}", first, exponent),
+        rest => format!("{}.{}e-{}", first, rest, exponent),
+    }
+}
+
+/// Decoded params rendered as a JSON object, see
+/// [`ValueFormatter::to_json_object`].
+#[cfg(feature = "json")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonObject {
+    /// Params keyed by sanitized name, tuples as nested objects.
+    pub value: serde_json::Value,
+    /// JSON pointer of every key (e.g. `/order/paid_at`) and the ABI name
+    /// it was derived from, in rendering order.
+    pub keys: Vec<(String, String)>,
+}
+
+/// Sanitizes param or tuple member names into identifier-like keys, unique
+/// within `names`.
+///
+/// Characters other than ASCII letters, digits and `_` become `_`, names
+/// starting with a digit get a `_` prefix, and empty names are keyed by
+/// position (`_0`, `_1`, ...). A key already taken gets the first free
+/// `_2`, `_3`, ... suffix, so the result only depends on the names and
+/// their order.
+///
+/// ```
+/// use ola_lang_abi::json_keys;
+///
+/// assert_eq!(
+///     json_keys(&["amount", "paid at", "", "amount", "2nd"]),
+///     ["amount", "paid_at", "_2", "amount_2", "_2nd"]
+/// );
+/// ```
+pub fn json_keys(names: &[&str]) -> Vec<String> {
+    let mut used = HashSet::new();
+
+    names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let mut key = name
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                .collect::<String>();
+            if key.is_empty() {
+                key = format!("_{}", i);
+            } else if key.starts_with(|c: char| c.is_ascii_digit()) {
+                key.insert(0, '_');
+            }
+
+            let mut unique = key.clone();
+            let mut n = 2;
+            while !used.insert(unique.clone()) {
+                unique = format!("{}_{}", key, n);
+                n += 1;
+            }
+            unique
+        })
+        .collect()
+}
+
+impl ValueFormatter {
+    /// Creates a formatter without colors, shortening long hex values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enables ANSI terminal colors.
+    pub fn color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Disables the shortening of long hex values and fields payloads.
+    pub fn full(mut self, full: bool) -> Self {
+        self.full = full;
+        self
+    }
+
+    /// Renders `fields` payloads packed as hex or base64.
+    pub fn fields(mut self, fields: FieldsEncoding) -> Self {
+        self.fields = fields;
+        self
+    }
+
+    /// Renders the integer params and tuple members with the given name as
+    /// decimal scaled amounts.
+    ///
+    /// ```
+    /// use ola_lang_abi::{Units, Value, ValueFormatter};
+    ///
+    /// let formatter = ValueFormatter::new().units("amount", Units::new(9).symbol("OLA"));
+    ///
+    /// assert_eq!(
+    ///     formatter.format_named("amount", &Value::U32(1_500_000_000)),
+    ///     "1.5 OLA"
+    /// );
+    /// ```
+    pub fn units(mut self, name: &str, units: Units) -> Self {
+        self.units.insert(name.to_string(), units);
+        self
+    }
+
+    /// Renders decoded params, one `name: type = value` line per param.
+    pub fn format_params(&self, params: &DecodedParams) -> String {
+        let mut out = String::new();
+
+        for (i, decoded) in params.iter().enumerate() {
+            let name = if decoded.param.name.is_empty() {
+                format!("[{}]", i)
+            } else {
+                decoded.param.name.clone()
+            };
+
+            writeln!(
+                out,
+                "{}: {} = {}",
+                self.paint(BOLD, &name),
+                self.paint(TYPE, &decoded.param.type_.to_string()),
+                self.format_named(&decoded.param.name, &decoded.value)
+            )
+            .unwrap();
+        }
+
+        out
+    }
+
+    /// Renders a single value, applying the units registered for `name`.
+    pub fn format_named(&self, name: &str, value: &Value) -> String {
+        let digits = match value {
+            Value::U32(n) | Value::Field(n) => n.to_string(),
+            Value::U256(n) => u256_to_decimal(&n.0),
+            _ => return self.format_value(value),
+        };
+
+        match self.units.get(name) {
+            Some(units) => self.paint(NUMBER, &units.scale(&digits)),
+            None => self.format_value(value),
+        }
+    }
+
+    /// Renders decoded params as JSON, in the serde shape of
+    /// [`DecodedParams`] but with `fields` payloads rendered as configured.
+    ///
+    /// ```
+    /// use ola_lang_abi::{DecodedParams, FieldsEncoding, Param, Type, Value, ValueFormatter};
+    ///
+    /// let params = DecodedParams::from(vec![(
+    ///     Param { name: "blob".to_string(), type_: Type::Fields, indexed: None, docs: None },
+    ///     Value::Fields(vec![1, 2]),
+    /// )]);
+    /// let json = ValueFormatter::new().fields(FieldsEncoding::Base64).to_json(&params);
+    ///
+    /// assert_eq!(json[0]["value"]["Fields"], "AAAAAAAAAAEAAAAAAAAAAg==");
+    /// ```
+    #[cfg(feature = "json")]
+    pub fn to_json(&self, params: &DecodedParams) -> serde_json::Value {
+        params
+            .iter()
+            .map(|decoded| {
+                serde_json::json!({
+                    "param": decoded.param,
+                    "value": self.value_json(&decoded.value),
+                })
+            })
+            .collect()
+    }
+
+    #[cfg(feature = "json")]
+    fn value_json(&self, value: &Value) -> serde_json::Value {
+        let values = |values: &[Value]| {
+            values
+                .iter()
+                .map(|value| self.value_json(value))
+                .collect::<Vec<_>>()
+        };
+
+        match value {
+            Value::Fields(fields) => serde_json::json!({ "Fields": self.fields.to_json(fields) }),
+            Value::FixedArray(elements, ty) => {
+                serde_json::json!({ "FixedArray": [values(elements), ty] })
+            }
+            Value::Array(elements, ty) => serde_json::json!({ "Array": [values(elements), ty] }),
+            Value::Tuple(members) => serde_json::json!({
+                "Tuple": members
+                    .iter()
+                    .map(|(name, value)| serde_json::json!([name, self.value_json(value)]))
+                    .collect::<Vec<_>>()
+            }),
+            Value::Some(value) => serde_json::json!({ "Some": self.value_json(value) }),
+            Value::Map(entries, key_ty, value_ty) => serde_json::json!({
+                "Map": [
+                    entries
+                        .iter()
+                        .map(|(key, value)| {
+                            serde_json::json!([self.value_json(key), self.value_json(value)])
+                        })
+                        .collect::<Vec<_>>(),
+                    key_ty,
+                    value_ty,
+                ]
+            }),
+            _ => serde_json::to_value(value).expect("values serialize to JSON"),
+        }
+    }
+
+    /// Renders decoded params as a JSON object keyed by param name, for
+    /// stores that map keys to columns.
+    ///
+    /// Names are sanitized with [`json_keys`] and the mapping back to the ABI
+    /// names is returned along with the object. Integers render as numbers,
+    /// `u256`, addresses and hashes as hex strings, `fields` payloads as
+    /// configured and tuples as nested objects.
+    ///
+    /// ```
+    /// use ola_lang_abi::{DecodedParams, Param, Type, Value, ValueFormatter};
+    ///
+    /// let params = DecodedParams::from(vec![(
+    ///     Param { name: "book id".to_string(), type_: Type::U32, indexed: None, docs: None },
+    ///     Value::U32(60),
+    /// )]);
+    /// let json = ValueFormatter::new().to_json_object(&params);
+    ///
+    /// assert_eq!(json.value, serde_json::json!({ "book_id": 60 }));
+    /// assert_eq!(json.keys, [("/book_id".to_string(), "book id".to_string())]);
+    /// ```
+    #[cfg(feature = "json")]
+    pub fn to_json_object(&self, params: &DecodedParams) -> JsonObject {
+        let mut keys = vec![];
+        let members = params
+            .iter()
+            .map(|decoded| (decoded.param.name.as_str(), &decoded.value))
+            .collect::<Vec<_>>();
+
+        let value = self.object_json(&members, "", &mut keys);
+
+        JsonObject { value, keys }
+    }
+
+    #[cfg(feature = "json")]
+    fn object_json(
+        &self,
+        members: &[(&str, &Value)],
+        path: &str,
+        keys: &mut Vec<(String, String)>,
+    ) -> serde_json::Value {
+        let names = members.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+        let mut object = serde_json::Map::new();
+
+        for (key, (name, value)) in json_keys(&names).into_iter().zip(members) {
+            let path = format!("{}/{}", path, key);
+            keys.push((path.clone(), name.to_string()));
+
+            let value = self.plain_json(value, &path, keys);
+            object.insert(key, value);
+        }
+
+        object.into()
+    }
+
+    #[cfg(feature = "json")]
+    fn plain_json(
+        &self,
+        value: &Value,
+        path: &str,
+        keys: &mut Vec<(String, String)>,
+    ) -> serde_json::Value {
+        match value {
+            Value::U32(n) | Value::Field(n) => (*n).into(),
+            Value::I32(n) => (*n).into(),
+            Value::I64(n) => (*n).into(),
+            Value::Bool(b) => (*b).into(),
+            Value::U256(n) => n.to_hex_string().into(),
+            Value::Address(words) | Value::Hash(words) => words.to_hex_string().into(),
+            Value::String(s) => s.as_str().into(),
+            Value::Fields(fields) => self.fields.to_json(fields),
+            Value::FixedArray(values, _) | Value::Array(values, _) => values
+                .iter()
+                .enumerate()
+                .map(|(i, value)| self.plain_json(value, &format!("{}/{}", path, i), keys))
+                .collect(),
+            Value::Tuple(members) => {
+                let members = members
+                    .iter()
+                    .map(|(name, value)| (name.as_str(), value))
+                    .collect::<Vec<_>>();
+                self.object_json(&members, path, keys)
+            }
+            Value::None(_) => serde_json::Value::Null,
+            Value::Some(value) => self.plain_json(value, path, keys),
+            Value::Map(entries, ..) => entries
+                .iter()
+                .map(|(key, value)| {
+                    let key = crate::value_json::map_key(key);
+                    let value = self.plain_json(value, &format!("{}/{}", path, key), keys);
+                    (key, value)
+                })
+                .collect::<serde_json::Map<_, _>>()
+                .into(),
+            Value::Placeholder(name, _) => format!("<{}>", name).into(),
+        }
+    }
+
+    /// Renders a single value.
+    pub fn format_value(&self, value: &Value) -> String {
+        match value {
+            Value::U32(n) | Value::Field(n) => self.paint(NUMBER, &n.to_string()),
+            Value::I32(n) => self.paint(NUMBER, &n.to_string()),
+            Value::I64(n) => self.paint(NUMBER, &n.to_string()),
+            Value::Bool(b) => self.paint(NUMBER, &b.to_string()),
+            Value::U256(n) => self.hex(&n.to_hex_string()),
+            Value::Address(addr) | Value::Hash(addr) => self.hex(&addr.to_hex_string()),
+            Value::String(s) => self.paint(STRING, &format!("{:?}", s)),
+            Value::Fields(fields) => self.format_fields(fields),
+            Value::FixedArray(values, _) | Value::Array(values, _) => format!(
+                "[{}]",
+                values
+                    .iter()
+                    .map(|value| self.format_value(value))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Value::Tuple(members) => format!(
+                "{{ {} }}",
+                members
+                    .iter()
+                    .map(|(name, value)| format!(
+                        "{}: {} = {}",
+                        self.paint(BOLD, name),
+                        self.paint(TYPE, &value.type_of().to_string()),
+                        self.format_named(name, value)
+                    ))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Value::None(_) => self.paint(NUMBER, "none"),
+            Value::Some(value) => self.format_value(value),
+            Value::Map(entries, ..) => format!(
+                "{{{}}}",
+                entries
+                    .iter()
+                    .map(|(key, value)| format!(
+                        "{}: {}",
+                        self.format_value(key),
+                        self.format_value(value)
+                    ))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Value::Placeholder(name, _) => self.paint(TYPE, &format!("<{}>", name)),
+        }
+    }
+
+    fn format_fields(&self, fields: &[u64]) -> String {
+        match self.fields {
+            FieldsEncoding::Array => {}
+            FieldsEncoding::Hex => return self.hex(&calldata::to_hex(fields)),
+            FieldsEncoding::Base64 => return self.paint(STRING, &calldata::to_base64(fields)),
+        }
+
+        let shown = if self.full || fields.len() <= MAX_FIELDS_LEN {
+            fields
+        } else {
+            &fields[..MAX_FIELDS_LEN]
+        };
+
+        let mut out = shown
+            .iter()
+            .map(|word| self.paint(NUMBER, &word.to_string()))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        if shown.len() < fields.len() {
+            write!(out, ", … {} more", fields.len() - shown.len()).unwrap();
+        }
+
+        format!("[{}]", out)
+    }
+
+    fn hex(&self, hex: &str) -> String {
+        if self.full || hex.len() <= MAX_HEX_LEN {
+            return self.paint(HEX, hex);
+        }
+
+        // keep the "0x" prefix, the leading and the trailing nibbles
+        let head = &hex[..MAX_HEX_LEN / 2 + 1];
+        let tail = &hex[hex.len() - MAX_HEX_LEN / 2 + 1..];
+        self.paint(HEX, &format!("{}…{}", head, tail))
+    }
+
+    fn paint(&self, color: &str, s: &str) -> String {
+        if self.color {
+            format!("{}{}{}", color, s, RESET)
+        } else {
+            s.to_string()
+        }
+    }
+}
+
+/// Renders big-endian u32 limbs as a decimal integer.
+fn u256_to_decimal(limbs: &[u64; 8]) -> String {
+    let mut limbs = limbs.map(|limb| limb as u32);
+    let mut digits = vec![];
+
+    while limbs.iter().any(|limb| *limb != 0) {
+        // long division by 10
+        let mut rem = 0u64;
+        for limb in limbs.iter_mut() {
+            let cur = (rem << 32) | *limb as u64;
+            *limb = (cur / 10) as u32;
+            rem = cur % 10;
+        }
+        digits.push(b'0' + rem as u8);
+    }
+
+    if digits.is_empty() {
+        return "0".to_string();
+    }
+
+    digits.reverse();
+    String::from_utf8(digits).unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::{FixedArray4, Param, Type};
+
+    fn params() -> DecodedParams {
+        DecodedParams::from(vec![
+            (
+                Param {
+                    name: "owner".to_string(),
+                    type_: Type::Address,
+                    indexed: None,
+                    docs: None,
+                },
+                Value::Address(FixedArray4([1, 2, 3, 4])),
+            ),
+            (
+                Param {
+                    name: "".to_string(),
+                    type_: Type::Tuple(vec![
+                        ("id".to_string(), Type::U32),
+                        ("title".to_string(), Type::String),
+                    ]),
+                    indexed: None,
+                    docs: None,
+                },
+                Value::Tuple(vec![
+                    ("id".to_string(), Value::U32(60)),
+                    ("title".to_string(), Value::String("olavm".to_string())),
+                ]),
+            ),
+        ])
+    }
+
+    #[test]
+    fn format_params_truncated() {
+        assert_eq!(
+            ValueFormatter::new().format_params(&params()),
+            "owner: address = 0x00000000…00000004\n\
+             [1]: (u32,string) = { id: u32 = 60, title: string = \"olavm\" }\n"
+        );
+    }
+
+    #[test]
+    fn format_params_full() {
+        assert_eq!(
+            ValueFormatter::new()
+                .full(true)
+                .format_value(&Value::Address(FixedArray4([1, 2, 3, 4]))),
+            FixedArray4([1, 2, 3, 4]).to_hex_string()
+        );
+    }
+
+    #[test]
+    fn format_fields_truncated() {
+        let fields = Value::Fields((0..10).collect());
+
+        assert_eq!(
+            ValueFormatter::new().format_value(&fields),
+            "[0, 1, 2, 3, 4, 5, 6, 7, … 2 more]"
+        );
+        assert_eq!(
+            ValueFormatter::new().full(true).format_value(&fields),
+            "[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn fields_encodings() {
+        let blob = Value::Tuple(vec![("blob".to_string(), Value::Fields(vec![1, u64::MAX]))]);
+        let params = DecodedParams::from(vec![(
+            Param {
+                name: "blobs".to_string(),
+                type_: Type::Array(Box::new(blob.type_of())),
+                indexed: None,
+                docs: None,
+            },
+            Value::Array(vec![blob.clone()], blob.type_of()),
+        )]);
+
+        assert_eq!(
+            ValueFormatter::new().to_json(&params),
+            serde_json::to_value(&params).unwrap()
+        );
+
+        for (fields, packed) in [
+            (FieldsEncoding::Hex, "0x0000000000000001ffffffffffffffff"),
+            (FieldsEncoding::Base64, "AAAAAAAAAAH//////////w=="),
+        ] {
+            let json = ValueFormatter::new().fields(fields).to_json(&params);
+            assert_eq!(
+                json[0]["value"]["Array"][0][0]["Tuple"][0][1]["Fields"],
+                packed
+            );
+            assert_eq!(
+                serde_json::from_value::<Value>(json[0]["value"].clone()).unwrap(),
+                params[0].value
+            );
+        }
+
+        assert_eq!(
+            ValueFormatter::new()
+                .fields(FieldsEncoding::Base64)
+                .format_value(&Value::Fields(vec![1])),
+            "AAAAAAAAAAE="
+        );
+
+        let optional = DecodedParams::from(vec![(
+            Param {
+                name: "memo".to_string(),
+                type_: Type::Option(Box::new(Type::Fields)),
+                indexed: None,
+                docs: None,
+            },
+            Value::Some(Box::new(Value::Fields(vec![1]))),
+        )]);
+        let json = ValueFormatter::new()
+            .fields(FieldsEncoding::Hex)
+            .to_json(&optional);
+        assert_eq!(json[0]["value"]["Some"]["Fields"], "0x0000000000000001");
+        assert_eq!(
+            serde_json::from_value::<Value>(json[0]["value"].clone()).unwrap(),
+            optional[0].value
+        );
+
+        let map = Value::Map(
+            vec![(Value::Fields(vec![1]), Value::Fields(vec![2]))],
+            Type::Fields,
+            Type::Fields,
+        );
+        let params = DecodedParams::from(vec![(
+            Param {
+                name: "blobs".to_string(),
+                type_: map.type_of(),
+                indexed: None,
+                docs: None,
+            },
+            map,
+        )]);
+        assert_eq!(
+            ValueFormatter::new().to_json(&params),
+            serde_json::to_value(&params).unwrap()
+        );
+        let json = ValueFormatter::new()
+            .fields(FieldsEncoding::Hex)
+            .to_json(&params);
+        assert_eq!(
+            json[0]["value"]["Map"][0][0],
+            serde_json::json!([
+                { "Fields": "0x0000000000000001" },
+                { "Fields": "0x0000000000000002" }
+            ])
+        );
+        assert_eq!(
+            serde_json::from_value::<Value>(json[0]["value"].clone()).unwrap(),
+            params[0].value
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn json_object_keys() {
+        let mut params = params().to_vec();
+        params.push(params[0].clone());
+        let params = DecodedParams::from(
+            params
+                .into_iter()
+                .map(|decoded| (decoded.param, decoded.value))
+                .collect::<Vec<_>>(),
+        );
+
+        let json = ValueFormatter::new().to_json_object(&params);
+
+        let owner = FixedArray4([1, 2, 3, 4]).to_hex_string();
+        assert_eq!(
+            json.value,
+            serde_json::json!({
+                "owner": owner,
+                "_1": { "id": 60, "title": "olavm" },
+                "owner_2": owner,
+            })
+        );
+        assert_eq!(
+            json.keys,
+            [
+                ("/owner", "owner"),
+                ("/_1", ""),
+                ("/_1/id", "id"),
+                ("/_1/title", "title"),
+                ("/owner_2", "owner"),
+            ]
+            .map(|(path, name)| (path.to_string(), name.to_string()))
+        );
+
+        assert_eq!(
+            json_keys(&["a b", "a_b", "a-b", "_1", ""]),
+            ["a_b", "a_b_2", "a_b_3", "_1", "_4"]
+        );
+    }
+
+    #[test]
+    fn format_colored() {
+        assert_eq!(
+            ValueFormatter::new()
+                .color(true)
+                .format_value(&Value::U32(1)),
+            "\x1b[33m1\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn format_units() {
+        let formatter = ValueFormatter::new()
+            .units("amount", Units::new(9).symbol("OLA"))
+            .units("price", Units::new(2));
+
+        assert_eq!(
+            formatter.format_named("amount", &Value::U32(1_500_000_000)),
+            "1.5 OLA"
+        );
+        assert_eq!(
+            formatter.format_named("amount", &Value::U32(5)),
+            "0.000000005 OLA"
+        );
+        assert_eq!(formatter.format_named("price", &Value::Field(1200)), "12");
+        assert_eq!(formatter.format_named("other", &Value::U32(1200)), "1200");
+
+        let big = Value::U256(crate::FixedArray8([0, 0, 0, 0, 0, 0, 1, 0]));
+        assert_eq!(formatter.format_named("price", &big), "42949672.96");
+
+        let tuple = Value::Tuple(vec![("amount".to_string(), Value::U32(2_000_000_000))]);
+        assert_eq!(formatter.format_value(&tuple), "{ amount: u32 = 2 OLA }");
+
+        let formatter = ValueFormatter::new().units("amount", Units::new(u32::MAX));
+        assert_eq!(
+            formatter.format_named("amount", &Value::U32(1500)),
+            "1.5e-4294967292"
+        );
+        assert_eq!(formatter.format_named("amount", &Value::U32(0)), "0");
+        assert_eq!(
+            ValueFormatter::new()
+                .units("amount", Units::new(MAX_DECIMALS + 1).symbol("OLA"))
+                .format_named("amount", &Value::U32(7)),
+            "7e-78 OLA"
+        );
+    }
+
+    #[test]
+    fn u256_decimal() {
+        assert_eq!(u256_to_decimal(&[0; 8]), "0");
+        assert_eq!(
+            u256_to_decimal(&[u32::MAX as u64; 8]),
+            "115792089237316195423570985008687907853269984665640564039457584007913129639935"
+        );
+    }
+}
@@ -0,0 +1,116 @@
+//! Hash backends: keccak256 for function selectors, poseidon for event
+//! topics and typed data.
+
+use anyhow::Result;
+use mini_goldilocks::poseidon::unsafe_poseidon_bytes_auto_padded;
+
+use crate::{FixedArray4, Value};
+
+#[cfg(feature = "abi")]
+use crate::SelectorEndianness;
+
+/// Computes the method id (function selector) of a function signature.
+#[cfg(feature = "abi")]
+pub(crate) fn method_id_of(signature: &str) -> u64 {
+    method_id_with_endianness(signature, SelectorEndianness::BigEndian)
+}
+
+/// Computes the method id of a function signature, reading the first 4
+/// bytes of its keccak256 hash in the given byte order.
+#[cfg(feature = "abi")]
+pub(crate) fn method_id_with_endianness(signature: &str, endianness: SelectorEndianness) -> u64 {
+    let prefix = keccak256(signature)[0..4].try_into().unwrap();
+    match endianness {
+        SelectorEndianness::BigEndian => u32::from_be_bytes(prefix) as u64,
+        SelectorEndianness::LittleEndian => u32::from_le_bytes(prefix) as u64,
+    }
+}
+
+/// Computes the full-width method id of a function signature, its whole
+/// keccak256 hash.
+#[cfg(feature = "abi")]
+pub(crate) fn method_id_full_of(signature: &str) -> FixedArray4 {
+    FixedArray4::from_bytes32(&keccak256(signature))
+}
+
+#[cfg(feature = "abi")]
+fn keccak256(signature: &str) -> [u8; 32] {
+    use tiny_keccak::{Hasher, Keccak};
+
+    let mut keccak_out = [0u8; 32];
+    let mut hasher = Keccak::v256();
+    hasher.update(signature.as_bytes());
+    hasher.finalize(&mut keccak_out);
+    keccak_out
+}
+
+/// Computes the topic hash of an event signature.
+#[cfg(feature = "abi")]
+pub(crate) fn topic_of(signature: &str) -> FixedArray4 {
+    FixedArray4(unsafe_poseidon_bytes_auto_padded(signature.as_bytes()))
+}
+
+/// Hashes a struct value for typed structured data signing.
+///
+/// The hash is the poseidon hash of the type hash, i.e. the poseidon hash of
+/// [`Type::struct_signature`](crate::Type::struct_signature), followed by the encoded members. Members are
+/// encoded as by [`Value::encode`], except strings, fields and arrays which
+/// are replaced by the poseidon hash of their encoding. Words are hashed as
+/// big-endian bytes.
+pub fn hash_struct(name: &str, value: &Value) -> Result<FixedArray4> {
+    let signature = value.type_of().struct_signature(name)?;
+
+    let members = match value {
+        Value::Tuple(members) => members,
+        _ => unreachable!("struct signatures are only defined for tuples"),
+    };
+
+    let mut words = unsafe_poseidon_bytes_auto_padded(signature.as_bytes()).to_vec();
+    for (_, member) in members {
+        let encoded = Value::encode(std::slice::from_ref(member));
+
+        match member {
+            Value::String(_) | Value::Fields(_) | Value::FixedArray(..) | Value::Array(..) => {
+                words.extend(poseidon_words(&encoded).0)
+            }
+            _ => words.extend(encoded),
+        }
+    }
+
+    Ok(poseidon_words(&words))
+}
+
+/// Poseidon hash of words, taken as big-endian bytes.
+pub(crate) fn poseidon_words(words: &[u64]) -> FixedArray4 {
+    let bytes = words
+        .iter()
+        .flat_map(|word| word.to_be_bytes())
+        .collect::<Vec<_>>();
+
+    FixedArray4(unsafe_poseidon_bytes_auto_padded(&bytes))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn hash_struct_members() {
+        let order = |id, title: &str| {
+            Value::Tuple(vec![
+                ("id".to_string(), Value::U32(id)),
+                ("title".to_string(), Value::String(title.to_string())),
+            ])
+        };
+
+        let hash = hash_struct("Order", &order(1, "ola")).unwrap();
+
+        assert_eq!(hash, hash_struct("Order", &order(1, "ola")).unwrap());
+        assert_ne!(hash, hash_struct("Order", &order(2, "ola")).unwrap());
+        assert_ne!(hash, hash_struct("Order", &order(1, "olb")).unwrap());
+        assert_ne!(hash, hash_struct("Sale", &order(1, "ola")).unwrap());
+        assert!(hash_struct("Order", &Value::U32(1)).is_err());
+    }
+}
@@ -0,0 +1,493 @@
+use crate::{
+    error::{AbiError, Result},
+    Abi, Constructor, Error, Event, Function, Param, StateMutability, Type, MAX_TYPE_DEPTH,
+};
+
+impl Abi {
+    /// Parses a human-readable ABI, one entry per line:
+    ///
+    /// ```text
+    /// constructor(string name)
+    /// function transfer(address to, u32 amount) returns (bool)
+    /// function getBook(u32 id) view returns ((u32 id, string name) book)
+    /// event Transfer(address indexed from, address indexed to, u32 amount)
+    /// error InsufficientBalance(u32 available, u32 required)
+    /// ```
+    ///
+    /// Types are written as in signatures, tuples as `(T1 a, T2 b)` or
+    /// `tuple(T1 a, T2 b)`, optional types as `T?`, maps as `map(K, V)`, and
+    /// param names are optional. Function modifiers set the state mutability (`pure`, `view`,
+    /// `nonpayable` or `payable`) and are otherwise ignored, events may be
+    /// followed by `anonymous`. Blank lines and `//` comments are skipped.
+    ///
+    /// ```
+    /// use ola_lang_abi::{Abi, Type};
+    ///
+    /// let abi = Abi::from_human_readable(&[
+    ///     "function transfer(address to, u32 amount) returns (bool)",
+    ///     "event Transfer(address indexed from, address indexed to, u32 amount)",
+    /// ]).unwrap();
+    ///
+    /// assert_eq!(abi.functions[0].signature(), "transfer(address,u32)");
+    /// assert_eq!(abi.functions[0].outputs[0].type_, Type::Bool);
+    /// assert_eq!(abi.events[0].inputs[1].indexed, Some(true));
+    /// ```
+    pub fn from_human_readable<S: AsRef<str>>(lines: &[S]) -> Result<Abi> {
+        let mut abi = Abi::default();
+
+        for (i, line) in lines.iter().enumerate() {
+            let line = line.as_ref();
+            let line = line.find("//").map_or(line, |comment| &line[..comment]);
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            parse_entry(&mut abi, line)
+                .map_err(|msg| AbiError::InvalidAbi(format!("line {}: {}", i + 1, msg)))?;
+        }
+
+        Ok(abi)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    Number(u64),
+    Punct(char),
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Ident(ident) => write!(f, "{:?}", ident),
+            Token::Number(n) => write!(f, "{}", n),
+            Token::Punct(c) => write!(f, "{:?}", c),
+        }
+    }
+}
+
+fn tokenize(line: &str) -> std::result::Result<Vec<Token>, String> {
+    let mut tokens = vec![];
+    let mut chars = line.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_alphabetic() || c == '_' || c == '$' {
+            let mut ident = String::new();
+            while let Some(&c) = chars.peek() {
+                if !(c.is_ascii_alphanumeric() || c == '_' || c == '$') {
+                    break;
+                }
+                ident.push(c);
+                chars.next();
+            }
+            tokens.push(Token::Ident(ident));
+        } else if c.is_ascii_digit() {
+            let mut digits = String::new();
+            while let Some(&c) = chars.peek() {
+                if !c.is_ascii_digit() {
+                    break;
+                }
+                digits.push(c);
+                chars.next();
+            }
+            let n = digits
+                .parse()
+                .map_err(|_| format!("array size {} is too large", digits))?;
+            tokens.push(Token::Number(n));
+        } else if "(),[]?".contains(c) {
+            tokens.push(Token::Punct(c));
+            chars.next();
+        } else {
+            return Err(format!("unexpected character {:?}", c));
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Recursive descent parser over the tokens of a line.
+struct Parser {
+    tokens: Vec<Token>,
+    at: usize,
+}
+
+type ParseResult<T> = std::result::Result<T, String>;
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.at)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.at).cloned();
+        self.at += 1;
+        token
+    }
+
+    fn unexpected<T>(&self, expected: &str) -> ParseResult<T> {
+        match self.peek() {
+            Some(token) => Err(format!("expected {}, got {}", expected, token)),
+            None => Err(format!("expected {}, got end of line", expected)),
+        }
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        let matches = self.peek() == Some(token);
+        if matches {
+            self.at += 1;
+        }
+        matches
+    }
+
+    fn expect(&mut self, c: char) -> ParseResult<()> {
+        if self.eat(&Token::Punct(c)) {
+            Ok(())
+        } else {
+            self.unexpected(&format!("{:?}", c))
+        }
+    }
+
+    fn ident(&mut self, expected: &str) -> ParseResult<String> {
+        match self.peek() {
+            Some(Token::Ident(ident)) => {
+                let ident = ident.clone();
+                self.at += 1;
+                Ok(ident)
+            }
+            _ => self.unexpected(expected),
+        }
+    }
+
+    fn keyword(&mut self, keyword: &str) -> bool {
+        self.eat(&Token::Ident(keyword.to_string()))
+    }
+
+    /// Parses `(param, ...)`, each `type [indexed] [name]`, nested `depth`
+    /// types deep.
+    fn params(&mut self, allow_indexed: bool, depth: usize) -> ParseResult<Vec<Param>> {
+        self.expect('(')?;
+
+        let mut params = vec![];
+        if self.eat(&Token::Punct(')')) {
+            return Ok(params);
+        }
+
+        loop {
+            let type_ = self.type_(depth)?;
+            let indexed = allow_indexed && self.keyword("indexed");
+            let name = match self.peek() {
+                Some(Token::Ident(_)) => self.ident("param name")?,
+                _ => String::new(),
+            };
+
+            params.push(Param {
+                name,
+                type_,
+                indexed: allow_indexed.then_some(indexed),
+                docs: None,
+            });
+
+            if !self.eat(&Token::Punct(',')) {
+                self.expect(')')?;
+                return Ok(params);
+            }
+        }
+    }
+
+    fn type_(&mut self, mut depth: usize) -> ParseResult<Type> {
+        let mut ty = match self.peek() {
+            Some(Token::Punct('(')) => self.tuple(depth)?,
+            Some(Token::Ident(ident)) if ident == "tuple" => {
+                self.at += 1;
+                self.tuple(depth)?
+            }
+            Some(Token::Ident(ident)) if ident == "map" => {
+                self.at += 1;
+                self.map(depth)?
+            }
+            Some(Token::Ident(ident)) => {
+                let ty = match ident.as_str() {
+                    "u32" => Type::U32,
+                    "u256" => Type::U256,
+                    "field" => Type::Field,
+                    "i32" => Type::I32,
+                    "i64" => Type::I64,
+                    "hash" => Type::Hash,
+                    "address" => Type::Address,
+                    "bool" => Type::Bool,
+                    "string" => Type::String,
+                    "fields" => Type::Fields,
+                    _ => return Err(format!("unknown type {:?}", ident)),
+                };
+                self.at += 1;
+                ty
+            }
+            _ => return self.unexpected("type"),
+        };
+
+        while let Some(Token::Punct(c @ ('[' | '?'))) = self.peek() {
+            let optional = *c == '?';
+            self.at += 1;
+            depth += 1;
+            if depth > MAX_TYPE_DEPTH {
+                return Err(too_deep());
+            }
+
+            if optional {
+                ty = Type::Option(Box::new(ty));
+                continue;
+            }
+
+            ty = match self.next() {
+                Some(Token::Punct(']')) => Type::Array(Box::new(ty)),
+                Some(Token::Number(size)) => {
+                    self.expect(']')?;
+                    Type::FixedArray(Box::new(ty), size)
+                }
+                _ => {
+                    self.at -= 1;
+                    return self.unexpected("array size or ']'");
+                }
+            };
+        }
+
+        Ok(ty)
+    }
+
+    fn tuple(&mut self, depth: usize) -> ParseResult<Type> {
+        if depth >= MAX_TYPE_DEPTH {
+            return Err(too_deep());
+        }
+
+        Ok(Type::Tuple(
+            self.params(false, depth + 1)?
+                .into_iter()
+                .map(|param| (param.name, param.type_))
+                .collect(),
+        ))
+    }
+
+    /// Parses `(K, V)` following `map`.
+    fn map(&mut self, depth: usize) -> ParseResult<Type> {
+        if depth >= MAX_TYPE_DEPTH {
+            return Err(too_deep());
+        }
+
+        self.expect('(')?;
+        let key = self.type_(depth + 1)?;
+        self.expect(',')?;
+        let value = self.type_(depth + 1)?;
+        self.expect(')')?;
+
+        Ok(Type::Map(Box::new(key), Box::new(value)))
+    }
+
+    /// Skips modifiers up to `stop` or the end of the line, returning the
+    /// last state mutability among them.
+    fn modifiers(&mut self, stop: &str) -> Option<StateMutability> {
+        let mut mutability = None;
+        while let Some(Token::Ident(ident)) = self.peek() {
+            if ident == stop {
+                break;
+            }
+            mutability = ident.parse().ok().or(mutability);
+            self.at += 1;
+        }
+
+        mutability
+    }
+
+    fn end(&self) -> ParseResult<()> {
+        match self.peek() {
+            None => Ok(()),
+            Some(_) => self.unexpected("end of line"),
+        }
+    }
+}
+
+fn too_deep() -> String {
+    format!("type nests deeper than {} levels", MAX_TYPE_DEPTH)
+}
+
+fn parse_entry(abi: &mut Abi, line: &str) -> ParseResult<()> {
+    let mut parser = Parser {
+        tokens: tokenize(line)?,
+        at: 0,
+    };
+
+    match parser.ident("entry kind")?.as_str() {
+        "function" => {
+            let name = parser.ident("function name")?;
+            let inputs = parser.params(false, 0)?;
+            let state_mutability = parser.modifiers("returns").unwrap_or_default();
+            let outputs = if parser.keyword("returns") {
+                parser.params(false, 0)?
+            } else {
+                vec![]
+            };
+            parser.end()?;
+
+            abi.push_function(Function {
+                name,
+                inputs,
+                outputs,
+                selector: None,
+                state_mutability,
+                docs: None,
+                provenance: None,
+            });
+        }
+        "event" => {
+            let name = parser.ident("event name")?;
+            let inputs = parser.params(true, 0)?;
+            let anonymous = parser.keyword("anonymous");
+            parser.end()?;
+
+            abi.push_event(Event {
+                name,
+                inputs,
+                anonymous,
+                provenance: None,
+            });
+        }
+        "error" => {
+            let name = parser.ident("error name")?;
+            let inputs = parser.params(false, 0)?;
+            parser.end()?;
+
+            abi.push_error(Error { name, inputs });
+        }
+        "constructor" => {
+            if abi.constructor.is_some() {
+                return Err("duplicate constructor".to_string());
+            }
+
+            let inputs = parser.params(false, 0)?;
+            parser.modifiers("");
+            parser.end()?;
+
+            abi.constructor = Some(Constructor { inputs, docs: None });
+        }
+        kind => {
+            return Err(format!(
+                "unknown entry kind {:?}, expected function, event, error or constructor",
+                kind
+            ))
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn human_readable_abi() {
+        let abi = Abi::from_human_readable(&[
+            "// BookExample",
+            "constructor(string name) payable",
+            "",
+            "function createBook(u32 id, string name) returns ((u32 id, string name) book)",
+            "function books(u32[] ids, tuple(address owner, u256 price)[2] meta, u32? limit) view",
+            "function tag(map(u32, string[]) labels)",
+            "event BookCreated(u32 indexed id, string indexed name, string author)",
+            "event Raw(fields) anonymous",
+            "error NotFound(u32 id)",
+        ])
+        .unwrap();
+
+        let json: Abi = serde_json::from_str(
+            r#"[
+                { "type": "constructor", "inputs": [{ "name": "name", "type": "string" }] },
+                {
+                    "type": "function",
+                    "name": "createBook",
+                    "inputs": [{ "name": "id", "type": "u32" }, { "name": "name", "type": "string" }],
+                    "outputs": [{
+                        "name": "book",
+                        "type": "tuple",
+                        "components": [{ "name": "id", "type": "u32" }, { "name": "name", "type": "string" }]
+                    }]
+                },
+                {
+                    "type": "function",
+                    "name": "books",
+                    "inputs": [
+                        { "name": "ids", "type": "u32[]" },
+                        {
+                            "name": "meta",
+                            "type": "tuple[2]",
+                            "components": [{ "name": "owner", "type": "address" }, { "name": "price", "type": "u256" }]
+                        },
+                        { "name": "limit", "type": "u32?" }
+                    ],
+                    "outputs": [],
+                    "stateMutability": "view"
+                },
+                {
+                    "type": "function",
+                    "name": "tag",
+                    "inputs": [{ "name": "labels", "type": "map(u32,string[])" }],
+                    "outputs": []
+                },
+                {
+                    "type": "event",
+                    "name": "BookCreated",
+                    "inputs": [
+                        { "name": "id", "type": "u32", "indexed": true },
+                        { "name": "name", "type": "string", "indexed": true },
+                        { "name": "author", "type": "string", "indexed": false }
+                    ],
+                    "anonymous": false
+                },
+                {
+                    "type": "event",
+                    "name": "Raw",
+                    "inputs": [{ "name": "", "type": "fields", "indexed": false }],
+                    "anonymous": true
+                },
+                { "type": "error", "name": "NotFound", "inputs": [{ "name": "id", "type": "u32" }] }
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(abi, json);
+
+        let err = |line: &str| {
+            Abi::from_human_readable(&["", line])
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(err("function f(u33 x)"), r#"line 2: unknown type "u33""#);
+        assert_eq!(
+            err("function f(u32 x"),
+            "line 2: expected ')', got end of line"
+        );
+        assert_eq!(
+            err("function f(u32[x])"),
+            r#"line 2: expected array size or ']', got "x""#
+        );
+        assert_eq!(
+            err("modifier onlyOwner()"),
+            r#"line 2: unknown entry kind "modifier", expected function, event, error or constructor"#
+        );
+        assert_eq!(
+            err("event E() x"),
+            r#"line 2: expected end of line, got "x""#
+        );
+        assert_eq!(
+            err(&format!("function f(u32{})", "[]".repeat(100))),
+            "line 2: type nests deeper than 64 levels"
+        );
+        assert_eq!(
+            err(&format!("function f({})", "(".repeat(100_000))),
+            "line 2: type nests deeper than 64 levels"
+        );
+    }
+}
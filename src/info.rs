@@ -0,0 +1,54 @@
+use crate::alloc_prelude::*;
+use serde::Serialize;
+
+/// Build information, for checking decoder compatibility at runtime.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VersionInfo {
+    /// Crate version.
+    pub version: &'static str,
+    /// Enabled cargo features.
+    pub features: Vec<&'static str>,
+    /// Hash backend of function selectors.
+    pub selector_hash: &'static str,
+    /// Hash backend of event topics.
+    pub topic_hash: &'static str,
+    /// Supported ABI types.
+    pub types: Vec<&'static str>,
+}
+
+/// Returns the version, features and hash backends of this build.
+///
+/// ```
+/// let info = ola_lang_abi::version_info();
+///
+/// assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+/// println!("{}", serde_json::to_string(&info).unwrap());
+/// ```
+pub fn version_info() -> VersionInfo {
+    let features = [
+        ("abi", cfg!(feature = "abi")),
+        ("cache", cfg!(feature = "cache")),
+        ("cli", cfg!(feature = "cli")),
+        ("codegen", cfg!(feature = "codegen")),
+        ("ethabi-compat", cfg!(feature = "ethabi-compat")),
+        ("hash", cfg!(feature = "hash")),
+        ("json", cfg!(feature = "json")),
+        ("rand", cfg!(feature = "rand")),
+        ("wasm", cfg!(feature = "wasm")),
+    ];
+
+    VersionInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        features: features
+            .into_iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(feature, _)| feature)
+            .collect(),
+        selector_hash: "keccak256",
+        topic_hash: "poseidon",
+        types: vec![
+            "u32", "u256", "field", "hash", "address", "bool", "string", "fields", "T[k]", "T[]",
+            "tuple",
+        ],
+    }
+}
@@ -0,0 +1,345 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{Abi, DecodedParams, Function, Param, Type, Value};
+
+/// Word layout of the functions of an ABI, see [`Abi::emit_layout_spec`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LayoutSpec {
+    /// Function layouts, in ABI order.
+    pub functions: Vec<FunctionLayout>,
+}
+
+/// Word layout of a function's calldata, `[params..., param-len, method_id]`,
+/// and output data, `[params..., param-len]`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FunctionLayout {
+    /// Function signature.
+    pub signature: String,
+    /// Function method id, the last calldata word.
+    pub method_id: u64,
+    /// Input parameter layouts.
+    pub inputs: Vec<ParamLayout>,
+    /// Words of the inputs before the first dynamic one, whose offsets are
+    /// the same for every call.
+    pub static_prefix: u64,
+    /// Output parameter layouts.
+    pub outputs: Vec<ParamLayout>,
+    /// Words of the outputs before the first dynamic one.
+    pub output_static_prefix: u64,
+}
+
+/// Word layout of a parameter or tuple member.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParamLayout {
+    /// Parameter name.
+    pub name: String,
+    /// Parameter type, e.g. `(u32,string)[]`.
+    #[serde(rename = "type")]
+    pub type_: String,
+    /// Word offset from the start of the data, `None` past the first
+    /// dynamic parameter, where it depends on the values.
+    pub offset: Option<u64>,
+    /// Number of words, `None` for dynamic types.
+    pub size: Option<u64>,
+    /// Member layouts of tuples, offsets being from the start of the data
+    /// too.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub members: Vec<ParamLayout>,
+}
+
+/// Word ranges of decoded params in their calldata or output data, as
+/// witness layout hints for the prover, see [`DecodedParams::witness_layout`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WitnessLayout {
+    /// Param ranges, in ABI order.
+    pub params: Vec<WordRange>,
+    /// Words of the params, the param-len word following them.
+    pub param_len: u64,
+}
+
+/// Words of a decoded param or tuple member.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WordRange {
+    /// Parameter name.
+    pub name: String,
+    /// Parameter type, e.g. `(u32,string)[]`.
+    #[serde(rename = "type")]
+    pub type_: String,
+    /// Offset of the first word from the start of the data.
+    pub start: u64,
+    /// Offset past the last word.
+    pub end: u64,
+    /// Member ranges of tuples.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub members: Vec<WordRange>,
+}
+
+impl DecodedParams {
+    /// Locates every param, and tuple member, in the words the params were
+    /// decoded from, with the default encoding options. Unlike
+    /// [`Abi::emit_layout_spec`], the ranges of values after dynamic ones
+    /// are known; it serializes to JSON.
+    ///
+    /// ```
+    /// use ola_lang_abi::Abi;
+    ///
+    /// let abi = Abi::from_human_readable(&["function createBook(u32 id, string name)"]).unwrap();
+    /// let (_, params) = abi.decode_input_from_slice(&[60, 3, 111, 108, 97, 4, 0x072f7e97]).unwrap();
+    ///
+    /// let layout = params.witness_layout();
+    /// assert_eq!((layout.params[1].start, layout.params[1].end), (1, 5));
+    /// assert_eq!(layout.param_len, 5);
+    /// ```
+    pub fn witness_layout(&self) -> WitnessLayout {
+        let mut start = 0;
+        let params = self
+            .iter()
+            .map(|decoded| {
+                word_range(
+                    &decoded.param.name,
+                    &decoded.param.type_,
+                    &decoded.value,
+                    &mut start,
+                )
+            })
+            .collect();
+
+        WitnessLayout {
+            params,
+            param_len: start,
+        }
+    }
+}
+
+/// Locates a value starting at `start`, moving `start` past it.
+fn word_range(name: &str, ty: &Type, value: &Value, start: &mut u64) -> WordRange {
+    let end = *start + Value::encoded_len(std::slice::from_ref(value)) as u64;
+
+    let members = match (ty, value) {
+        (Type::Tuple(tys), Value::Tuple(values)) => {
+            let mut at = *start;
+            tys.iter()
+                .zip(values)
+                .map(|((name, ty), (_, value))| word_range(name, ty, value, &mut at))
+                .collect()
+        }
+        _ => vec![],
+    };
+    let range = WordRange {
+        name: name.to_string(),
+        type_: ty.to_string(),
+        start: *start,
+        end,
+        members,
+    };
+
+    *start = end;
+    range
+}
+
+impl Abi {
+    /// Describes how the parameters of every function map to calldata and
+    /// output words, as a machine-readable spec for documentation and other
+    /// implementations; it serializes to JSON.
+    ///
+    /// Values are laid out one after the other, dynamic ones (strings,
+    /// fields and arrays) as their length word followed by their elements,
+    /// so only the offsets up to the first dynamic parameter are static.
+    ///
+    /// ```
+    /// use ola_lang_abi::Abi;
+    ///
+    /// let abi: Abi = serde_json::from_str(r#"[{
+    ///     "type": "function",
+    ///     "name": "createBook",
+    ///     "inputs": [
+    ///         { "name": "id", "type": "u32" },
+    ///         { "name": "name", "type": "string" },
+    ///         { "name": "owner", "type": "address" }
+    ///     ],
+    ///     "outputs": []
+    /// }]"#).unwrap();
+    ///
+    /// let spec = abi.emit_layout_spec();
+    /// let inputs = &spec.functions[0].inputs;
+    /// assert_eq!((inputs[1].offset, inputs[1].size), (Some(1), None));
+    /// assert_eq!((inputs[2].offset, inputs[2].size), (None, Some(4)));
+    /// assert_eq!(spec.functions[0].static_prefix, 1);
+    /// ```
+    pub fn emit_layout_spec(&self) -> LayoutSpec {
+        LayoutSpec {
+            functions: self.functions.iter().map(function_layout).collect(),
+        }
+    }
+}
+
+fn function_layout(f: &Function) -> FunctionLayout {
+    let inputs = params_layout(&f.inputs);
+    let outputs = params_layout(&f.outputs);
+
+    FunctionLayout {
+        signature: f.signature(),
+        method_id: f.method_id(),
+        static_prefix: static_prefix(&inputs),
+        inputs,
+        output_static_prefix: static_prefix(&outputs),
+        outputs,
+    }
+}
+
+fn params_layout(params: &[Param]) -> Vec<ParamLayout> {
+    let values = params
+        .iter()
+        .map(|Param { name, type_, .. }| (name.as_str(), type_))
+        .collect::<Vec<_>>();
+
+    layout(&values, Some(0))
+}
+
+/// Lays out values one after the other from `offset`.
+fn layout(values: &[(&str, &Type)], mut offset: Option<u64>) -> Vec<ParamLayout> {
+    values
+        .iter()
+        .map(|&(name, ty)| {
+            let size = ty.static_size();
+            let members = match ty {
+                Type::Tuple(members) => {
+                    let members = members
+                        .iter()
+                        .map(|(name, ty)| (name.as_str(), ty))
+                        .collect::<Vec<_>>();
+                    layout(&members, offset)
+                }
+                _ => vec![],
+            };
+            let layout = ParamLayout {
+                name: name.to_string(),
+                type_: ty.to_string(),
+                offset,
+                size,
+                members,
+            };
+
+            // the offset of the first dynamic value is still known, the
+            // ones after it aren't
+            offset = offset.zip(size).and_then(|(at, size)| at.checked_add(size));
+
+            layout
+        })
+        .collect()
+}
+
+/// Words before the first dynamic parameter.
+fn static_prefix(params: &[ParamLayout]) -> u64 {
+    params
+        .iter()
+        .find(|param| param.size.is_none())
+        .or(params.last())
+        .map_or(0, |param| {
+            param.offset.unwrap_or(0) + param.size.unwrap_or(0)
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{FixedArray4, FixedArray8, Value};
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn layout_spec() {
+        let abi: Abi = serde_json::from_str(
+            r#"[{
+                "type": "function",
+                "name": "createBook",
+                "inputs": [
+                    { "name": "price", "type": "u256" },
+                    {
+                        "name": "meta",
+                        "type": "tuple",
+                        "components": [
+                            { "name": "owner", "type": "address" },
+                            { "name": "title", "type": "string" },
+                            { "name": "id", "type": "u32" }
+                        ]
+                    },
+                    { "name": "ok", "type": "bool" }
+                ],
+                "outputs": [{ "name": "ids", "type": "u32[2]" }]
+            }]"#,
+        )
+        .unwrap();
+
+        let f = &abi.emit_layout_spec().functions[0];
+        assert_eq!(f.method_id, abi.functions[0].method_id());
+        assert_eq!(f.static_prefix, 8);
+        assert_eq!(f.output_static_prefix, 2);
+
+        assert_eq!(
+            serde_json::to_value(&f.inputs).unwrap(),
+            serde_json::json!([
+                { "name": "price", "type": "u256", "offset": 0, "size": 8 },
+                {
+                    "name": "meta",
+                    "type": "(address,string,u32)",
+                    "offset": 8,
+                    "size": null,
+                    "members": [
+                        { "name": "owner", "type": "address", "offset": 8, "size": 4 },
+                        { "name": "title", "type": "string", "offset": 12, "size": null },
+                        { "name": "id", "type": "u32", "offset": null, "size": 1 }
+                    ]
+                },
+                { "name": "ok", "type": "bool", "offset": null, "size": 1 }
+            ])
+        );
+
+        // the words are where the encoder puts them
+        let calldata = abi
+            .encode_input_with_signature(
+                &f.signature,
+                &[
+                    Value::U256(FixedArray8::from(7u64)),
+                    Value::Tuple(vec![
+                        (
+                            "owner".to_string(),
+                            Value::Address(FixedArray4([1, 2, 3, 4])),
+                        ),
+                        ("title".to_string(), Value::String("ola".to_string())),
+                        ("id".to_string(), Value::U32(5)),
+                    ]),
+                    Value::Bool(true),
+                ],
+            )
+            .unwrap();
+        assert_eq!(calldata[8..12], [1, 2, 3, 4]);
+        assert_eq!(calldata[12], 3);
+
+        let (_, params) = abi.decode_input_from_slice(&calldata).unwrap();
+        let layout = params.witness_layout();
+        assert_eq!(
+            serde_json::to_value(&layout).unwrap(),
+            serde_json::json!({
+                "params": [
+                    { "name": "price", "type": "u256", "start": 0, "end": 8 },
+                    {
+                        "name": "meta",
+                        "type": "(address,string,u32)",
+                        "start": 8,
+                        "end": 17,
+                        "members": [
+                            { "name": "owner", "type": "address", "start": 8, "end": 12 },
+                            { "name": "title", "type": "string", "start": 12, "end": 16 },
+                            { "name": "id", "type": "u32", "start": 16, "end": 17 }
+                        ]
+                    },
+                    { "name": "ok", "type": "bool", "start": 17, "end": 18 }
+                ],
+                "param_len": 18
+            })
+        );
+        assert_eq!(calldata[layout.param_len as usize], layout.param_len);
+        assert_eq!(calldata[17..18], [1]);
+    }
+}
@@ -1,13 +1,170 @@
 //! Ethereum Smart Contracts ABI (abstract binary interface) utility library.
+//!
+//! The codec (types, values, params) has no optional dependencies. Cargo
+//! features layer the rest on top of it:
+//!
+//! - `hash`: keccak256 selectors and poseidon topics and typed data hashes.
+//! - `json`: JSON rendering of decoded values.
+//! - `abi` (default): ABI JSON files, functions, events and errors, and
+//!   everything built on them; implies `hash` and `json`.
+//! - `codegen`: Rust source generation from ABIs, for build scripts.
+//! - `derive`: `#[derive(AbiEncode, AbiDecode)]` mapping structs to tuples.
+//! - `cache`, `cli`, `ethabi-compat`, `rand`, `wasm`: see `Cargo.toml`.
+//! - `std` (default): implied by all of the above. Without it the codec is
+//!   `no_std` and only needs `alloc`, e.g. for Ola VM guest programs.
+//!
+//! Only the codec builds without `std`: types, params, values, the
+//! encoder and decoder, [`codec`], [`calldata`] and the L1/L2 messages.
+//! `hash`, and so `abi`, need `std`, as the poseidon backend of topics
+//! (`mini-goldilocks`) depends on `num` with its `std` default features;
+//! guest programs encode and decode calls from types and method ids known
+//! ahead of time.
 
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "abi")]
 mod abi;
+#[cfg(feature = "abi")]
+mod abi_store;
+#[cfg(feature = "abi")]
+mod artifact;
+#[cfg(feature = "cache")]
+mod cache;
+pub mod calldata;
+pub mod codec;
+#[cfg(feature = "codegen")]
+pub mod codegen;
+mod convert;
+#[cfg(feature = "abi")]
+mod de;
+mod decoder;
+#[cfg(feature = "std")]
+mod diff;
+mod encoder;
+#[cfg(feature = "abi")]
+mod envelope;
+mod error;
+#[cfg(feature = "ethabi-compat")]
+mod ethabi_compat;
+#[cfg(feature = "abi")]
 mod event;
+#[cfg(feature = "std")]
+mod format;
+#[cfg(feature = "hash")]
+mod hash;
+#[cfg(feature = "abi")]
+mod human_readable;
+mod info;
+#[cfg(feature = "abi")]
+mod layout;
+#[cfg(feature = "abi")]
+mod log_decoder;
+mod messages;
+#[cfg(feature = "abi")]
+mod nested;
 mod params;
+pub mod prelude;
+#[cfg(feature = "abi")]
+mod raw_log;
+#[cfg(feature = "abi")]
+mod reencode;
+#[cfg(feature = "abi")]
+mod registry;
+#[cfg(feature = "abi")]
+mod remap;
+#[cfg(feature = "abi")]
+mod selector_map;
+#[cfg(feature = "abi")]
+mod template;
+#[cfg(feature = "abi")]
+mod transaction;
+#[cfg(feature = "hash")]
+mod typed_data;
 mod types;
+#[cfg(feature = "json")]
+mod value_json;
+mod value_ref;
 mod values;
+mod varint;
+#[cfg(feature = "abi")]
+mod vectors;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+mod word_order;
 
+#[cfg(feature = "abi")]
 pub use abi::*;
+#[cfg(feature = "abi")]
+pub use abi_store::*;
+#[cfg(feature = "abi")]
+pub use artifact::*;
+#[cfg(feature = "cache")]
+pub use cache::*;
+#[cfg(feature = "json")]
+pub use codec::{decode_values, encode_values};
+pub use convert::*;
+#[cfg(feature = "abi")]
+pub use de::from_calldata;
+pub use decoder::*;
+#[cfg(feature = "std")]
+pub use diff::*;
+pub use encoder::*;
+#[cfg(feature = "abi")]
+pub use envelope::*;
+pub use error::AbiError;
+#[cfg(feature = "abi")]
 pub use event::*;
+#[cfg(feature = "std")]
+pub use format::*;
+#[cfg(feature = "hash")]
+pub use hash::hash_struct;
+pub use info::*;
+#[cfg(feature = "abi")]
+pub use layout::*;
+#[cfg(feature = "abi")]
+pub use log_decoder::*;
+pub use messages::*;
+#[cfg(feature = "abi")]
+pub use nested::*;
 pub use params::*;
+#[cfg(feature = "abi")]
+pub use raw_log::*;
+#[cfg(feature = "abi")]
+pub use registry::*;
+#[cfg(feature = "abi")]
+pub use template::*;
+#[cfg(feature = "abi")]
+pub use transaction::*;
+#[cfg(feature = "hash")]
+pub use typed_data::*;
 pub use types::*;
+pub use value_ref::*;
 pub use values::*;
+#[cfg(feature = "abi")]
+pub use vectors::*;
+pub use word_order::*;
+
+#[cfg(feature = "derive")]
+pub use ola_lang_abi_derive::{AbiDecode, AbiEncode};
+
+/// `alloc` counterparts of the `std` prelude items, imported by the modules
+/// of the `no_std` codec.
+mod alloc_prelude {
+    pub use alloc::{
+        borrow::ToOwned,
+        boxed::Box,
+        format,
+        string::{String, ToString},
+        vec,
+        vec::Vec,
+    };
+}
+
+/// Items used by the derive macros' generated code, not public API.
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::convert::struct_members;
+    pub use anyhow::Result;
+}
@@ -0,0 +1,221 @@
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+
+use crate::{Abi, DecodedParams, Event, ExtraTopics, FixedArray4};
+
+/// Decodes logs of many contracts, dispatching on the emitting address.
+///
+/// Logs of unknown emitters, or whose event the emitter's ABI doesn't
+/// decode (e.g. logs emitted by a proxy), are decoded against the other
+/// ABIs, in the order they were added.
+///
+/// ```no_run
+/// use ola_lang_abi::{Abi, FixedArray4, LogDecoder};
+///
+/// # fn logs() -> Vec<(FixedArray4, Vec<FixedArray4>, Vec<u64>)> { vec![] }
+/// let token: Abi = serde_json::from_str(r#"[{
+///     "type": "event",
+///     "name": "Transfer",
+///     "inputs": [{"name": "amount", "type": "u32"}],
+///     "anonymous": false
+/// }]"#).unwrap();
+///
+/// let mut decoder = LogDecoder::new();
+/// decoder.add(FixedArray4([0, 0, 0, 1]), token);
+///
+/// for (address, topics, data) in logs() {
+///     let log = decoder.decode(&address, &topics, &data).unwrap();
+///     println!("{} {}", log.event.name, log.contract);
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LogDecoder {
+    abis: Vec<(FixedArray4, Abi)>,
+    by_address: HashMap<FixedArray4, usize>,
+    extra_topics: ExtraTopics,
+}
+
+/// Log decoded by a [`LogDecoder`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedLog<'a> {
+    /// Address of the contract whose ABI decoded the log.
+    ///
+    /// This differs from the emitter when the log was decoded by the
+    /// fallback search.
+    pub contract: FixedArray4,
+    /// Matched event.
+    pub event: &'a Event,
+    /// Decoded event params.
+    pub params: DecodedParams,
+    /// Trailing topics beyond the event's, ignored with
+    /// [`ExtraTopics::Ignore`]. Worth a warning, the node may have changed
+    /// its log format.
+    pub ignored_topics: Vec<FixedArray4>,
+}
+
+impl LogDecoder {
+    /// Creates a decoder without any ABI.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the policy for topics beyond the ones of the event, strict by
+    /// default.
+    pub fn with_extra_topics(mut self, extra: ExtraTopics) -> Self {
+        self.extra_topics = extra;
+        self
+    }
+
+    /// Registers the ABI of the contract at `address`, replacing the
+    /// previous one.
+    pub fn add(&mut self, address: FixedArray4, abi: Abi) {
+        match self.by_address.get(&address) {
+            Some(&i) => self.abis[i].1 = abi,
+            None => {
+                self.by_address.insert(address, self.abis.len());
+                self.abis.push((address, abi));
+            }
+        }
+    }
+
+    /// Returns the ABI registered for `address`.
+    pub fn abi(&self, address: &FixedArray4) -> Option<&Abi> {
+        self.by_address.get(address).map(|&i| &self.abis[i].1)
+    }
+
+    /// Number of registered contracts.
+    pub fn len(&self) -> usize {
+        self.abis.len()
+    }
+
+    /// Whether no contract is registered.
+    pub fn is_empty(&self) -> bool {
+        self.abis.is_empty()
+    }
+
+    /// Decodes a log emitted by `address`.
+    ///
+    /// Fails with the emitter ABI's error if no ABI decodes the log.
+    pub fn decode(
+        &self,
+        address: &FixedArray4,
+        topics: &[FixedArray4],
+        data: &[u64],
+    ) -> Result<DecodedLog<'_>> {
+        let emitter = self.by_address.get(address).copied();
+
+        let mut emitter_err = None;
+        if let Some(i) = emitter {
+            match self.decode_with(i, topics, data) {
+                Ok(log) => return Ok(log),
+                Err(err) => emitter_err = Some(err),
+            }
+        }
+
+        (0..self.abis.len())
+            .filter(|&i| Some(i) != emitter)
+            .find_map(|i| self.decode_with(i, topics, data).ok())
+            .ok_or_else(|| {
+                emitter_err.unwrap_or_else(|| anyhow!("no ABI decodes the log of {}", address))
+            })
+    }
+
+    fn decode_with(
+        &self,
+        i: usize,
+        topics: &[FixedArray4],
+        data: &[u64],
+    ) -> Result<DecodedLog<'_>> {
+        let (contract, abi) = &self.abis[i];
+        let (event, params, ignored) = abi.decode_log_with(topics, data, self.extra_topics)?;
+
+        Ok(DecodedLog {
+            contract: *contract,
+            event,
+            params,
+            ignored_topics: ignored.to_vec(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::Value;
+
+    fn abi(event: &str, params: usize) -> Abi {
+        let inputs = (0..params)
+            .map(|i| serde_json::json!({ "name": format!("x{}", i), "type": "u32" }))
+            .collect::<Vec<_>>();
+
+        serde_json::from_value(serde_json::json!([{
+            "type": "event",
+            "name": event,
+            "inputs": inputs,
+            "anonymous": false
+        }]))
+        .unwrap()
+    }
+
+    #[test]
+    fn log_decoder_dispatch() {
+        let token = FixedArray4([0, 0, 0, 1]);
+        let vault = FixedArray4([0, 0, 0, 2]);
+
+        let mut decoder = LogDecoder::new();
+        decoder.add(token, abi("Transfer", 1));
+        decoder.add(vault, abi("Deposit", 2));
+        assert_eq!(decoder.len(), 2);
+
+        let transfer = [decoder.abi(&token).unwrap().events[0].topic()];
+        let deposit = [decoder.abi(&vault).unwrap().events[0].topic()];
+
+        let log = decoder.decode(&token, &transfer, &[5]).unwrap();
+        assert_eq!((log.contract, log.event.name.as_str()), (token, "Transfer"));
+        assert_eq!(log.params[0].value, Value::U32(5));
+
+        // unknown emitter, and emitter whose ABI lacks the event
+        for emitter in [FixedArray4([9; 4]), token] {
+            let log = decoder.decode(&emitter, &deposit, &[1, 2]).unwrap();
+            assert_eq!((log.contract, log.event.name.as_str()), (vault, "Deposit"));
+        }
+
+        assert_eq!(
+            decoder
+                .decode(&token, &[FixedArray4::ZERO], &[])
+                .unwrap_err()
+                .to_string(),
+            "ABI event not found"
+        );
+        assert!(decoder.decode(&FixedArray4([9; 4]), &[], &[]).is_err());
+
+        decoder.add(token, abi("Deposit", 2));
+        assert_eq!(decoder.len(), 2);
+        let log = decoder.decode(&token, &deposit, &[1, 2]).unwrap();
+        assert_eq!(log.contract, token);
+    }
+
+    #[test]
+    fn log_decoder_extra_topics() {
+        let token = FixedArray4([0, 0, 0, 1]);
+        let mut decoder = LogDecoder::new();
+        decoder.add(token, abi("Transfer", 1));
+
+        let topics = [
+            decoder.abi(&token).unwrap().events[0].topic(),
+            FixedArray4([0, 0, 0, 7]),
+        ];
+        assert!(decoder.decode(&token, &topics, &[5]).is_err());
+
+        let decoder = decoder.with_extra_topics(ExtraTopics::Ignore);
+        let log = decoder.decode(&token, &topics, &[5]).unwrap();
+        assert_eq!(log.params[0].value, Value::U32(5));
+        assert_eq!(log.ignored_topics, vec![FixedArray4([0, 0, 0, 7])]);
+
+        let log = decoder.decode(&token, &topics[..1], &[5]).unwrap();
+        assert!(log.ignored_topics.is_empty());
+    }
+}
@@ -0,0 +1,228 @@
+use crate::alloc_prelude::*;
+use anyhow::{anyhow, Result};
+
+use crate::{codec, FixedArray4, FixedArray8, FromValue, ToValue, Type, Value};
+
+/// L1 → L2 priority operation, e.g. a deposit or a forced L2 call.
+///
+/// Encoded as the tuple
+/// `(u32 serialId, address sender, address contract, u256 value, u32 gasLimit, fields calldata)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct L1ToL2Message {
+    /// Priority queue serial id.
+    pub serial_id: u64,
+    /// L1 sender, left padded to 32 bytes.
+    pub sender: FixedArray4,
+    /// Called L2 contract.
+    pub contract: FixedArray4,
+    /// Bridged value.
+    pub value: FixedArray8,
+    /// L2 gas limit of the call.
+    pub gas_limit: u64,
+    /// L2 calldata.
+    pub calldata: Vec<u64>,
+}
+
+/// L2 → L1 message, e.g. a withdrawal.
+///
+/// Encoded as the tuple
+/// `(address sender, address receiver, address token, u256 amount, fields data)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct L2ToL1Message {
+    /// L2 sender.
+    pub sender: FixedArray4,
+    /// L1 receiver, left padded to 32 bytes.
+    pub receiver: FixedArray4,
+    /// Withdrawn token, zero for the native token.
+    pub token: FixedArray4,
+    /// Withdrawn amount.
+    pub amount: FixedArray8,
+    /// Additional data passed to the L1 receiver.
+    pub data: Vec<u64>,
+}
+
+fn member(name: &str, value: Value) -> (String, Value) {
+    (name.to_string(), value)
+}
+
+fn fields(value: &Value) -> Result<Vec<u64>> {
+    match value {
+        Value::Fields(fields) => Ok(fields.clone()),
+        _ => Err(anyhow!("expected fields, got {}", value.type_of())),
+    }
+}
+
+impl L1ToL2Message {
+    /// Returns the tuple type of the message.
+    pub fn ty() -> Type {
+        Type::Tuple(vec![
+            ("serialId".to_string(), Type::U32),
+            ("sender".to_string(), Type::Address),
+            ("contract".to_string(), Type::Address),
+            ("value".to_string(), Type::U256),
+            ("gasLimit".to_string(), Type::U32),
+            ("calldata".to_string(), Type::Fields),
+        ])
+    }
+
+    /// Encodes the message into words, failing on fields out of range of
+    /// their type, e.g. `u256` limbs wider than 32 bits.
+    pub fn encode(&self) -> Result<Vec<u64>> {
+        Ok(codec::encode(&[Self::ty()], &[self.to_value()])?)
+    }
+
+    /// Decodes a message, failing on trailing words.
+    pub fn decode(words: &[u64]) -> Result<Self> {
+        Self::from_value(&codec::decode(&[Self::ty()], words)?[0])
+    }
+}
+
+impl ToValue for L1ToL2Message {
+    fn to_value(&self) -> Value {
+        Value::Tuple(vec![
+            member("serialId", Value::U32(self.serial_id)),
+            member("sender", Value::Address(self.sender)),
+            member("contract", Value::Address(self.contract)),
+            member("value", Value::U256(self.value)),
+            member("gasLimit", Value::U32(self.gas_limit)),
+            member("calldata", Value::Fields(self.calldata.clone())),
+        ])
+    }
+}
+
+impl FromValue for L1ToL2Message {
+    fn from_value(value: &Value) -> Result<Self> {
+        value.check_type(&Self::ty())?;
+
+        let members = match value {
+            Value::Tuple(members) => members,
+            _ => unreachable!(),
+        };
+
+        Ok(Self {
+            serial_id: u64::from_value(&members[0].1)?,
+            sender: FixedArray4::from_value(&members[1].1)?,
+            contract: FixedArray4::from_value(&members[2].1)?,
+            value: FixedArray8::from_value(&members[3].1)?,
+            gas_limit: u64::from_value(&members[4].1)?,
+            calldata: fields(&members[5].1)?,
+        })
+    }
+}
+
+impl L2ToL1Message {
+    /// Returns the tuple type of the message.
+    pub fn ty() -> Type {
+        Type::Tuple(vec![
+            ("sender".to_string(), Type::Address),
+            ("receiver".to_string(), Type::Address),
+            ("token".to_string(), Type::Address),
+            ("amount".to_string(), Type::U256),
+            ("data".to_string(), Type::Fields),
+        ])
+    }
+
+    /// Encodes the message into words, failing on fields out of range of
+    /// their type, e.g. `u256` limbs wider than 32 bits.
+    pub fn encode(&self) -> Result<Vec<u64>> {
+        Ok(codec::encode(&[Self::ty()], &[self.to_value()])?)
+    }
+
+    /// Decodes a message, failing on trailing words.
+    pub fn decode(words: &[u64]) -> Result<Self> {
+        Self::from_value(&codec::decode(&[Self::ty()], words)?[0])
+    }
+}
+
+impl ToValue for L2ToL1Message {
+    fn to_value(&self) -> Value {
+        Value::Tuple(vec![
+            member("sender", Value::Address(self.sender)),
+            member("receiver", Value::Address(self.receiver)),
+            member("token", Value::Address(self.token)),
+            member("amount", Value::U256(self.amount)),
+            member("data", Value::Fields(self.data.clone())),
+        ])
+    }
+}
+
+impl FromValue for L2ToL1Message {
+    fn from_value(value: &Value) -> Result<Self> {
+        value.check_type(&Self::ty())?;
+
+        let members = match value {
+            Value::Tuple(members) => members,
+            _ => unreachable!(),
+        };
+
+        Ok(Self {
+            sender: FixedArray4::from_value(&members[0].1)?,
+            receiver: FixedArray4::from_value(&members[1].1)?,
+            token: FixedArray4::from_value(&members[2].1)?,
+            amount: FixedArray8::from_value(&members[3].1)?,
+            data: fields(&members[4].1)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn l1_to_l2_round_trip() {
+        let message = L1ToL2Message {
+            serial_id: 3,
+            sender: FixedArray4([0, 0, 0, 1]),
+            contract: FixedArray4([2, 2, 2, 2]),
+            value: FixedArray8([0, 0, 0, 0, 0, 0, 0, 100]),
+            gas_limit: 50_000,
+            calldata: vec![7, 1, 0xf146ff09],
+        };
+
+        let words = message.encode().unwrap();
+        assert_eq!(
+            words,
+            [
+                vec![3, 0, 0, 0, 1, 2, 2, 2, 2],
+                vec![0, 0, 0, 0, 0, 0, 0, 100],
+                vec![50_000, 3, 7, 1, 0xf146ff09],
+            ]
+            .concat()
+        );
+        assert_eq!(L1ToL2Message::decode(&words).unwrap(), message);
+        assert!(L1ToL2Message::decode(&words[..words.len() - 1]).is_err());
+        let invalid = L1ToL2Message {
+            value: FixedArray8([u64::MAX; 8]),
+            ..message
+        };
+        assert!(invalid.encode().is_err());
+    }
+
+    #[test]
+    fn l2_to_l1_round_trip() {
+        let message = L2ToL1Message {
+            sender: FixedArray4([1, 1, 1, 1]),
+            receiver: FixedArray4([0, 0, 0, 2]),
+            token: FixedArray4::ZERO,
+            amount: FixedArray8([0, 0, 0, 0, 0, 0, 1, 0]),
+            data: vec![],
+        };
+
+        let words = message.encode().unwrap();
+        assert_eq!(words.len(), 4 * 3 + 8 + 1);
+        assert_eq!(L2ToL1Message::decode(&words).unwrap(), message);
+
+        let mut trailing = words.clone();
+        trailing.push(0);
+        assert!(L2ToL1Message::decode(&trailing).is_err());
+        assert!(L2ToL1Message::from_value(&Value::U32(1)).is_err());
+        let invalid = L2ToL1Message {
+            amount: FixedArray8([1 << 32; 8]),
+            ..message
+        };
+        assert!(invalid.encode().is_err());
+    }
+}
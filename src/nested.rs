@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+
+use crate::{Abi, DecodeOptions, SelectorRegistry, Type, Value, MAX_TYPE_DEPTH};
+
+/// Call decoded from calldata embedded in a `fields` value, see
+/// [`Value::try_decode_nested`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NestedCall {
+    /// Path of the `fields` value holding the calldata, e.g. `/calls/1`,
+    /// relative to the value or call params it was found in.
+    pub path: String,
+    /// Signature of the embedded call.
+    pub signature: String,
+    /// Decoded params of the embedded call.
+    pub params: Vec<Value>,
+    /// Calls embedded in the params in turn.
+    pub nested: Vec<NestedCall>,
+}
+
+impl Value {
+    /// Finds the `fields` values holding calldata of a call known to the
+    /// registry, as routers and multicall aggregators take them, and decodes
+    /// them, recursing into the params of the embedded calls.
+    ///
+    /// Calldata is recognized by its method id and param-len words and must
+    /// decode strictly with one of the signatures registered for the method
+    /// id, tried in registration order. Fields that don't are left alone.
+    ///
+    /// ```
+    /// use ola_lang_abi::{Abi, FixedArray4, SelectorRegistry, Type, Value};
+    ///
+    /// let abi = Abi::from_human_readable(&[
+    ///     "function multicall(fields[] calls)",
+    ///     "function transfer(address to, u32 amount)",
+    /// ]).unwrap();
+    /// let mut registry = SelectorRegistry::new();
+    /// registry.add_abi(&abi);
+    ///
+    /// let to = Value::Address(FixedArray4([0, 0, 0, 1]));
+    /// let transfer = abi
+    ///     .encode_input_with_signature("transfer(address,u32)", &[to.clone(), Value::U32(5)])
+    ///     .unwrap();
+    /// let calls = Value::Array(vec![Value::Fields(transfer)], Type::Fields);
+    ///
+    /// let nested = calls.try_decode_nested(&registry);
+    /// assert_eq!(nested[0].path, "/0");
+    /// assert_eq!(nested[0].signature, "transfer(address,u32)");
+    /// assert_eq!(nested[0].params, [to, Value::U32(5)]);
+    /// ```
+    pub fn try_decode_nested(&self, registry: &SelectorRegistry) -> Vec<NestedCall> {
+        let mut finder = CallFinder {
+            registry,
+            input_types: HashMap::new(),
+        };
+        let mut calls = vec![];
+        finder.find_calls(self, "", 0, &mut calls);
+
+        calls
+    }
+}
+
+/// Walks values for embedded calls, parsing each registered signature at
+/// most once.
+struct CallFinder<'a> {
+    registry: &'a SelectorRegistry,
+    /// Input types by signature, `None` for signatures that don't parse.
+    input_types: HashMap<&'a str, Option<Vec<Type>>>,
+}
+
+impl<'a> CallFinder<'a> {
+    fn find_calls(&mut self, value: &Value, path: &str, depth: usize, calls: &mut Vec<NestedCall>) {
+        match value {
+            Value::Fields(words) => {
+                if let Some(call) = self.decode_call(words, path, depth) {
+                    calls.push(call);
+                }
+            }
+            Value::FixedArray(values, _) | Value::Array(values, _) => {
+                for (i, value) in values.iter().enumerate() {
+                    self.find_calls(value, &format!("{}/{}", path, i), depth, calls);
+                }
+            }
+            Value::Tuple(members) => {
+                for (i, (name, value)) in members.iter().enumerate() {
+                    let path = if name.is_empty() {
+                        format!("{}/{}", path, i)
+                    } else {
+                        format!("{}/{}", path, name)
+                    };
+                    self.find_calls(value, &path, depth, calls);
+                }
+            }
+            Value::Some(value) => self.find_calls(value, path, depth, calls),
+            Value::Map(entries, ..) => {
+                for (i, (_, value)) in entries.iter().enumerate() {
+                    self.find_calls(value, &format!("{}/{}", path, i), depth, calls);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn decode_call(&mut self, words: &[u64], path: &str, depth: usize) -> Option<NestedCall> {
+        // embedded calldata nested too deep is left undecoded, not to exhaust
+        // the stack on crafted input
+        if depth >= MAX_TYPE_DEPTH {
+            return None;
+        }
+
+        let (&method_id, rest) = words.split_last()?;
+        let (&param_len, params) = rest.split_last()?;
+        if param_len != params.len() as u64 {
+            return None;
+        }
+
+        let registry = self.registry;
+        registry
+            .function_signatures(method_id)
+            .iter()
+            .find_map(|signature| {
+                let types = self
+                    .input_types
+                    .entry(signature)
+                    .or_insert_with(|| input_types(signature))
+                    .as_deref()?;
+                let params = Value::decode_with_options(
+                    params,
+                    types,
+                    DecodeOptions {
+                        strict: true,
+                        ..Default::default()
+                    },
+                )
+                .ok()?;
+
+                let mut nested = vec![];
+                for (i, param) in params.iter().enumerate() {
+                    self.find_calls(param, &format!("/{}", i), depth + 1, &mut nested);
+                }
+
+                Some(NestedCall {
+                    path: if path.is_empty() { "/" } else { path }.to_string(),
+                    signature: signature.clone(),
+                    params,
+                    nested,
+                })
+            })
+    }
+}
+
+/// Parses the input types of a function signature.
+fn input_types(signature: &str) -> Option<Vec<Type>> {
+    let abi = Abi::from_human_readable(&[format!("function {}", signature)]).ok()?;
+
+    Some(
+        abi.functions[0]
+            .inputs
+            .iter()
+            .map(|param| param.type_.clone())
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::FixedArray4;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn nested_calls() {
+        let abi = Abi::from_human_readable(&[
+            "function multicall((address target, fields data)[] calls)",
+            "function transfer(address to, u32 amount)",
+            "function note(string text)",
+        ])
+        .unwrap();
+        let mut registry = SelectorRegistry::new();
+        registry.add_abi(&abi);
+
+        let target = Value::Address(FixedArray4([0, 0, 0, 9]));
+        let to = Value::Address(FixedArray4([0, 0, 0, 1]));
+        let transfer = abi
+            .encode_input_with_signature("transfer(address,u32)", &[to.clone(), Value::U32(5)])
+            .unwrap();
+        let calls = |data: Vec<Value>| {
+            let call_type = Type::Tuple(vec![
+                ("target".to_string(), Type::Address),
+                ("data".to_string(), Type::Fields),
+            ]);
+            Value::Array(
+                data.into_iter()
+                    .map(|data| {
+                        Value::Tuple(vec![
+                            ("target".to_string(), target.clone()),
+                            ("data".to_string(), data),
+                        ])
+                    })
+                    .collect(),
+                call_type,
+            )
+        };
+        let inner = abi
+            .encode_input_with_signature(
+                "multicall((address,fields)[])",
+                &[calls(vec![Value::Fields(transfer.clone())])],
+            )
+            .unwrap();
+
+        let outer = calls(vec![
+            Value::Fields(vec![1, 2, 3]),
+            Value::Fields(inner),
+            Value::Fields(transfer),
+        ]);
+        let nested = outer.try_decode_nested(&registry);
+
+        assert_eq!(nested.len(), 2);
+        assert_eq!(nested[0].path, "/1/data");
+        assert_eq!(nested[0].signature, "multicall((address,fields)[])");
+        assert_eq!(nested[0].nested.len(), 1);
+        // signatures carry no member names
+        assert_eq!(nested[0].nested[0].path, "/0/0/1");
+        assert_eq!(nested[0].nested[0].params, [to.clone(), Value::U32(5)]);
+        assert!(nested[0].nested[0].nested.is_empty());
+        assert_eq!(nested[1].path, "/2/data");
+
+        // a known method id over words that don't decode isn't a call
+        let note = abi.functions[2].method_id();
+        assert!(Value::Fields(vec![7, 1, note])
+            .try_decode_nested(&registry)
+            .is_empty());
+        assert!(Value::Fields(vec![])
+            .try_decode_nested(&registry)
+            .is_empty());
+        assert!(Value::Fields(vec![0, note])
+            .try_decode_nested(&registry)
+            .is_empty());
+    }
+
+    #[test]
+    fn signatures_parsed_once() {
+        let abi = Abi::from_human_readable(&["function transfer(address to, u32 amount)"]).unwrap();
+        let mut registry = SelectorRegistry::new();
+        registry.add_abi(&abi);
+
+        let transfer = |amount| {
+            let to = Value::Address(FixedArray4([0, 0, 0, 1]));
+            let calldata = abi
+                .encode_input_with_signature("transfer(address,u32)", &[to, Value::U32(amount)])
+                .unwrap();
+            Value::Fields(calldata)
+        };
+        let calls = Value::Array((0..3).map(transfer).collect(), Type::Fields);
+
+        let mut finder = CallFinder {
+            registry: &registry,
+            input_types: HashMap::new(),
+        };
+        let mut nested = vec![];
+        finder.find_calls(&calls, "", 0, &mut nested);
+
+        assert_eq!(nested.len(), 3);
+        assert_eq!(
+            finder.input_types.into_iter().collect::<Vec<_>>(),
+            vec![(
+                "transfer(address,u32)",
+                Some(vec![Type::Address, Type::U32])
+            )]
+        );
+    }
+}
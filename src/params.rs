@@ -1,10 +1,14 @@
+use crate::alloc_prelude::*;
+use alloc::rc::Rc;
+use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, rc::Rc};
+#[cfg(feature = "std")]
+use std::collections::HashMap;
 
 use crate::{types::Type, Value};
 
 /// ABI decoded param value.
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct DecodedParam {
     // Param definition.
     pub param: Param,
@@ -21,19 +25,44 @@ impl From<(Param, Value)> for DecodedParam {
 /// ABI decoded values. Fast access by param index and name.
 ///
 /// This struct provides a way for accessing decoded param values by index and by name.
-#[derive(Debug, Clone, Eq, PartialEq)]
+///
+/// Params are always in the order of the ABI params they were decoded with,
+/// indexed event params included, and serialize as a JSON array in that
+/// order, so positional consumers can rely on [`DecodedParams::nth`].
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct DecodedParams(Vec<DecodedParam>);
 
 impl DecodedParams {
     /// Creates a reader.
     ///
     /// Parameters are indexed by name at reader creation.
-    pub fn reader(&self) -> DecodedParamsReader {
+    #[cfg(feature = "std")]
+    pub fn reader(&self) -> DecodedParamsReader<'_> {
         DecodedParamsReader::new(self)
     }
+
+    /// Returns the `i`th param, in ABI order.
+    pub fn nth(&self, i: usize) -> Option<&DecodedParam> {
+        self.0.get(i)
+    }
+
+    /// Number of params.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether there are no params.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the param names in ABI order, empty for unnamed params.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(|decoded| decoded.param.name.as_str())
+    }
 }
 
-impl std::ops::Deref for DecodedParams {
+impl core::ops::Deref for DecodedParams {
     type Target = Vec<DecodedParam>;
 
     fn deref(&self) -> &Self::Target {
@@ -48,6 +77,7 @@ impl From<Vec<(Param, Value)>> for DecodedParams {
 }
 
 /// Provides fast read access to decoded params by parameter index and name.
+#[cfg(feature = "std")]
 pub struct DecodedParamsReader<'a> {
     /// Decoded params by parameter index.
     pub by_index: Vec<&'a DecodedParam>,
@@ -55,6 +85,7 @@ pub struct DecodedParamsReader<'a> {
     pub by_name: HashMap<&'a str, &'a DecodedParam>,
 }
 
+#[cfg(feature = "std")]
 impl<'a> DecodedParamsReader<'a> {
     fn new(decoded_params: &'a DecodedParams) -> Self {
         let by_index = decoded_params.iter().collect();
@@ -78,21 +109,13 @@ pub struct Param {
     pub type_: Type,
     /// Whether it is an indexed parameter (events only).
     pub indexed: Option<bool>,
+    /// Documentation attached by the compiler (`"doc"` or `"notice"`).
+    pub docs: Option<String>,
 }
 
 impl Param {
     fn build_param_entry(&self) -> ParamEntry {
-        let tuple_params = match &self.type_ {
-            Type::Tuple(params) => Some(params.clone()),
-            Type::Array(ty) | Type::FixedArray(ty, _) => {
-                if let Type::Tuple(params) = ty.as_ref() {
-                    Some(params.clone())
-                } else {
-                    None
-                }
-            }
-            _ => None,
-        };
+        let tuple_params = tuple_members(&self.type_).cloned();
 
         let components = tuple_params.map(|params| {
             params
@@ -102,6 +125,7 @@ impl Param {
                         name: name.clone(),
                         type_: ty.clone(),
                         indexed: None,
+                        docs: None,
                     }
                     .build_param_entry()
                 })
@@ -113,6 +137,7 @@ impl Param {
             type_: param_type_string(&self.type_),
             indexed: self.indexed,
             components,
+            doc: self.docs.clone(),
         }
     }
 }
@@ -133,6 +158,13 @@ impl<'a> Deserialize<'a> for Param {
     {
         let entry: ParamEntry = Deserialize::deserialize(deserializer)?;
 
+        if entry.depth(0) > MAX_TYPE_DEPTH {
+            return Err(serde::de::Error::custom(format!(
+                "type of param {} nests deeper than {} levels",
+                entry.name, MAX_TYPE_DEPTH
+            )));
+        }
+
         let (_, ty) = parse_exact_type(Rc::new(entry.components), &entry.type_)
             .map_err(|e| serde::de::Error::custom(e.to_string()))?;
 
@@ -140,15 +172,41 @@ impl<'a> Deserialize<'a> for Param {
             name: entry.name.to_string(),
             type_: ty,
             indexed: entry.indexed,
+            docs: entry.doc,
         })
     }
 }
 
+/// Maximum nesting of array dimensions and tuples in ABI param types.
+///
+/// Types are processed recursively, so hostile ABI files nesting them
+/// deeper would exhaust the stack.
+pub const MAX_TYPE_DEPTH: usize = 64;
+
+/// Members of the tuple a type is made of, possibly wrapped in arrays,
+/// options and maps, whose value is preferred over their key.
+fn tuple_members(ty: &Type) -> Option<&Vec<(String, Type)>> {
+    match ty {
+        Type::Tuple(members) => Some(members),
+        Type::Array(inner) | Type::FixedArray(inner, _) | Type::Option(inner) => {
+            tuple_members(inner)
+        }
+        Type::Map(key, value) => tuple_members(value).or_else(|| tuple_members(key)),
+        _ => None,
+    }
+}
+
 fn param_type_string(ty: &Type) -> String {
     match ty {
         Type::Tuple(_) => String::from("tuple"),
         Type::Array(ty) => format!("{}[]", param_type_string(ty)),
         Type::FixedArray(ty, size) => format!("{}[{}]", param_type_string(ty), size),
+        Type::Option(ty) => format!("{}?", param_type_string(ty)),
+        Type::Map(key, value) => format!(
+            "map({},{})",
+            param_type_string(key),
+            param_type_string(value)
+        ),
         _ => format!("{}", ty),
     }
 }
@@ -162,14 +220,38 @@ struct ParamEntry {
     pub indexed: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub components: Option<Vec<ParamEntry>>,
+    #[serde(alias = "notice", skip_serializing_if = "Option::is_none")]
+    pub doc: Option<String>,
+}
+
+impl ParamEntry {
+    /// Nesting depth of the entry's type, stopping early once deeper than
+    /// [`MAX_TYPE_DEPTH`].
+    fn depth(&self, parent: usize) -> usize {
+        let dimensions =
+            self.type_.matches(['[', '?']).count() + self.type_.matches("map(").count();
+        let depth = parent.saturating_add(dimensions);
+
+        match &self.components {
+            Some(components) if self.type_.contains("tuple") && depth < MAX_TYPE_DEPTH => {
+                components
+                    .iter()
+                    .map(|component| component.depth(depth + 1))
+                    .max()
+                    .unwrap_or(depth + 1)
+            }
+            Some(_) if self.type_.contains("tuple") => depth + 1,
+            _ => depth,
+        }
+    }
 }
 
 use nom::{
     branch::alt,
     bytes::complete::tag,
     character::complete::{char, digit1},
-    combinator::{all_consuming, map_res, opt, recognize},
-    multi::many1,
+    combinator::{all_consuming, map, map_res, opt, recognize},
+    multi::{many0, many1},
     sequence::delimited,
     IResult,
 };
@@ -213,10 +295,24 @@ fn parse_type(
     components: Rc<Option<Vec<ParamEntry>>>,
 ) -> impl Fn(&str) -> TypeParseResult<&str, Type> {
     move |input: &str| {
-        alt((
-            parse_array(components.clone()),
-            parse_simple_type(components.clone()),
-        ))(input)
+        let (i, ty) = parse_simple_type(components.clone())(input)?;
+
+        // parsing the element type only once keeps nested tuples linear,
+        // `[]` and `[k]` suffixes wrap it in arrays and `?` in options
+        let (i, suffixes) = map_error(many0(alt((
+            map(delimited(char('['), opt(parse_integer), char(']')), Some),
+            map(char('?'), |_| None),
+        )))(i))?;
+
+        let ty = suffixes
+            .into_iter()
+            .fold(ty, |ty, suffix: Option<Option<u64>>| match suffix {
+                Some(None) => Type::Array(Box::new(ty)),
+                Some(Some(size)) => Type::FixedArray(Box::new(ty), size),
+                None => Type::Option(Box::new(ty)),
+            });
+
+        Ok((i, ty))
     }
 }
 
@@ -226,9 +322,12 @@ fn parse_simple_type(
     move |input: &str| {
         alt((
             parse_tuple(components.clone()),
+            |i| parse_map(components.clone(), i),
             parse_fields,
             parse_u32,
             parse_u256,
+            parse_i32,
+            parse_i64,
             parse_field,
             parse_address,
             parse_hash,
@@ -246,6 +345,14 @@ fn parse_u256(input: &str) -> TypeParseResult<&str, Type> {
     map_error(tag("u256")(input).map(|(i, _)| (i, Type::U256)))
 }
 
+fn parse_i32(input: &str) -> TypeParseResult<&str, Type> {
+    map_error(tag("i32")(input).map(|(i, _)| (i, Type::I32)))
+}
+
+fn parse_i64(input: &str) -> TypeParseResult<&str, Type> {
+    map_error(tag("i64")(input).map(|(i, _)| (i, Type::I64)))
+}
+
 fn parse_field(input: &str) -> TypeParseResult<&str, Type> {
     map_error(tag("field")(input).map(|(i, _)| (i, Type::Field)))
 }
@@ -270,28 +377,6 @@ fn parse_fields(input: &str) -> TypeParseResult<&str, Type> {
     map_error(tag("fields")(input).map(|(i, _)| (i, Type::Fields)))
 }
 
-fn parse_array(
-    components: Rc<Option<Vec<ParamEntry>>>,
-) -> impl Fn(&str) -> TypeParseResult<&str, Type> {
-    move |input: &str| {
-        let (i, ty) = parse_simple_type(components.clone())(input)?;
-
-        let (i, sizes) = map_error(many1(delimited(char('['), opt(parse_integer), char(']')))(
-            i,
-        ))?;
-
-        let array_from_size = |ty: Type, size: Option<u64>| match size {
-            None => Type::Array(Box::new(ty)),
-            Some(size) => Type::FixedArray(Box::new(ty), size),
-        };
-
-        let init_arr_ty = array_from_size(ty, sizes[0]);
-        let arr_ty = sizes.into_iter().skip(1).fold(init_arr_ty, array_from_size);
-
-        Ok((i, arr_ty))
-    }
-}
-
 fn parse_tuple(
     components: Rc<Option<Vec<ParamEntry>>>,
 ) -> impl Fn(&str) -> TypeParseResult<&str, Type> {
@@ -322,6 +407,17 @@ fn parse_tuple(
     }
 }
 
+/// Parses `map(K,V)`, a tuple key or value taking the components.
+fn parse_map(components: Rc<Option<Vec<ParamEntry>>>, input: &str) -> TypeParseResult<&str, Type> {
+    let (i, _) = map_error(tag("map(")(input))?;
+    let (i, key) = parse_type(components.clone())(i)?;
+    let (i, _) = map_error(char(',')(i))?;
+    let (i, value) = parse_type(components)(i)?;
+    let (i, _) = map_error(char(')')(i))?;
+
+    Ok((i, Type::Map(Box::new(key), Box::new(value))))
+}
+
 fn parse_integer(input: &str) -> IResult<&str, u64> {
     map_res(recognize(many1(digit1)), str::parse)(input)
 }
@@ -333,6 +429,69 @@ mod test {
     use pretty_assertions::assert_eq;
     use serde_json::json;
 
+    #[test]
+    fn decoded_params_order() {
+        let param = |name: &str, type_| Param {
+            name: name.to_string(),
+            type_,
+            indexed: None,
+            docs: None,
+        };
+        let params = [
+            param("zeta", Type::U32),
+            param("", Type::Bool),
+            param("alpha", Type::String),
+            param("mid", Type::U32),
+        ];
+        let tys = params.iter().map(|p| p.type_.clone()).collect::<Vec<_>>();
+        let values = Value::decode_from_slice(&[3, 1, 2, 104, 105, 9], &tys).unwrap();
+        let decoded = DecodedParams::from(params.into_iter().zip(values).collect::<Vec<_>>());
+
+        assert_eq!(decoded.len(), 4);
+        assert!(!decoded.is_empty());
+        assert_eq!(
+            decoded.names().collect::<Vec<_>>(),
+            ["zeta", "", "alpha", "mid"]
+        );
+        assert_eq!(decoded.nth(3).unwrap().value, Value::U32(9));
+        assert_eq!(decoded.nth(4), None);
+
+        let json = serde_json::to_string(&decoded).unwrap();
+        let round_trip: DecodedParams = serde_json::from_str(&json).unwrap();
+        assert_eq!(round_trip, decoded);
+        assert_eq!(
+            serde_json::to_value(&decoded).unwrap()[2]["param"]["name"],
+            "alpha"
+        );
+    }
+
+    #[test]
+    fn param_depth_limit() {
+        let nested = |depth: usize| {
+            let mut param = json!({ "name": "leaf", "type": "u32" });
+            for _ in 0..depth {
+                param = json!({ "name": "t", "type": "tuple", "components": [param] });
+            }
+            param
+        };
+
+        assert!(serde_json::from_value::<Param>(nested(MAX_TYPE_DEPTH)).is_ok());
+        assert_eq!(
+            serde_json::from_value::<Param>(nested(MAX_TYPE_DEPTH + 1))
+                .unwrap_err()
+                .to_string(),
+            format!(
+                "type of param t nests deeper than {} levels",
+                MAX_TYPE_DEPTH
+            )
+        );
+
+        let dimensions = format!("u32{}", "[]".repeat(100_000));
+        assert!(
+            serde_json::from_value::<Param>(json!({ "name": "a", "type": dimensions })).is_err()
+        );
+    }
+
     #[test]
     fn serde_u32() {
         let v = json!({
@@ -347,7 +506,8 @@ mod test {
             Param {
                 name: "a".to_string(),
                 type_: Type::U32,
-                indexed: None
+                indexed: None,
+                docs: None,
             }
         );
 
@@ -356,6 +516,23 @@ mod test {
         assert_eq!(v, param_json);
     }
 
+    #[test]
+    fn serde_signed() {
+        let v = json!({
+            "name": "a",
+            "type": "i64[2]",
+        });
+
+        let param: Param = serde_json::from_value(v.clone()).expect("param deserialized");
+
+        assert_eq!(param.type_, Type::FixedArray(Box::new(Type::I64), 2));
+        assert_eq!(serde_json::to_value(param).expect("param serialized"), v);
+
+        let param: Param = serde_json::from_value(json!({ "name": "a", "type": "i32" }))
+            .expect("param deserialized");
+        assert_eq!(param.type_, Type::I32);
+    }
+
     #[test]
     fn serde_u256() {
         let v = json!({
@@ -370,7 +547,8 @@ mod test {
             Param {
                 name: "a".to_string(),
                 type_: Type::U256,
-                indexed: None
+                indexed: None,
+                docs: None,
             }
         );
 
@@ -393,7 +571,8 @@ mod test {
             Param {
                 name: "a".to_string(),
                 type_: Type::Field,
-                indexed: None
+                indexed: None,
+                docs: None,
             }
         );
 
@@ -416,7 +595,8 @@ mod test {
             Param {
                 name: "a".to_string(),
                 type_: Type::Address,
-                indexed: None
+                indexed: None,
+                docs: None,
             }
         );
 
@@ -439,7 +619,8 @@ mod test {
             Param {
                 name: "a".to_string(),
                 type_: Type::Bool,
-                indexed: None
+                indexed: None,
+                docs: None,
             }
         );
 
@@ -462,7 +643,8 @@ mod test {
             Param {
                 name: "a".to_string(),
                 type_: Type::String,
-                indexed: None
+                indexed: None,
+                docs: None,
             }
         );
 
@@ -485,7 +667,8 @@ mod test {
             Param {
                 name: "a".to_string(),
                 type_: Type::Fields,
-                indexed: None
+                indexed: None,
+                docs: None,
             }
         );
 
@@ -507,7 +690,8 @@ mod test {
             Param {
                 name: "a".to_string(),
                 type_: Type::Array(Box::new(Type::U32)),
-                indexed: None
+                indexed: None,
+                docs: None,
             }
         );
 
@@ -529,7 +713,8 @@ mod test {
             Param {
                 name: "a".to_string(),
                 type_: Type::Array(Box::new(Type::Array(Box::new(Type::Address)))),
-                indexed: None
+                indexed: None,
+                docs: None,
             }
         );
 
@@ -551,7 +736,8 @@ mod test {
             Param {
                 name: "a".to_string(),
                 type_: Type::Array(Box::new(Type::FixedArray(Box::new(Type::String), 2))),
-                indexed: None
+                indexed: None,
+                docs: None,
             }
         );
 
@@ -571,7 +757,8 @@ mod test {
             Param {
                 name: "a".to_string(),
                 type_: Type::FixedArray(Box::new(Type::Array(Box::new(Type::String))), 3),
-                indexed: None
+                indexed: None,
+                docs: None,
             }
         );
 
@@ -580,6 +767,68 @@ mod test {
         assert_eq!(v, param_json);
     }
 
+    #[test]
+    fn serde_option() {
+        let v = json!({
+            "name": "config",
+            "type": "tuple",
+            "components": [
+                { "name": "fee", "type": "u32?" },
+                { "name": "tags", "type": "string?[]" },
+                {
+                    "name": "owners",
+                    "type": "tuple[]?",
+                    "components": [{ "name": "owner", "type": "address" }]
+                }
+            ]
+        });
+
+        let param: Param = serde_json::from_value(v.clone()).expect("param deserialized");
+        let owner = Type::Tuple(vec![("owner".to_string(), Type::Address)]);
+        assert_eq!(
+            param.type_,
+            Type::Tuple(vec![
+                ("fee".to_string(), Type::Option(Box::new(Type::U32))),
+                (
+                    "tags".to_string(),
+                    Type::Array(Box::new(Type::Option(Box::new(Type::String))))
+                ),
+                (
+                    "owners".to_string(),
+                    Type::Option(Box::new(Type::Array(Box::new(owner))))
+                ),
+            ])
+        );
+        assert_eq!(param.type_.to_string(), "(u32?,string?[],(address)[]?)");
+
+        let param_json = serde_json::to_value(param).expect("param serialized");
+        assert_eq!(v, param_json);
+    }
+
+    #[test]
+    fn serde_map() {
+        let v = json!({
+            "name": "books",
+            "type": "map(u32,tuple[])",
+            "components": [{ "name": "title", "type": "string" }]
+        });
+
+        let param: Param = serde_json::from_value(v.clone()).expect("param deserialized");
+        let book = Type::Tuple(vec![("title".to_string(), Type::String)]);
+        assert_eq!(
+            param.type_,
+            Type::Map(Box::new(Type::U32), Box::new(Type::Array(Box::new(book))))
+        );
+        assert_eq!(param.type_.to_string(), "map(u32,(string)[])");
+
+        let param_json = serde_json::to_value(param).expect("param serialized");
+        assert_eq!(v, param_json);
+
+        for ty in ["map(u32)", "map(u32,string", "map(u32, string)"] {
+            assert!(serde_json::from_value::<Param>(json!({ "name": "m", "type": ty })).is_err());
+        }
+    }
+
     #[test]
     fn serde_tuple() {
         let v = json!({
@@ -628,7 +877,8 @@ mod test {
                         ])))
                     )
                 ]),
-                indexed: None
+                indexed: None,
+                docs: None,
             }
         );
 
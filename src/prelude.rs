@@ -0,0 +1,17 @@
+//! Commonly used types and traits, for a one-line import.
+//!
+//! ```
+//! use ola_lang_abi::prelude::*;
+//!
+//! let value = Value::from((7u32, true));
+//! assert_eq!(<(u32, bool)>::from_value(&value).unwrap(), (7, true));
+//! ```
+
+pub use crate::{
+    DecodeOptions, DecodedParam, DecodedParams, Decoder, EncodeOptions, Encoder, FixedArray4,
+    FixedArray8, FromValue, IntegerPacking, Param, StringPacking, ToValue, ToValues, Type, Value,
+    Write64,
+};
+
+#[cfg(feature = "abi")]
+pub use crate::{Abi, Event, Function, SelectorEndianness, SelectorWidth, StateMutability};
@@ -0,0 +1,136 @@
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::{
+    calldata,
+    error::{AbiError, Result},
+    FixedArray4,
+};
+
+/// Log as returned by a node: its topics and data words, see
+/// [`Abi::decode_log`](crate::Abi::decode_log).
+///
+/// Deserializes from the usual node JSON shapes, topics as hex strings or
+/// arrays of 4 words and data as hex or an array of words; other fields,
+/// e.g. the emitter address, are ignored:
+///
+/// ```
+/// use ola_lang_abi::{FixedArray4, RawLog};
+///
+/// let log: RawLog = serde_json::from_str(r#"{
+///     "address": "0x01",
+///     "topics": ["0x0a", [0, 0, 0, 11]],
+///     "data": "0x0000000000000001"
+/// }"#).unwrap();
+///
+/// assert_eq!(log.topics, [FixedArray4([0, 0, 0, 10]), FixedArray4([0, 0, 0, 11])]);
+/// assert_eq!(log.data, [1]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RawLog {
+    /// Log topics.
+    #[serde(deserialize_with = "deserialize_topics")]
+    pub topics: Vec<FixedArray4>,
+    /// Log data.
+    #[serde(deserialize_with = "deserialize_data")]
+    pub data: Vec<u64>,
+}
+
+impl RawLog {
+    /// Creates a log from hex topics and hex data, as parsed by
+    /// [`FixedArray4::from_hex`] and [`calldata::from_hex`].
+    pub fn from_hex<S: AsRef<str>>(topics: &[S], data: &str) -> Result<Self> {
+        Ok(Self {
+            topics: topics
+                .iter()
+                .map(|topic| FixedArray4::from_hex(topic.as_ref()))
+                .collect::<Result<_>>()?,
+            data: calldata::from_hex(data)?,
+        })
+    }
+}
+
+impl From<(Vec<FixedArray4>, Vec<u64>)> for RawLog {
+    fn from((topics, data): (Vec<FixedArray4>, Vec<u64>)) -> Self {
+        Self { topics, data }
+    }
+}
+
+impl From<(&[FixedArray4], &[u64])> for RawLog {
+    fn from((topics, data): (&[FixedArray4], &[u64])) -> Self {
+        Self {
+            topics: topics.to_vec(),
+            data: data.to_vec(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Topic {
+    Hex(String),
+    Words(FixedArray4),
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Data {
+    Hex(String),
+    Words(Vec<u64>),
+}
+
+fn deserialize_topics<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<FixedArray4>, D::Error> {
+    Vec::<Topic>::deserialize(deserializer)?
+        .into_iter()
+        .map(|topic| match topic {
+            Topic::Hex(hex) => FixedArray4::from_hex(&hex).map_err(serde::de::Error::custom),
+            Topic::Words(words) => Ok(words),
+        })
+        .collect()
+}
+
+fn deserialize_data<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u64>, D::Error> {
+    match Data::deserialize(deserializer)? {
+        Data::Hex(hex) => calldata::from_hex(&hex).map_err(serde::de::Error::custom),
+        Data::Words(words) => Ok(words),
+    }
+}
+
+impl std::str::FromStr for RawLog {
+    type Err = AbiError;
+
+    /// Parses a node JSON log.
+    fn from_str(s: &str) -> Result<Self> {
+        serde_json::from_str(s).map_err(|e| AbiError::InvalidInput(format!("invalid log: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn raw_log_shapes() {
+        let log = RawLog::from((vec![FixedArray4([0, 0, 0, 10])], vec![1, 2]));
+
+        assert_eq!(
+            RawLog::from_hex(&["0xa"], "0x00000000000000010000000000000002").unwrap(),
+            log
+        );
+        assert_eq!(
+            r#"{"topics": [[0, 0, 0, 10]], "data": [1, 2]}"#.parse::<RawLog>().unwrap(),
+            log
+        );
+        assert_eq!(
+            serde_json::from_value::<RawLog>(serde_json::to_value(&log).unwrap()).unwrap(),
+            log
+        );
+
+        assert!(RawLog::from_hex(&["0xzz"], "0x").is_err());
+        assert!(RawLog::from_hex(&["0xa"], "0x01").is_err());
+        assert!(r#"{"topics": ["0xzz"], "data": []}"#.parse::<RawLog>().is_err());
+    }
+}
@@ -0,0 +1,144 @@
+use anyhow::{anyhow, Result};
+
+use crate::{codec, DecodedParams, Function};
+
+impl DecodedParams {
+    /// Re-encodes the params as calldata of `function`,
+    /// `[params..., param-len, method_id]`.
+    ///
+    /// Fails unless the params are the function inputs, in order, with values
+    /// of their types.
+    pub fn reencode(&self, function: &Function) -> Result<Vec<u64>> {
+        if self.len() != function.inputs.len()
+            || self
+                .iter()
+                .zip(&function.inputs)
+                .any(|(decoded, param)| decoded.param != *param)
+        {
+            return Err(anyhow!(
+                "decoded params don't match {} inputs",
+                function.signature()
+            ));
+        }
+
+        let tys = self
+            .iter()
+            .map(|decoded| decoded.param.type_.clone())
+            .collect::<Vec<_>>();
+        let values = self
+            .iter()
+            .map(|decoded| decoded.value.clone())
+            .collect::<Vec<_>>();
+
+        let mut calldata = codec::encode(&tys, &values)?;
+        calldata.push(calldata.len() as u64);
+        calldata.push(function.method_id());
+
+        Ok(calldata)
+    }
+
+    /// Checks that re-encoding the params reproduces `calldata`, e.g. the
+    /// payload they were decoded from.
+    ///
+    /// Decoding is lenient (any bool word other than `1` decodes as `false`,
+    /// trailing words are ignored), so this catches payloads that don't
+    /// round-trip.
+    ///
+    /// ```
+    /// use ola_lang_abi::Abi;
+    ///
+    /// let abi: Abi = serde_json::from_str(r#"[{
+    ///     "type": "function",
+    ///     "name": "setPaused",
+    ///     "inputs": [{"name": "paused", "type": "bool"}],
+    ///     "outputs": []
+    /// }]"#).unwrap();
+    /// let method_id = abi.functions[0].method_id();
+    ///
+    /// let (f, params) = abi.decode_input_from_slice(&[1, 1, method_id]).unwrap();
+    /// assert!(params.verify_reencode(f, &[1, 1, method_id]).is_ok());
+    ///
+    /// let (f, params) = abi.decode_input_from_slice(&[2, 1, method_id]).unwrap();
+    /// assert!(params.verify_reencode(f, &[2, 1, method_id]).is_err());
+    /// ```
+    pub fn verify_reencode(&self, function: &Function, calldata: &[u64]) -> Result<()> {
+        let reencoded = self.reencode(function)?;
+
+        if let Some(at) = reencoded
+            .iter()
+            .zip(calldata)
+            .position(|(word, original)| word != original)
+        {
+            return Err(anyhow!(
+                "re-encoded calldata differs at word {}: {:#x}, originally {:#x}",
+                at,
+                reencoded[at],
+                calldata[at]
+            ));
+        }
+
+        if reencoded.len() != calldata.len() {
+            return Err(anyhow!(
+                "re-encoded calldata has {} words, originally {}",
+                reencoded.len(),
+                calldata.len()
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::{Abi, Value};
+
+    #[test]
+    fn reencode_decoded_params() {
+        let abi: Abi = serde_json::from_value(serde_json::json!([{
+            "type": "function",
+            "name": "post",
+            "inputs": [
+                { "name": "flag", "type": "bool" },
+                { "name": "text", "type": "string" }
+            ],
+            "outputs": []
+        }]))
+        .unwrap();
+        let f = &abi.functions[0];
+
+        let calldata = abi
+            .encode_input_with_signature(
+                &f.signature(),
+                &[Value::Bool(true), Value::String("ola".to_string())],
+            )
+            .unwrap();
+
+        let (_, params) = abi.decode_input_from_slice(&calldata).unwrap();
+        assert_eq!(params.reencode(f).unwrap(), calldata);
+        assert!(params.verify_reencode(f, &calldata).is_ok());
+
+        // a bool word of 2 decodes as false, which encodes as 0
+        let mut tampered = calldata.clone();
+        tampered[0] = 2;
+        let (_, params) = abi.decode_input_from_slice(&tampered).unwrap();
+        assert_eq!(
+            params
+                .verify_reencode(f, &tampered)
+                .unwrap_err()
+                .to_string(),
+            "re-encoded calldata differs at word 0: 0x0, originally 0x2"
+        );
+
+        let params = DecodedParams::from(vec![
+            (f.inputs[0].clone(), Value::Bool(true)),
+            (f.inputs[1].clone(), Value::U32(1)),
+        ]);
+        assert!(params.reencode(f).is_err());
+        assert!(DecodedParams::from(vec![]).reencode(f).is_err());
+    }
+}
@@ -0,0 +1,315 @@
+use anyhow::{anyhow, Result};
+use std::{collections::HashMap, path::Path};
+
+use crate::{
+    hash::{method_id_of, topic_of},
+    Abi, FixedArray4,
+};
+
+const MAGIC: &[u8; 6] = b"OLAREG";
+const FORMAT_VERSION: u8 = 1;
+
+/// Index of known function selectors and event topics to their signatures.
+///
+/// The index can be persisted with [`SelectorRegistry::to_bytes`] or
+/// [`SelectorRegistry::save`] and restored without rehashing any signature,
+/// which matters for services knowing hundreds of thousands of them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SelectorRegistry {
+    functions: HashMap<u64, Vec<String>>,
+    events: HashMap<FixedArray4, Vec<String>>,
+}
+
+impl SelectorRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers every function and event of the given ABI.
+    pub fn add_abi(&mut self, abi: &Abi) {
+        for f in &abi.functions {
+            self.insert_function(f.method_id(), f.signature());
+        }
+
+        for e in &abi.events {
+            self.insert_event(e.topic(), e.signature());
+        }
+    }
+
+    /// Registers a function signature, e.g. `transfer(address,u32)`.
+    pub fn add_function_signature(&mut self, signature: &str) {
+        self.insert_function(method_id_of(signature), signature.to_string());
+    }
+
+    /// Registers an event signature, e.g. `Transfer(address,address,u32)`.
+    pub fn add_event_signature(&mut self, signature: &str) {
+        self.insert_event(topic_of(signature), signature.to_string());
+    }
+
+    /// Registers a function signature under an already known selector.
+    pub fn insert_function(&mut self, selector: u64, signature: String) {
+        let signatures = self.functions.entry(selector).or_default();
+        if !signatures.contains(&signature) {
+            signatures.push(signature);
+        }
+    }
+
+    /// Registers an event signature under an already known topic.
+    pub fn insert_event(&mut self, topic: FixedArray4, signature: String) {
+        let signatures = self.events.entry(topic).or_default();
+        if !signatures.contains(&signature) {
+            signatures.push(signature);
+        }
+    }
+
+    /// Returns the function signatures known for a selector.
+    pub fn function_signatures(&self, selector: u64) -> &[String] {
+        self.functions
+            .get(&selector)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Returns the event signatures known for a topic.
+    pub fn event_signatures(&self, topic: &FixedArray4) -> &[String] {
+        self.events
+            .get(topic)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Number of registered function selectors.
+    pub fn functions_len(&self) -> usize {
+        self.functions.len()
+    }
+
+    /// Number of registered event topics.
+    pub fn events_len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Serializes the index into a compact binary representation.
+    ///
+    /// Entries are written sorted by selector and topic, so equal registries
+    /// serialize to the same bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = MAGIC.to_vec();
+        buf.push(FORMAT_VERSION);
+
+        let mut functions = self.functions.iter().collect::<Vec<_>>();
+        functions.sort_unstable_by_key(|(selector, _)| **selector);
+
+        buf.extend((functions.len() as u64).to_le_bytes());
+        for (selector, signatures) in functions {
+            buf.extend(selector.to_le_bytes());
+            write_signatures(&mut buf, signatures);
+        }
+
+        let mut events = self.events.iter().collect::<Vec<_>>();
+        events.sort_unstable_by_key(|(topic, _)| **topic);
+
+        buf.extend((events.len() as u64).to_le_bytes());
+        for (topic, signatures) in events {
+            for limb in topic.0 {
+                buf.extend(limb.to_le_bytes());
+            }
+            write_signatures(&mut buf, signatures);
+        }
+
+        buf
+    }
+
+    /// Deserializes an index produced by [`SelectorRegistry::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut reader = Reader(bytes);
+
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(anyhow!("not a selector registry"));
+        }
+
+        let version = reader.take(1)?[0];
+        if version != FORMAT_VERSION {
+            return Err(anyhow!(
+                "unsupported selector registry version: {}",
+                version
+            ));
+        }
+
+        let mut registry = Self::new();
+
+        for _ in 0..reader.u64()? {
+            let selector = reader.u64()?;
+            registry.functions.insert(selector, reader.signatures()?);
+        }
+
+        for _ in 0..reader.u64()? {
+            let topic = FixedArray4([reader.u64()?, reader.u64()?, reader.u64()?, reader.u64()?]);
+            registry.events.insert(topic, reader.signatures()?);
+        }
+
+        if !reader.0.is_empty() {
+            return Err(anyhow!("trailing bytes after selector registry"));
+        }
+
+        Ok(registry)
+    }
+
+    /// Writes the index to a file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        std::fs::write(path, self.to_bytes())?;
+
+        Ok(())
+    }
+
+    /// Reads an index written by [`SelectorRegistry::save`].
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+}
+
+fn write_signatures(buf: &mut Vec<u8>, signatures: &[String]) {
+    buf.extend((signatures.len() as u64).to_le_bytes());
+    for signature in signatures {
+        buf.extend((signature.len() as u64).to_le_bytes());
+        buf.extend(signature.as_bytes());
+    }
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.0.len() < len {
+            return Err(anyhow!(
+                "reached end of input while reading selector registry"
+            ));
+        }
+
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+
+        Ok(head)
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn signatures(&mut self) -> Result<Vec<String>> {
+        let count = self.u64()?;
+
+        (0..count)
+            .map(|_| {
+                let len = usize::try_from(self.u64()?)?;
+                Ok(String::from_utf8(self.take(len)?.to_vec())?)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    fn test_registry() -> SelectorRegistry {
+        let abi: Abi = serde_json::from_str(
+            r#"[
+                {
+                    "type": "function",
+                    "name": "createBook",
+                    "inputs": [
+                        { "name": "id", "type": "u32" },
+                        { "name": "name", "type": "string" }
+                    ],
+                    "outputs": []
+                },
+                {
+                    "type": "event",
+                    "name": "BookCreated",
+                    "inputs": [{ "name": "id", "type": "u32", "indexed": true }],
+                    "anonymous": false
+                }
+            ]"#,
+        )
+        .unwrap();
+
+        let mut registry = SelectorRegistry::new();
+        registry.add_abi(&abi);
+        registry.add_function_signature("transfer(address,u32)");
+
+        registry
+    }
+
+    #[test]
+    fn registry_lookup() {
+        let registry = test_registry();
+
+        assert_eq!(
+            registry.function_signatures(method_id_of("createBook(u32,string)")),
+            &["createBook(u32,string)".to_string()]
+        );
+        assert_eq!(
+            registry.event_signatures(&topic_of("BookCreated(u32)")),
+            &["BookCreated(u32)".to_string()]
+        );
+        assert!(registry.function_signatures(0).is_empty());
+    }
+
+    #[test]
+    fn registry_bytes_round_trip() {
+        let registry = test_registry();
+
+        let restored = SelectorRegistry::from_bytes(&registry.to_bytes()).unwrap();
+
+        assert_eq!(restored, registry);
+    }
+
+    #[test]
+    fn registry_bytes_are_stable() {
+        let signatures = (0..64).map(|i| format!("f{}(u32)", i)).collect::<Vec<_>>();
+        let registry = |signatures: &mut dyn Iterator<Item = &String>| {
+            let mut registry = SelectorRegistry::new();
+            for signature in signatures {
+                registry.add_function_signature(signature);
+                registry.add_event_signature(signature);
+            }
+            registry
+        };
+
+        let bytes = registry(&mut signatures.iter()).to_bytes();
+        assert_eq!(registry(&mut signatures.iter().rev()).to_bytes(), bytes);
+        assert_eq!(
+            SelectorRegistry::from_bytes(&bytes).unwrap().to_bytes(),
+            bytes
+        );
+
+        // sorted by selector
+        let first = u64::from_le_bytes(bytes[15..23].try_into().unwrap());
+        let min = signatures.iter().map(|s| method_id_of(s)).min().unwrap();
+        assert_eq!(first, min);
+    }
+
+    #[test]
+    fn registry_rejects_truncated_bytes() {
+        let bytes = test_registry().to_bytes();
+
+        assert!(SelectorRegistry::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(SelectorRegistry::from_bytes(b"garbage").is_err());
+    }
+
+    #[test]
+    fn registry_save_load() {
+        let registry = test_registry();
+        let path =
+            std::env::temp_dir().join(format!("ola-lang-abi-registry-{}.bin", std::process::id()));
+
+        registry.save(&path).unwrap();
+        let restored = SelectorRegistry::load(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(restored.unwrap(), registry);
+    }
+}
@@ -0,0 +1,223 @@
+use anyhow::{anyhow, Result};
+
+use crate::{DecodedParams, FixedArray8, Function, Type, Value};
+
+impl DecodedParams {
+    /// Migrates params decoded with `old_fn` inputs to the inputs of `new_fn`.
+    ///
+    /// Params are matched by name (by position when unnamed). Values are
+    /// widened when the type changed (`u32` to `field` or `u256`, `field` to
+    /// `u256`, `bool` to integers, fixed size arrays to dynamic arrays, tuple
+    /// members by name, map keys and values, values to optional values) and
+    /// params missing from the old version get their type's default value.
+    /// Any other type change is an error.
+    pub fn remap(&self, old_fn: &Function, new_fn: &Function) -> Result<DecodedParams> {
+        if self.len() != old_fn.inputs.len()
+            || self
+                .iter()
+                .zip(&old_fn.inputs)
+                .any(|(decoded, param)| decoded.param != *param)
+        {
+            return Err(anyhow!(
+                "decoded params don't match {} inputs",
+                old_fn.signature()
+            ));
+        }
+
+        new_fn
+            .inputs
+            .iter()
+            .enumerate()
+            .map(|(i, param)| {
+                let old = if param.name.is_empty() {
+                    self.get(i).filter(|old| old.param.name.is_empty())
+                } else {
+                    self.iter().find(|old| old.param.name == param.name)
+                };
+
+                let value = match old {
+                    Some(old) => migrate_value(&old.value, &param.type_).ok_or_else(|| {
+                        anyhow!(
+                            "cannot migrate param {:?} from {} to {}",
+                            param.name,
+                            old.param.type_,
+                            param.type_
+                        )
+                    })?,
+                    None => Value::default_for(&param.type_)?,
+                };
+
+                Ok((param.clone(), value))
+            })
+            .collect::<Result<Vec<_>>>()
+            .map(DecodedParams::from)
+    }
+}
+
+/// Converts a value to the given type, if allowed by the widening rules of
+/// [`DecodedParams::remap`].
+fn migrate_value(value: &Value, ty: &Type) -> Option<Value> {
+    if value.type_of() == *ty {
+        return Some(value.clone());
+    }
+
+    let u256 = |n: u64| {
+        let mut limbs = [0; 8];
+        limbs[6] = n >> 32;
+        limbs[7] = n & 0xffff_ffff;
+        Value::U256(FixedArray8(limbs))
+    };
+
+    match (value, ty) {
+        (Value::U32(n), Type::Field) => Some(Value::Field(*n)),
+        (Value::U32(n), Type::U256) | (Value::Field(n), Type::U256) => Some(u256(*n)),
+        (Value::Bool(b), Type::U32) => Some(Value::U32(*b as u64)),
+        (Value::Bool(b), Type::Field) => Some(Value::Field(*b as u64)),
+        (Value::FixedArray(values, _), Type::FixedArray(elem_ty, size))
+            if values.len() as u64 == *size =>
+        {
+            migrate_values(values, elem_ty)
+                .map(|values| Value::FixedArray(values, *elem_ty.clone()))
+        }
+        (Value::FixedArray(values, _) | Value::Array(values, _), Type::Array(elem_ty)) => {
+            migrate_values(values, elem_ty).map(|values| Value::Array(values, *elem_ty.clone()))
+        }
+        (Value::Tuple(members), Type::Tuple(tys)) => tys
+            .iter()
+            .map(|(name, ty)| {
+                let value = match members.iter().find(|(member, _)| member == name) {
+                    Some((_, value)) => migrate_value(value, ty)?,
+                    None => Value::default_for(ty).ok()?,
+                };
+
+                Some((name.clone(), value))
+            })
+            .collect::<Option<Vec<_>>>()
+            .map(Value::Tuple),
+        (Value::Map(entries, ..), Type::Map(key_ty, value_ty)) => entries
+            .iter()
+            .map(|(key, value)| {
+                Some((migrate_value(key, key_ty)?, migrate_value(value, value_ty)?))
+            })
+            .collect::<Option<Vec<_>>>()
+            .map(|entries| Value::Map(entries, *key_ty.clone(), *value_ty.clone())),
+        (Value::Some(value), Type::Option(ty)) => {
+            migrate_value(value, ty).map(|value| Value::Some(Box::new(value)))
+        }
+        (Value::None(_), Type::Option(ty)) => Some(Value::None(*ty.clone())),
+        (value, Type::Option(ty)) => {
+            migrate_value(value, ty).map(|value| Value::Some(Box::new(value)))
+        }
+        _ => None,
+    }
+}
+
+fn migrate_values(values: &[Value], ty: &Type) -> Option<Vec<Value>> {
+    values
+        .iter()
+        .map(|value| migrate_value(value, ty))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::{Param, StateMutability};
+
+    fn function(name: &str, inputs: Vec<(&str, Type)>) -> Function {
+        Function {
+            name: name.to_string(),
+            inputs: inputs
+                .into_iter()
+                .map(|(name, type_)| Param {
+                    name: name.to_string(),
+                    type_,
+                    indexed: None,
+                    docs: None,
+                })
+                .collect(),
+            outputs: vec![],
+            selector: None,
+            state_mutability: StateMutability::NonPayable,
+            docs: None,
+            provenance: None,
+        }
+    }
+
+    #[test]
+    fn remap_decoded_params() {
+        let old_fn = function(
+            "f",
+            vec![
+                ("amount", Type::U32),
+                ("to", Type::Address),
+                ("ids", Type::FixedArray(Box::new(Type::U32), 2)),
+                ("dropped", Type::Bool),
+            ],
+        );
+        let new_fn = function(
+            "f",
+            vec![
+                ("to", Type::Address),
+                ("amount", Type::U256),
+                ("memo", Type::String),
+                ("ids", Type::Array(Box::new(Type::Field))),
+            ],
+        );
+
+        let to = crate::FixedArray4([1, 2, 3, 4]);
+        let decoded = DecodedParams::from(
+            old_fn
+                .inputs
+                .iter()
+                .cloned()
+                .zip(vec![
+                    Value::U32(7),
+                    Value::Address(to),
+                    Value::FixedArray(vec![Value::U32(1), Value::U32(2)], Type::U32),
+                    Value::Bool(true),
+                ])
+                .collect::<Vec<_>>(),
+        );
+
+        let remapped = decoded.remap(&old_fn, &new_fn).expect("remap failed");
+
+        assert_eq!(
+            remapped,
+            DecodedParams::from(
+                new_fn
+                    .inputs
+                    .iter()
+                    .cloned()
+                    .zip(vec![
+                        Value::Address(to),
+                        Value::U256(FixedArray8([0, 0, 0, 0, 0, 0, 0, 7])),
+                        Value::String(String::new()),
+                        Value::Array(vec![Value::Field(1), Value::Field(2)], Type::Field),
+                    ])
+                    .collect::<Vec<_>>()
+            )
+        );
+    }
+
+    #[test]
+    fn remap_rejects_narrowing() {
+        let old_fn = function("f", vec![("amount", Type::Field)]);
+        let new_fn = function("f", vec![("amount", Type::U32)]);
+        let decoded = DecodedParams::from(vec![(old_fn.inputs[0].clone(), Value::Field(7))]);
+
+        assert!(decoded.remap(&old_fn, &new_fn).is_err());
+        assert!(decoded.remap(&new_fn, &old_fn).is_err());
+
+        let optional_fn = function("f", vec![("amount", Type::Option(Box::new(Type::U256)))]);
+        let remapped = decoded.remap(&old_fn, &optional_fn).unwrap();
+        assert_eq!(
+            remapped[0].value,
+            Value::Some(Box::new(Value::U256(FixedArray8([0, 0, 0, 0, 0, 0, 0, 7]))))
+        );
+        assert!(remapped.remap(&optional_fn, &old_fn).is_err());
+    }
+}
@@ -0,0 +1,235 @@
+use std::{collections::HashMap, sync::RwLock};
+
+use crate::Function;
+
+/// Index of [`Abi::functions`](crate::Abi::functions) by method id and
+/// signature, built on the first lookup, see
+/// [`Abi::function_by_selector`](crate::Abi::function_by_selector).
+///
+/// Lookups, misses included, are answered by the index alone. The functions
+/// are public though, so the index keeps a copy of those it was built from:
+/// it is rebuilt when their number changes or when a function it finds
+/// differs from its copy, without hashing any signature. Functions edited in
+/// place under a new signature are only found once the index is rebuilt.
+///
+/// The index is built once, even when the first lookups race on several
+/// threads, so a parsed [`Abi`](crate::Abi) can be shared by workers.
+#[derive(Debug, Default)]
+pub(crate) struct SelectorMap {
+    index: RwLock<Option<SelectorIndex>>,
+}
+
+#[derive(Debug, Clone)]
+struct SelectorIndex {
+    functions: Vec<Function>,
+    by_selector: HashMap<u64, Vec<usize>>,
+    by_signature: HashMap<String, usize>,
+}
+
+impl SelectorMap {
+    /// Positions of the functions with the method id, in order.
+    pub(crate) fn selector(&self, functions: &[Function], method_id: u64) -> Vec<usize> {
+        self.lookup(functions, |index| match index.by_selector.get(&method_id) {
+            Some(hits) => index.fresh(functions, hits).then(|| hits.clone()),
+            None => Some(vec![]),
+        })
+    }
+
+    /// Position of the first function with the signature.
+    pub(crate) fn signature(&self, functions: &[Function], signature: &str) -> Option<usize> {
+        self.lookup(functions, |index| match index.by_signature.get(signature) {
+            Some(&hit) => index.fresh(functions, &[hit]).then_some(Some(hit)),
+            None => Some(None),
+        })
+    }
+
+    /// Forgets the index, after the functions were changed.
+    pub(crate) fn reset(&mut self) {
+        *self.index.get_mut().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    /// Looks up the index, `None` meaning that the functions it found
+    /// changed, built for the functions first if missing or stale.
+    fn lookup<T: Default>(
+        &self,
+        functions: &[Function],
+        lookup: impl Fn(&SelectorIndex) -> Option<T>,
+    ) -> T {
+        let fits = |index: &Option<SelectorIndex>| {
+            index
+                .as_ref()
+                .filter(|index| index.functions.len() == functions.len())
+                .and_then(&lookup)
+        };
+
+        if let Some(found) = fits(&self.index.read().unwrap_or_else(|e| e.into_inner())) {
+            return found;
+        }
+
+        let mut index = self.index.write().unwrap_or_else(|e| e.into_inner());
+        // another thread may have rebuilt it meanwhile
+        if let Some(found) = fits(&index) {
+            return found;
+        }
+
+        // a fresh index finds the functions it was built from
+        lookup(index.insert(SelectorIndex::new(functions))).unwrap_or_default()
+    }
+}
+
+impl SelectorIndex {
+    fn new(functions: &[Function]) -> Self {
+        let mut by_selector = HashMap::<u64, Vec<usize>>::new();
+        let mut by_signature = HashMap::new();
+
+        for (i, f) in functions.iter().enumerate() {
+            by_selector.entry(f.method_id()).or_default().push(i);
+            by_signature.entry(f.signature()).or_insert(i);
+        }
+
+        Self {
+            functions: functions.to_vec(),
+            by_selector,
+            by_signature,
+        }
+    }
+
+    /// Whether the functions at the positions are those indexed.
+    fn fresh(&self, functions: &[Function], positions: &[usize]) -> bool {
+        positions
+            .iter()
+            .all(|&i| functions.get(i) == self.functions.get(i))
+    }
+}
+
+impl Clone for SelectorMap {
+    fn clone(&self) -> Self {
+        let index = self.index.read().unwrap_or_else(|e| e.into_inner());
+
+        Self {
+            index: RwLock::new(index.clone()),
+        }
+    }
+}
+
+/// A cache of the functions: all maps compare equal.
+impl PartialEq for SelectorMap {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for SelectorMap {}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Barrier};
+
+    use pretty_assertions::assert_eq;
+
+    use crate::{Abi, Value};
+
+    #[test]
+    fn shared_abi_builds_index_once() {
+        let abi: Abi = serde_json::from_value(serde_json::json!([
+            {
+                "type": "function",
+                "name": "transfer",
+                "inputs": [{ "name": "amount", "type": "u32" }],
+                "outputs": []
+            },
+            {
+                "type": "function",
+                "name": "approve",
+                "inputs": [{ "name": "paused", "type": "bool" }],
+                "outputs": []
+            }
+        ]))
+        .unwrap();
+        let abi = Arc::new(abi);
+        let workers = 8;
+        let barrier = Arc::new(Barrier::new(workers));
+
+        let handles = (0..workers)
+            .map(|i| {
+                let (abi, barrier) = (abi.clone(), barrier.clone());
+
+                std::thread::spawn(move || {
+                    let f = &abi.functions[i % 2];
+                    let value = match i % 2 {
+                        0 => Value::U32(i as u64),
+                        _ => Value::Bool(true),
+                    };
+
+                    // race on the first lookup, which builds the index
+                    barrier.wait();
+                    let input = abi
+                        .encode_input_with_signature(&f.signature(), std::slice::from_ref(&value))
+                        .unwrap();
+                    let (decoded, params) = abi.decode_input_from_slice(&input).unwrap();
+
+                    assert_eq!(decoded, f);
+                    assert_eq!(params[0].value, value);
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let index = abi.selectors.index.read().unwrap();
+        let index = index.as_ref().unwrap();
+        assert_eq!(index.functions.len(), 2);
+        assert_eq!(index.by_signature["approve(bool)"], 1);
+    }
+
+    #[test]
+    fn edited_functions_rebuild_index() {
+        let mut abi = Abi::from_human_readable(&["function a()", "function c(u32 x)"]).unwrap();
+        assert!(abi.function_by_signature("a()").is_some());
+
+        // renamed in place: found once a lookup of the old name rebuilds
+        abi.functions[0].name = "b".to_string();
+        assert!(abi.function_by_signature("a()").is_none());
+        assert_eq!(abi.function_by_signature("b()"), Some(&abi.functions[0]));
+        let method_id = abi.functions[0].method_id();
+        assert_eq!(abi.function_by_selector(method_id), Some(&abi.functions[0]));
+
+        // pushed directly
+        let mut d = abi.functions[1].clone();
+        d.name = "d".to_string();
+        abi.functions.push(d);
+        assert_eq!(abi.function_by_signature("d(u32)"), Some(&abi.functions[2]));
+        let index = abi.selectors.index.read().unwrap();
+        assert_eq!(index.as_ref().unwrap().functions, abi.functions);
+        drop(index);
+
+        // removed
+        abi.functions.swap_remove(0);
+        assert!(abi.function_by_signature("b()").is_none());
+        assert_eq!(abi.function_by_signature("d(u32)"), Some(&abi.functions[0]));
+        assert!(abi.function_by_selector(method_id).is_none());
+    }
+
+    #[test]
+    fn misses_are_answered_by_the_index() {
+        let abi = Abi::from_human_readable(&["function a()", "function b(u32 x)"]).unwrap();
+        assert!(abi.function_by_selector(1).is_none());
+
+        // the index is built once, and not rebuilt by misses
+        let built = abi
+            .selectors
+            .index
+            .read()
+            .unwrap()
+            .as_ref()
+            .unwrap()
+            .by_selector
+            .len();
+        assert_eq!(built, 2);
+        assert!(abi.function_by_signature("c()").is_none());
+        assert!(abi.function_by_selector(2).is_none());
+        assert_eq!(abi.function_by_signature("b(u32)"), Some(&abi.functions[1]));
+    }
+}
@@ -0,0 +1,271 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    error::{AbiError, Result},
+    Abi, Encoder, Type, Value,
+};
+
+/// Calldata with named slots left to fill in, see [`Abi::encode_template`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CalldataTemplate {
+    /// Calldata `[params..., param-len, method_id]`, placeholders holding
+    /// the default value of their type.
+    pub calldata: Vec<u64>,
+    /// Slots by placeholder name.
+    pub patches: BTreeMap<String, Patch>,
+}
+
+/// Slot of a placeholder in a [`CalldataTemplate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Patch {
+    /// Type of the value to fill in.
+    pub type_: Type,
+    /// Word offsets of the placeholder occurrences in the calldata, each
+    /// spanning the static size of the type.
+    pub offsets: Vec<usize>,
+}
+
+impl Abi {
+    /// Encodes a function call whose params contain
+    /// [`Value::Placeholder`]s, recording where each one lands so the final
+    /// values can be spliced in later without encoding the call again, e.g.
+    /// the nonce of a relayed meta transaction.
+    ///
+    /// Placeholders must have static types, as values of dynamic types
+    /// could change the length of the calldata. A name may be used more than
+    /// once, with the same type.
+    ///
+    /// ```
+    /// use ola_lang_abi::{Abi, FixedArray4, Type, Value};
+    ///
+    /// let abi: Abi = serde_json::from_str(r#"[{
+    ///     "type": "function",
+    ///     "name": "relay",
+    ///     "inputs": [
+    ///         { "name": "memo", "type": "string" },
+    ///         { "name": "to", "type": "address" },
+    ///         { "name": "nonce", "type": "u32" }
+    ///     ],
+    ///     "outputs": []
+    /// }]"#).unwrap();
+    ///
+    /// let template = abi.encode_template("relay(string,address,u32)", &[
+    ///     Value::String("hi".to_string()),
+    ///     Value::Placeholder("to".to_string(), Type::Address),
+    ///     Value::Placeholder("nonce".to_string(), Type::U32),
+    /// ]).unwrap();
+    /// assert_eq!(template.patches["nonce"].offsets, [7]);
+    ///
+    /// let to = Value::Address(FixedArray4([0, 0, 0, 1]));
+    /// let calldata = template.fill(&[("to", to.clone()), ("nonce", Value::U32(5))]).unwrap();
+    ///
+    /// assert_eq!(
+    ///     calldata,
+    ///     abi.encode_input_with_signature(
+    ///         "relay(string,address,u32)",
+    ///         &[Value::String("hi".to_string()), to, Value::U32(5)],
+    ///     ).unwrap()
+    /// );
+    /// ```
+    pub fn encode_template(&self, signature: &str, params: &[Value]) -> Result<CalldataTemplate> {
+        let f = self
+            .function_by_signature(signature)
+            .ok_or(AbiError::FunctionNotFound)?;
+
+        if f.inputs.len() != params.len() {
+            return Err(AbiError::LengthMismatch(format!(
+                "expected {} values, got {}",
+                f.inputs.len(),
+                params.len()
+            )));
+        }
+        for (param, value) in f.inputs.iter().zip(params) {
+            value.check_type(&param.type_)?;
+        }
+
+        let mut template = CalldataTemplate {
+            calldata: vec![],
+            patches: BTreeMap::new(),
+        };
+        for value in params {
+            template.encode(value)?;
+        }
+        template.calldata.push(template.calldata.len() as u64);
+        template.calldata.push(f.method_id());
+
+        Ok(template)
+    }
+}
+
+impl CalldataTemplate {
+    fn encode(&mut self, value: &Value) -> Result<()> {
+        match value {
+            Value::Placeholder(name, ty) => {
+                if ty.static_size().is_none() {
+                    return Err(AbiError::InvalidInput(format!(
+                        "placeholder {} has dynamic type {}",
+                        name, ty
+                    )));
+                }
+
+                let patch = self.patches.entry(name.clone()).or_insert_with(|| Patch {
+                    type_: ty.clone(),
+                    offsets: vec![],
+                });
+                if patch.type_ != *ty {
+                    return Err(AbiError::TypeMismatch {
+                        expected: patch.type_.clone(),
+                        found: ty.clone(),
+                    });
+                }
+                patch.offsets.push(self.calldata.len());
+
+                Encoder::new(&mut self.calldata).encode(std::slice::from_ref(value));
+            }
+            Value::FixedArray(values, _) => {
+                values.iter().try_for_each(|value| self.encode(value))?
+            }
+            Value::Array(values, _) => {
+                self.calldata.push(values.len() as u64);
+                values.iter().try_for_each(|value| self.encode(value))?;
+            }
+            Value::Tuple(members) => members
+                .iter()
+                .try_for_each(|(_, value)| self.encode(value))?,
+            _ => Encoder::new(&mut self.calldata).encode(std::slice::from_ref(value)),
+        }
+
+        Ok(())
+    }
+
+    /// Writes the value of a placeholder into every slot of it.
+    pub fn patch(&mut self, name: &str, value: &Value) -> Result<()> {
+        let patch = self
+            .patches
+            .get(name)
+            .ok_or_else(|| AbiError::InvalidInput(format!("no placeholder named {}", name)))?;
+        value.check_type(&patch.type_)?;
+
+        let words = Value::encode(std::slice::from_ref(value));
+        for &offset in &patch.offsets {
+            self.calldata[offset..offset + words.len()].copy_from_slice(&words);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the calldata with every placeholder filled in, failing on
+    /// missing or unknown names.
+    pub fn fill(&self, values: &[(&str, Value)]) -> Result<Vec<u64>> {
+        let mut filled = self.clone();
+        for (name, value) in values {
+            filled.patch(name, value)?;
+        }
+
+        if let Some(name) = self
+            .patches
+            .keys()
+            .find(|name| !values.iter().any(|(filled, _)| filled == name))
+        {
+            return Err(AbiError::InvalidInput(format!(
+                "placeholder {} isn't filled in",
+                name
+            )));
+        }
+
+        Ok(filled.calldata)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn template_patches() {
+        let abi: Abi = serde_json::from_str(
+            r#"[{
+                "type": "function",
+                "name": "batch",
+                "inputs": [
+                    { "name": "tags", "type": "string[]" },
+                    {
+                        "name": "call",
+                        "type": "tuple",
+                        "components": [
+                            { "name": "to", "type": "address" },
+                            { "name": "amount", "type": "u256" }
+                        ]
+                    },
+                    { "name": "refund", "type": "address" }
+                ],
+                "outputs": []
+            }]"#,
+        )
+        .unwrap();
+        let signature = "batch(string[],(address,u256),address)";
+        let to = || Value::Placeholder("to".to_string(), Type::Address);
+        let tags = Value::Array(vec![Value::String("a".to_string())], Type::String);
+
+        let template = abi
+            .encode_template(
+                signature,
+                &[
+                    tags.clone(),
+                    Value::Tuple(vec![
+                        ("to".to_string(), to()),
+                        ("amount".to_string(), Value::U256(7u64.into())),
+                    ]),
+                    to(),
+                ],
+            )
+            .unwrap();
+        assert_eq!(template.patches["to"].offsets, [3, 15]);
+
+        let address = Value::Address(crate::FixedArray4([1, 2, 3, 4]));
+        assert_eq!(
+            template.fill(&[("to", address.clone())]).unwrap(),
+            abi.encode_input_with_signature(
+                signature,
+                &[
+                    tags.clone(),
+                    Value::Tuple(vec![
+                        ("to".to_string(), address.clone()),
+                        ("amount".to_string(), Value::U256(7u64.into())),
+                    ]),
+                    address,
+                ],
+            )
+            .unwrap()
+        );
+
+        assert!(template.fill(&[]).is_err());
+        assert!(template.fill(&[("to", Value::U32(1))]).is_err());
+        assert!(template
+            .fill(&[
+                ("to", Value::Address(crate::FixedArray4([0; 4]))),
+                ("x", Value::U32(1))
+            ])
+            .is_err());
+
+        // placeholders can't change the calldata length
+        let tuple = |to| {
+            Value::Tuple(vec![
+                ("to".to_string(), to),
+                ("amount".to_string(), Value::U256(7u64.into())),
+            ])
+        };
+        assert!(abi
+            .encode_template(
+                signature,
+                &[
+                    Value::Placeholder("tags".to_string(), Type::Array(Box::new(Type::String))),
+                    tuple(to()),
+                    to(),
+                ],
+            )
+            .is_err());
+    }
+}
@@ -0,0 +1,158 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    calldata,
+    error::{AbiError, Result},
+    Abi, DecodedParams,
+};
+
+/// Transaction JSON of the Ola node; only the fields below are read.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NodeTransaction {
+    #[serde(default)]
+    hash: Option<String>,
+    #[serde(default, alias = "initiatorAddress")]
+    from: Option<String>,
+    #[serde(default, alias = "contractAddress")]
+    to: Option<String>,
+    #[serde(alias = "data", alias = "calldata")]
+    input: String,
+}
+
+/// Transaction decoded by [`Abi::decode_transaction`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DecodedTransaction {
+    /// Transaction hash, as given by the node.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+    /// Sender address, as given by the node.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    /// Called contract address, as given by the node.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<String>,
+    /// Called function signature.
+    pub function: String,
+    /// Called function method id.
+    pub method_id: u64,
+    /// Decoded function params.
+    pub params: DecodedParams,
+}
+
+impl Abi {
+    /// Decodes the call of a transaction, as returned by the Ola node.
+    ///
+    /// The calldata is read from the `input` field (or `data`, `calldata`)
+    /// as hex words, see [`calldata::from_hex`]; `hash`, `from` and `to`
+    /// (or `initiatorAddress`, `contractAddress`) are passed through.
+    ///
+    /// ```
+    /// use ola_lang_abi::{calldata, Abi, Value};
+    ///
+    /// let abi: Abi = serde_json::from_str(r#"[{
+    ///     "type": "function",
+    ///     "name": "setCount",
+    ///     "inputs": [{"name": "count", "type": "u32"}],
+    ///     "outputs": []
+    /// }]"#).unwrap();
+    ///
+    /// let input = abi
+    ///     .encode_input_with_signature("setCount(u32)", &[Value::U32(7)])
+    ///     .unwrap();
+    /// let tx_json = format!(
+    ///     r#"{{"hash": "0x01", "input": "{}"}}"#,
+    ///     calldata::to_hex(&input)
+    /// );
+    ///
+    /// let tx = abi.decode_transaction(&tx_json).unwrap();
+    /// assert_eq!(tx.function, "setCount(u32)");
+    /// assert_eq!(tx.params[0].value, Value::U32(7));
+    /// ```
+    pub fn decode_transaction(&self, tx_json: &str) -> Result<DecodedTransaction> {
+        let tx: NodeTransaction = serde_json::from_str(tx_json)
+            .map_err(|e| AbiError::InvalidInput(format!("invalid transaction JSON: {}", e)))?;
+
+        let input = calldata::from_hex(&tx.input)
+            .map_err(|e| AbiError::InvalidInput(format!("invalid transaction input: {}", e)))?;
+
+        let (function, params) = self.decode_input_from_slice(&input)?;
+
+        Ok(DecodedTransaction {
+            hash: tx.hash,
+            from: tx.from,
+            to: tx.to,
+            function: function.signature(),
+            method_id: function.method_id(),
+            params,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::{FixedArray4, Value};
+
+    #[test]
+    fn abi_decode_transaction() {
+        let abi: Abi = serde_json::from_str(
+            r#"[{
+                "type": "function",
+                "name": "transfer",
+                "inputs": [{"name": "to", "type": "address"}, {"name": "amount", "type": "u32"}],
+                "outputs": []
+            }]"#,
+        )
+        .unwrap();
+
+        let f = &abi.functions[0];
+        let input = abi
+            .encode_input_with_signature(
+                &f.signature(),
+                &[Value::Address(FixedArray4([0, 0, 0, 9])), Value::U32(5)],
+            )
+            .unwrap();
+
+        let tx = abi
+            .decode_transaction(&format!(
+                r#"{{
+                    "hash": "0xabc",
+                    "initiatorAddress": "0x01",
+                    "contractAddress": "0x02",
+                    "nonce": 3,
+                    "data": "{}"
+                }}"#,
+                calldata::to_hex(&input)
+            ))
+            .unwrap();
+
+        assert_eq!(
+            tx,
+            DecodedTransaction {
+                hash: Some("0xabc".to_string()),
+                from: Some("0x01".to_string()),
+                to: Some("0x02".to_string()),
+                function: "transfer(address,u32)".to_string(),
+                method_id: f.method_id(),
+                params: f
+                    .decode_input_from_slice(&input[..input.len() - 2])
+                    .unwrap(),
+            }
+        );
+        assert_eq!(
+            serde_json::to_value(&tx).unwrap()["function"],
+            "transfer(address,u32)"
+        );
+
+        assert!(abi.decode_transaction(r#"{"hash": "0xabc"}"#).is_err());
+        assert!(abi
+            .decode_transaction(r#"{"input": "0x123"}"#)
+            .unwrap_err()
+            .to_string()
+            .starts_with("invalid transaction input"));
+    }
+}
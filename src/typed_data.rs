@@ -0,0 +1,173 @@
+use anyhow::Result;
+
+use crate::{hash::poseidon_words, hash_struct, FixedArray4, ToValue, Value};
+
+/// Struct name of signing domains.
+pub const DOMAIN_TYPE: &str = "OlaDomain";
+
+/// Word prefixing digests, telling them apart from transaction hashes.
+const DIGEST_PREFIX: u64 = 0x1901;
+
+/// Signing domain, i.e. which dapp and chain a signature is meant for.
+///
+/// Only the given fields are part of the domain struct, in the order
+/// `name`, `version`, `chainId`, `verifyingContract`, `salt`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Domain {
+    /// Dapp or protocol name.
+    pub name: Option<String>,
+    /// Signing domain version.
+    pub version: Option<String>,
+    /// Chain id.
+    pub chain_id: Option<u64>,
+    /// Contract verifying the signature.
+    pub verifying_contract: Option<FixedArray4>,
+    /// Disambiguating salt.
+    pub salt: Option<FixedArray4>,
+}
+
+impl ToValue for Domain {
+    fn to_value(&self) -> Value {
+        let mut members = vec![];
+
+        if let Some(name) = &self.name {
+            members.push(("name".to_string(), Value::String(name.clone())));
+        }
+        if let Some(version) = &self.version {
+            members.push(("version".to_string(), Value::String(version.clone())));
+        }
+        if let Some(chain_id) = self.chain_id {
+            members.push(("chainId".to_string(), Value::U32(chain_id)));
+        }
+        if let Some(contract) = self.verifying_contract {
+            members.push(("verifyingContract".to_string(), Value::Address(contract)));
+        }
+        if let Some(salt) = self.salt {
+            members.push(("salt".to_string(), Value::Hash(salt)));
+        }
+
+        Value::Tuple(members)
+    }
+}
+
+/// Typed structured data to be signed, the Ola analogue of EIP-712.
+///
+/// ```
+/// use ola_lang_abi::{Domain, TypedData, Value};
+///
+/// let domain = Domain {
+///     name: Some("Books".to_string()),
+///     chain_id: Some(1),
+///     ..Default::default()
+/// };
+/// let order = Value::Tuple(vec![
+///     ("id".to_string(), Value::U32(60)),
+///     ("title".to_string(), Value::String("olavm".to_string())),
+/// ]);
+///
+/// let digest = TypedData::new(&domain, "Order", order).digest().unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypedData {
+    /// Domain struct, see [`Domain`].
+    pub domain: Value,
+    /// Struct name of the message.
+    pub primary_type: String,
+    /// Message struct.
+    pub message: Value,
+}
+
+impl TypedData {
+    /// Creates typed data from a domain and a `Value::Tuple` message.
+    pub fn new(domain: &impl ToValue, primary_type: impl Into<String>, message: Value) -> Self {
+        Self {
+            domain: domain.to_value(),
+            primary_type: primary_type.into(),
+            message,
+        }
+    }
+
+    /// Returns the struct hash of the domain.
+    pub fn domain_separator(&self) -> Result<FixedArray4> {
+        hash_struct(DOMAIN_TYPE, &self.domain)
+    }
+
+    /// Returns the struct hash of the message.
+    pub fn message_hash(&self) -> Result<FixedArray4> {
+        hash_struct(&self.primary_type, &self.message)
+    }
+
+    /// Returns the digest to sign: the poseidon hash of the `0x1901` prefix
+    /// word, the domain separator and the message hash.
+    pub fn digest(&self) -> Result<FixedArray4> {
+        let mut words = vec![DIGEST_PREFIX];
+        words.extend(self.domain_separator()?.0);
+        words.extend(self.message_hash()?.0);
+
+        Ok(poseidon_words(&words))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    fn domain(chain_id: u64) -> Domain {
+        Domain {
+            name: Some("Books".to_string()),
+            version: Some("1".to_string()),
+            chain_id: Some(chain_id),
+            verifying_contract: Some(FixedArray4([0, 0, 0, 1])),
+            salt: None,
+        }
+    }
+
+    fn order(id: u64) -> Value {
+        Value::Tuple(vec![
+            ("id".to_string(), Value::U32(id)),
+            ("title".to_string(), Value::String("olavm".to_string())),
+        ])
+    }
+
+    #[test]
+    fn domain_struct() {
+        assert_eq!(
+            domain(1)
+                .to_value()
+                .type_of()
+                .struct_signature(DOMAIN_TYPE)
+                .unwrap(),
+            "OlaDomain(string name,string version,u32 chainId,address verifyingContract)"
+        );
+        assert_eq!(Domain::default().to_value(), Value::Tuple(vec![]));
+    }
+
+    #[test]
+    fn typed_data_digest() {
+        let typed_data = TypedData::new(&domain(1), "Order", order(60));
+        let digest = typed_data.digest().unwrap();
+
+        assert_eq!(
+            typed_data.message_hash().unwrap(),
+            hash_struct("Order", &order(60)).unwrap()
+        );
+        assert_ne!(
+            digest,
+            TypedData::new(&domain(2), "Order", order(60))
+                .digest()
+                .unwrap()
+        );
+        assert_ne!(
+            digest,
+            TypedData::new(&domain(1), "Order", order(61))
+                .digest()
+                .unwrap()
+        );
+
+        let not_a_struct = TypedData::new(&domain(1), "Order", Value::Bool(true));
+        assert!(not_a_struct.digest().is_err());
+        assert!(not_a_struct.domain_separator().is_ok());
+    }
+}
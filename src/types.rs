@@ -1,5 +1,13 @@
+use crate::alloc_prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{AbiError, Result},
+    MAX_TYPE_DEPTH,
+};
+
 /// Available ABI types.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum Type {
     /// Unsigned int type uint32.
     U32,
@@ -23,6 +31,16 @@ pub enum Type {
     Array(Box<Type>),
     /// Tuple type (tuple(T1, T2, ..., Tn))
     Tuple(Vec<(String, Type)>),
+    /// Optional value type (T?), encoded as a presence flag word, `0` or
+    /// `1`, followed by the value when present.
+    Option(Box<Type>),
+    /// Key-value map type (map(K,V)), encoded as the number of entries
+    /// followed by each key and its value.
+    Map(Box<Type>, Box<Type>),
+    /// Signed int type int32, encoded as a field element.
+    I32,
+    /// Signed int type int64, encoded as a field element.
+    I64,
 }
 
 impl Type {
@@ -32,6 +50,8 @@ impl Type {
             Type::U32 => false,
             Type::U256 => false,
             Type::Field => false,
+            Type::I32 => false,
+            Type::I64 => false,
             Type::Address => false,
             Type::Hash => false,
             Type::Bool => false,
@@ -40,16 +60,119 @@ impl Type {
             Type::Fields => true,
             Type::Array(_) => true,
             Type::Tuple(tys) => tys.iter().any(|(_, ty)| ty.is_dynamic()),
+            Type::Option(_) => true,
+            Type::Map(..) => true,
+        }
+    }
+
+    /// Returns the number of words the type encodes to, or `None` for
+    /// dynamic types, whose size depends on the value.
+    ///
+    /// ```
+    /// use ola_lang_abi::Type;
+    ///
+    /// assert_eq!(Type::U256.static_size(), Some(8));
+    /// assert_eq!(Type::FixedArray(Box::new(Type::Address), 2).static_size(), Some(8));
+    /// assert_eq!(Type::String.static_size(), None);
+    /// ```
+    pub fn static_size(&self) -> Option<u64> {
+        match self {
+            Type::U32 | Type::Field | Type::I32 | Type::I64 | Type::Bool => Some(1),
+            Type::Address | Type::Hash => Some(4),
+            Type::U256 => Some(8),
+            Type::FixedArray(ty, size) => ty.static_size()?.checked_mul(*size),
+            Type::String | Type::Fields | Type::Array(_) | Type::Option(_) | Type::Map(..) => None,
+            Type::Tuple(tys) => tys
+                .iter()
+                .try_fold(0u64, |size, (_, ty)| size.checked_add(ty.static_size()?)),
+        }
+    }
+
+    /// Returns the struct signature of a tuple type, e.g.
+    /// `Order(u32 id,string title)`, as hashed for typed structured data.
+    ///
+    /// Fails for non-tuple types, invalid or missing struct and member names,
+    /// and tuples with nested tuple members.
+    ///
+    /// ```
+    /// use ola_lang_abi::Type;
+    ///
+    /// let order = Type::Tuple(vec![
+    ///     ("id".to_string(), Type::U32),
+    ///     ("title".to_string(), Type::String),
+    /// ]);
+    ///
+    /// assert_eq!(order.struct_signature("Order").unwrap(), "Order(u32 id,string title)");
+    /// ```
+    pub fn struct_signature(&self, name: &str) -> Result<String> {
+        let members = match self {
+            Type::Tuple(members) => members,
+            _ => {
+                return Err(AbiError::InvalidInput(format!(
+                    "struct {} must be a tuple, got {}",
+                    name, self
+                )))
+            }
+        };
+
+        if !is_identifier(name) {
+            return Err(AbiError::InvalidInput(format!(
+                "invalid struct name {:?}",
+                name
+            )));
+        }
+
+        let members = members
+            .iter()
+            .map(|(member, ty)| {
+                if !is_identifier(member) {
+                    return Err(AbiError::InvalidInput(format!(
+                        "invalid member name {:?} in struct {}",
+                        member, name
+                    )));
+                }
+
+                if ty.contains_tuple() {
+                    return Err(AbiError::InvalidInput(format!(
+                        "nested struct member {}.{} is not supported",
+                        name, member
+                    )));
+                }
+
+                Ok(format!("{} {}", ty, member))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(format!("{}({})", name, members.join(",")))
+    }
+
+    fn contains_tuple(&self) -> bool {
+        match self {
+            Type::Tuple(_) => true,
+            Type::FixedArray(ty, _) | Type::Array(ty) | Type::Option(ty) => ty.contains_tuple(),
+            Type::Map(key, value) => key.contains_tuple() || value.contains_tuple(),
+            _ => false,
         }
     }
 }
 
-impl std::fmt::Display for Type {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+impl core::fmt::Display for Type {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Type::U32 => write!(f, "u32"),
             Type::U256 => write!(f, "u256"),
             Type::Field => write!(f, "field"),
+            Type::I32 => write!(f, "i32"),
+            Type::I64 => write!(f, "i64"),
             Type::Hash => write!(f, "hash"),
             Type::Address => write!(f, "address"),
             Type::Bool => write!(f, "bool"),
@@ -57,6 +180,8 @@ impl std::fmt::Display for Type {
             Type::Fields => write!(f, "fields"),
             Type::FixedArray(ty, size) => write!(f, "{}[{}]", ty, size),
             Type::Array(ty) => write!(f, "{}[]", ty),
+            Type::Option(ty) => write!(f, "{}?", ty),
+            Type::Map(key, value) => write!(f, "map({},{})", key, value),
             Type::Tuple(tys) => write!(
                 f,
                 "({})",
@@ -68,3 +193,286 @@ impl std::fmt::Display for Type {
         }
     }
 }
+
+/// Parses a type in its canonical form, as displayed, e.g. `u32[2][]`,
+/// `(address,field)[3]`, `u32?` or `map(u32,string)`. Tuples may also be
+/// written `tuple(T1,T2)`, and whitespace between tokens is ignored. Tuple
+/// members are unnamed.
+///
+/// ```
+/// use ola_lang_abi::Type;
+///
+/// let ty: Type = "tuple(u32, string)[]".parse().unwrap();
+/// assert_eq!(ty.to_string(), "(u32,string)[]");
+///
+/// let err = "(u32,strin)".parse::<Type>().unwrap_err();
+/// assert_eq!(err.to_string(), r#"unknown type "strin" at offset 5 of "(u32,strin)""#);
+/// ```
+impl core::str::FromStr for Type {
+    type Err = AbiError;
+
+    fn from_str(s: &str) -> Result<Self, AbiError> {
+        let mut parser = TypeParser { s, at: 0 };
+        let ty = parser.type_(0)?;
+
+        parser.skip_whitespace();
+        if parser.at < s.len() {
+            return Err(parser.unexpected("end of type"));
+        }
+
+        Ok(ty)
+    }
+}
+
+/// Recursive descent parser of canonical types, see the `FromStr` impl of
+/// [`Type`].
+struct TypeParser<'a> {
+    s: &'a str,
+    at: usize,
+}
+
+impl TypeParser<'_> {
+    fn rest(&self) -> &str {
+        &self.s[self.at..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.at = self.s.len() - self.rest().trim_start().len();
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        let matches = self.rest().starts_with(token);
+        if matches {
+            self.at += token.len();
+        }
+        matches
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), AbiError> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("{:?}", token)))
+        }
+    }
+
+    fn error(&self, msg: &str) -> AbiError {
+        AbiError::InvalidInput(format!("{} at offset {} of {:?}", msg, self.at, self.s))
+    }
+
+    fn unexpected(&self, expected: &str) -> AbiError {
+        match self.rest().chars().next() {
+            Some(c) => self.error(&format!("expected {}, got {:?}", expected, c)),
+            None => self.error(&format!("expected {}, got end of type", expected)),
+        }
+    }
+
+    /// Takes the longest run of characters matching `f`.
+    fn take(&mut self, f: impl Fn(char) -> bool) -> &str {
+        self.skip_whitespace();
+        let start = self.at;
+        let len = self.rest().find(|c| !f(c)).unwrap_or(self.rest().len());
+        self.at += len;
+
+        &self.s[start..self.at]
+    }
+
+    fn type_(&mut self, mut depth: usize) -> Result<Type, AbiError> {
+        self.skip_whitespace();
+        let start = self.at;
+
+        let mut ty = if self.eat("(") {
+            self.tuple(depth)?
+        } else {
+            match self.take(|c| c.is_ascii_alphanumeric() || c == '_') {
+                "u32" => Type::U32,
+                "u256" => Type::U256,
+                "field" => Type::Field,
+                "i32" => Type::I32,
+                "i64" => Type::I64,
+                "hash" => Type::Hash,
+                "address" => Type::Address,
+                "bool" => Type::Bool,
+                "string" => Type::String,
+                "fields" => Type::Fields,
+                "tuple" => {
+                    self.expect("(")?;
+                    self.tuple(depth)?
+                }
+                "map" => self.map(depth)?,
+                "" => return Err(self.unexpected("type")),
+                ident => {
+                    let msg = format!("unknown type {:?}", ident);
+                    self.at = start;
+                    return Err(self.error(&msg));
+                }
+            }
+        };
+
+        loop {
+            ty = if self.eat("?") {
+                Type::Option(Box::new(ty))
+            } else if self.eat("[") {
+                let ty = match self.take(|c| c.is_ascii_digit()) {
+                    "" => Type::Array(Box::new(ty)),
+                    digits => {
+                        let size = digits
+                            .parse()
+                            .map_err(|_| format!("array size {} is too large", digits));
+                        Type::FixedArray(Box::new(ty), size.map_err(|msg| self.error(&msg))?)
+                    }
+                };
+                self.expect("]")?;
+                ty
+            } else {
+                return Ok(ty);
+            };
+
+            depth += 1;
+            if depth > MAX_TYPE_DEPTH {
+                return Err(self.too_deep());
+            }
+        }
+    }
+
+    /// Parses the members of a tuple, after its `(`.
+    fn tuple(&mut self, depth: usize) -> Result<Type, AbiError> {
+        if depth >= MAX_TYPE_DEPTH {
+            return Err(self.too_deep());
+        }
+
+        let mut members = vec![];
+        if self.eat(")") {
+            return Ok(Type::Tuple(members));
+        }
+
+        loop {
+            members.push((String::new(), self.type_(depth + 1)?));
+
+            if !self.eat(",") {
+                self.expect(")")?;
+                return Ok(Type::Tuple(members));
+            }
+        }
+    }
+
+    /// Parses `(K,V)` following `map`.
+    fn map(&mut self, depth: usize) -> Result<Type, AbiError> {
+        if depth >= MAX_TYPE_DEPTH {
+            return Err(self.too_deep());
+        }
+
+        self.expect("(")?;
+        let key = self.type_(depth + 1)?;
+        self.expect(",")?;
+        let value = self.type_(depth + 1)?;
+        self.expect(")")?;
+
+        Ok(Type::Map(Box::new(key), Box::new(value)))
+    }
+
+    fn too_deep(&self) -> AbiError {
+        self.error(&format!("type nests deeper than {} levels", MAX_TYPE_DEPTH))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn struct_signature() {
+        let order = Type::Tuple(vec![
+            ("id".to_string(), Type::U32),
+            ("owners".to_string(), Type::Array(Box::new(Type::Address))),
+            ("title".to_string(), Type::String),
+        ]);
+
+        assert_eq!(
+            order.struct_signature("Order").unwrap(),
+            "Order(u32 id,address[] owners,string title)"
+        );
+        assert_eq!(
+            Type::Tuple(vec![]).struct_signature("Empty").unwrap(),
+            "Empty()"
+        );
+
+        assert!(Type::U32.struct_signature("Order").is_err());
+        assert!(order.struct_signature("1Order").is_err());
+        assert!(order.struct_signature("").is_err());
+        assert!(Type::Tuple(vec![("".to_string(), Type::U32)])
+            .struct_signature("Order")
+            .is_err());
+        assert!(Type::Tuple(vec![(
+            "items".to_string(),
+            Type::Array(Box::new(order.clone()))
+        )])
+        .struct_signature("Cart")
+        .is_err());
+    }
+
+    #[test]
+    fn type_from_str() {
+        let parse = |s: &str| s.parse::<Type>();
+        let tuple =
+            |tys: Vec<Type>| Type::Tuple(tys.into_iter().map(|ty| (String::new(), ty)).collect());
+
+        assert_eq!(
+            parse("u32[2][]").unwrap(),
+            Type::Array(Box::new(Type::FixedArray(Box::new(Type::U32), 2)))
+        );
+        assert_eq!(
+            parse("tuple(u32,string)").unwrap(),
+            tuple(vec![Type::U32, Type::String])
+        );
+        assert_eq!(
+            parse(" ( address , field ) [3] ").unwrap(),
+            Type::FixedArray(Box::new(tuple(vec![Type::Address, Type::Field])), 3)
+        );
+        assert_eq!(parse("()").unwrap(), tuple(vec![]));
+
+        for s in [
+            "u256",
+            "i64[]?",
+            "((bool,hash)[],fields)",
+            "map(u32,(string,i32?)[2])",
+            "map(address,map(u32,bool))[]",
+        ] {
+            assert_eq!(parse(s).unwrap().to_string(), s);
+        }
+
+        for (s, err) in [
+            ("", "expected type, got end of type at offset 0 of \"\""),
+            (
+                "u32[",
+                r#"expected "]", got end of type at offset 4 of "u32[""#,
+            ),
+            (
+                "u32[2x]",
+                r#"expected "]", got 'x' at offset 5 of "u32[2x]""#,
+            ),
+            (
+                "(u32,",
+                r#"expected type, got end of type at offset 5 of "(u32,""#,
+            ),
+            (
+                "u32 u32",
+                r#"expected end of type, got 'u' at offset 4 of "u32 u32""#,
+            ),
+            (
+                "map(u32)",
+                r#"expected ",", got ')' at offset 7 of "map(u32)""#,
+            ),
+            ("uint", r#"unknown type "uint" at offset 0 of "uint""#),
+        ] {
+            assert_eq!(parse(s).unwrap_err().to_string(), err);
+        }
+        assert!(parse("u32[99999999999999999999]").is_err());
+        assert!(parse(&format!("{}u32{}", "(".repeat(100), ")".repeat(100))).is_err());
+        assert!(parse(&format!("u32{}", "?".repeat(MAX_TYPE_DEPTH + 1))).is_err());
+        assert!(parse(&format!("u32{}", "?".repeat(MAX_TYPE_DEPTH))).is_ok());
+    }
+}
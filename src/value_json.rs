@@ -0,0 +1,314 @@
+use std::collections::HashSet;
+
+use serde_json::Value as Json;
+
+use crate::{
+    error::{AbiError, Result},
+    FixedArray4, FixedArray8, Type, Value,
+};
+
+impl Value {
+    /// Renders the value as idiomatic JSON, unlike its serde representation
+    /// (`{"U32": 5}`): integers and bools as JSON ones, `u256`, addresses and
+    /// hashes as hex strings, `fields` and arrays as arrays, tuples as
+    /// objects keyed by member name, maps as objects keyed by the JSON text
+    /// of their keys (strings as is), and absent optional values as `null`.
+    ///
+    /// Tuples with unnamed or duplicate member names render as arrays, which
+    /// would otherwise lose members.
+    ///
+    /// ```
+    /// use ola_lang_abi::{FixedArray4, Value};
+    ///
+    /// let book = Value::Tuple(vec![
+    ///     ("id".to_string(), Value::U32(60)),
+    ///     ("owner".to_string(), Value::Address(FixedArray4([0, 0, 0, 1]))),
+    /// ]);
+    ///
+    /// assert_eq!(
+    ///     book.to_json(),
+    ///     serde_json::json!({ "id": 60, "owner": FixedArray4([0, 0, 0, 1]).to_hex_string() })
+    /// );
+    /// ```
+    pub fn to_json(&self) -> Json {
+        match self {
+            Value::U32(n) | Value::Field(n) => (*n).into(),
+            Value::I32(n) => (*n).into(),
+            Value::I64(n) => (*n).into(),
+            Value::Bool(b) => (*b).into(),
+            Value::U256(n) => n.to_hex_string().into(),
+            Value::Address(words) | Value::Hash(words) => words.to_hex_string().into(),
+            Value::String(s) => s.as_str().into(),
+            Value::Fields(words) => words.as_slice().into(),
+            Value::FixedArray(values, _) | Value::Array(values, _) => {
+                values.iter().map(Value::to_json).collect()
+            }
+            Value::Tuple(members) if has_keys(members.iter().map(|(name, _)| name.as_str())) => {
+                members
+                    .iter()
+                    .map(|(name, value)| (name.clone(), value.to_json()))
+                    .collect::<serde_json::Map<_, _>>()
+                    .into()
+            }
+            Value::Tuple(members) => members.iter().map(|(_, value)| value.to_json()).collect(),
+            Value::None(_) => Json::Null,
+            Value::Some(value) => value.to_json(),
+            Value::Map(entries, ..) => entries
+                .iter()
+                .map(|(key, value)| (map_key(key), value.to_json()))
+                .collect::<serde_json::Map<_, _>>()
+                .into(),
+            Value::Placeholder(name, _) => format!("<{}>", name).into(),
+        }
+    }
+
+    /// Parses a value of the given type from idiomatic JSON, as rendered by
+    /// [`Value::to_json`].
+    ///
+    /// Integers may also be given as decimal or `0x` hex strings, `u256` as
+    /// numbers, and tuples as arrays of their members in order. Map entries
+    /// come in the order of the JSON object, sorted by key text.
+    ///
+    /// ```
+    /// use ola_lang_abi::{Type, Value};
+    ///
+    /// let ty = Type::Tuple(vec![("id".to_string(), Type::U32), ("tags".to_string(), Type::Array(Box::new(Type::String)))]);
+    /// let value = Value::from_json(&ty, &serde_json::json!({ "id": "60", "tags": ["ola"] })).unwrap();
+    ///
+    /// assert_eq!(value.to_json(), serde_json::json!({ "id": 60, "tags": ["ola"] }));
+    /// assert!(Value::from_json(&ty, &serde_json::json!({ "id": 60 })).is_err());
+    /// ```
+    pub fn from_json(ty: &Type, json: &Json) -> Result<Value> {
+        let value = from_json(ty, json, "")?;
+        value.check_type(ty)?;
+
+        Ok(value)
+    }
+}
+
+/// JSON object key of a map key, the JSON text of its value, strings as is.
+pub(crate) fn map_key(key: &Value) -> String {
+    match key.to_json() {
+        Json::String(s) => s,
+        json => json.to_string(),
+    }
+}
+
+/// Whether tuple members can be keyed by name.
+fn has_keys<'a>(names: impl Iterator<Item = &'a str>) -> bool {
+    let mut seen = HashSet::new();
+    names
+        .into_iter()
+        .all(|name| !name.is_empty() && seen.insert(name))
+}
+
+fn from_json(ty: &Type, json: &Json, path: &str) -> Result<Value> {
+    let at = if path.is_empty() { "/" } else { path };
+    let invalid = || AbiError::InvalidInput(format!("expected {} at {:?}, got {}", ty, at, json));
+
+    let value = match ty {
+        Type::U32 => Value::U32(unsigned(json).ok_or_else(invalid)?),
+        Type::Field => Value::Field(unsigned(json).ok_or_else(invalid)?),
+        Type::I32 => Value::I32(
+            signed(json)
+                .and_then(|n| i32::try_from(n).ok())
+                .ok_or_else(invalid)?,
+        ),
+        Type::I64 => Value::I64(signed(json).ok_or_else(invalid)?),
+        Type::Bool => Value::Bool(json.as_bool().ok_or_else(invalid)?),
+        Type::U256 => Value::U256(match json {
+            Json::String(hex) => FixedArray8::from_hex(hex).map_err(|_| invalid())?,
+            _ => FixedArray8::from(json.as_u64().ok_or_else(invalid)?),
+        }),
+        Type::Address | Type::Hash => {
+            let words = json
+                .as_str()
+                .and_then(|hex| FixedArray4::from_hex(hex).ok())
+                .ok_or_else(invalid)?;
+
+            match ty {
+                Type::Address => Value::Address(words),
+                _ => Value::Hash(words),
+            }
+        }
+        Type::String => Value::String(json.as_str().ok_or_else(invalid)?.to_string()),
+        Type::Fields => Value::Fields(
+            json.as_array()
+                .ok_or_else(invalid)?
+                .iter()
+                .map(|word| unsigned(word).ok_or_else(invalid))
+                .collect::<Result<_>>()?,
+        ),
+        Type::FixedArray(elem, _) | Type::Array(elem) => {
+            let values = json
+                .as_array()
+                .ok_or_else(invalid)?
+                .iter()
+                .enumerate()
+                .map(|(i, json)| from_json(elem, json, &format!("{}/{}", path, i)))
+                .collect::<Result<Vec<_>>>()?;
+
+            match ty {
+                Type::Array(_) => Value::Array(values, *elem.clone()),
+                _ => Value::FixedArray(values, *elem.clone()),
+            }
+        }
+        Type::Tuple(members) => {
+            let jsons = match json {
+                Json::Array(jsons) if jsons.len() == members.len() => jsons.iter().collect(),
+                Json::Object(object) => members
+                    .iter()
+                    .map(|(name, _)| {
+                        object.get(name).ok_or_else(|| {
+                            AbiError::InvalidInput(format!("missing member {:?} at {:?}", name, at))
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?,
+                _ => return Err(invalid()),
+            };
+
+            Value::Tuple(
+                members
+                    .iter()
+                    .zip(jsons)
+                    .enumerate()
+                    .map(|(i, ((name, ty), json))| {
+                        let key = if name.is_empty() {
+                            i.to_string()
+                        } else {
+                            name.clone()
+                        };
+                        let value = from_json(ty, json, &format!("{}/{}", path, key))?;
+                        Ok((name.clone(), value))
+                    })
+                    .collect::<Result<_>>()?,
+            )
+        }
+        Type::Option(ty) => match json {
+            Json::Null => Value::None(*ty.clone()),
+            _ => Value::Some(Box::new(from_json(ty, json, path)?)),
+        },
+        Type::Map(key_ty, value_ty) => Value::Map(
+            json.as_object()
+                .ok_or_else(invalid)?
+                .iter()
+                .map(|(key, json)| {
+                    // keys other than strings are the JSON text of the key,
+                    // hex strings aside
+                    let key_json = match **key_ty {
+                        Type::String => Json::String(key.clone()),
+                        _ => serde_json::from_str(key).unwrap_or_else(|_| key.as_str().into()),
+                    };
+                    let path = format!("{}/{}", path, key);
+                    Ok((
+                        from_json(key_ty, &key_json, &path)?,
+                        from_json(value_ty, json, &path)?,
+                    ))
+                })
+                .collect::<Result<_>>()?,
+            *key_ty.clone(),
+            *value_ty.clone(),
+        ),
+    };
+
+    Ok(value)
+}
+
+/// Parses a JSON number or decimal or `0x` hex string.
+fn unsigned(json: &Json) -> Option<u64> {
+    match json {
+        Json::Number(n) => n.as_u64(),
+        Json::String(s) => match s.strip_prefix("0x") {
+            Some(hex) => u64::from_str_radix(hex, 16).ok(),
+            None => s.parse().ok(),
+        },
+        _ => None,
+    }
+}
+
+/// Parses a JSON number or decimal string.
+fn signed(json: &Json) -> Option<i64> {
+    match json {
+        Json::Number(n) => n.as_i64(),
+        Json::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn json_round_trip() {
+        let order = Type::Tuple(vec![
+            ("id".to_string(), Type::U32),
+            ("delta".to_string(), Type::I64),
+            ("owner".to_string(), Type::Address),
+            ("amount".to_string(), Type::U256),
+            ("blob".to_string(), Type::Fields),
+            (
+                "pair".to_string(),
+                Type::Tuple(vec![
+                    (String::new(), Type::Bool),
+                    (String::new(), Type::String),
+                ]),
+            ),
+            ("ids".to_string(), Type::FixedArray(Box::new(Type::U32), 2)),
+            (
+                "labels".to_string(),
+                Type::Map(Box::new(Type::U32), Box::new(Type::String)),
+            ),
+            (
+                "owners".to_string(),
+                Type::Map(Box::new(Type::Address), Box::new(Type::Bool)),
+            ),
+        ]);
+        let json = json!({
+            "id": 7,
+            "delta": -3,
+            "owner": FixedArray4([0, 0, 0, 1]).to_hex_string(),
+            "amount": FixedArray8::from(5u64).to_hex_string(),
+            "blob": [1, 2],
+            "pair": [true, "ola"],
+            "ids": [1, 2],
+            "labels": { "1": "one", "2": "two" },
+            "owners": { FixedArray4([0, 0, 0, 1]).to_hex_string(): true },
+        });
+
+        let value = Value::from_json(&order, &json).unwrap();
+        assert_eq!(value.to_json(), json);
+        assert_eq!(Value::from_json(&order, &value.to_json()).unwrap(), value);
+
+        assert_eq!(
+            Value::from_json(&Type::U256, &json!(5)).unwrap(),
+            Value::U256(FixedArray8::from(5u64))
+        );
+        assert_eq!(
+            Value::from_json(&Type::Field, &json!("0x10")).unwrap(),
+            Value::Field(16)
+        );
+
+        let mut bad = json.clone();
+        bad["delta"] = json!("x");
+        let err = Value::from_json(&order, &bad).unwrap_err();
+        assert_eq!(err.to_string(), r#"expected i64 at "/delta", got "x""#);
+        assert_eq!(
+            Value::from_json(&order, &json!({ "id": 7 }))
+                .unwrap_err()
+                .to_string(),
+            r#"missing member "delta" at "/""#
+        );
+        assert!(Value::from_json(&Type::I32, &json!(i64::MAX)).is_err());
+        assert!(Value::from_json(&Type::FixedArray(Box::new(Type::U32), 2), &json!([1])).is_err());
+        assert!(Value::from_json(&Type::Address, &json!(1)).is_err());
+        assert!(Value::from_json(
+            &Type::Map(Box::new(Type::U32), Box::new(Type::String)),
+            &json!({ "x": "one" })
+        )
+        .is_err());
+    }
+}
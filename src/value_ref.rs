@@ -0,0 +1,314 @@
+use crate::alloc_prelude::*;
+use crate::{
+    decoder::{capacity_hint, offset, span},
+    error::{AbiError, Result},
+    values::word_to_signed,
+    FixedArray4, FixedArray8, Type, Value,
+};
+
+/// ABI value borrowing from the decoded words, see
+/// [`Value::decode_borrowed`].
+///
+/// Strings and fields are sub-slices of the input, one word per string
+/// byte; tuple member names and array element types borrow from the type
+/// hints.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValueRef<'a> {
+    /// Unsigned int value (uint32).
+    U32(u64),
+    /// Unsigned int value (uint256).
+    U256(FixedArray8),
+    /// Field value.
+    Field(u64),
+    /// Signed int value (int32).
+    I32(i32),
+    /// Signed int value (int64).
+    I64(i64),
+    /// Address value (address).
+    Address(FixedArray4),
+    /// Hash value (hash).
+    Hash(FixedArray4),
+    /// Bool value (bool).
+    Bool(bool),
+    /// Fixed size array value (T\[k\]).
+    FixedArray(Vec<ValueRef<'a>>, &'a Type),
+    /// UTF-8 string value (string), as its words, not yet checked.
+    String(&'a [u64]),
+    /// Dynamic size field value.
+    Fields(&'a [u64]),
+    /// Dynamic size array value (T[]).
+    Array(Vec<ValueRef<'a>>, &'a Type),
+    /// Tuple value (tuple(T1, T2, ..., Tn)), as (name, value) items.
+    Tuple(Vec<(&'a str, ValueRef<'a>)>),
+    /// Absent optional value (T?) of the given type.
+    None(&'a Type),
+    /// Present optional value (T?).
+    Some(Box<ValueRef<'a>>),
+    /// Key-value map value (map(K,V)) of the given key and value types, as
+    /// (key, value) items.
+    Map(Vec<(ValueRef<'a>, ValueRef<'a>)>, &'a Type, &'a Type),
+}
+
+impl ValueRef<'_> {
+    /// Converts to an owned [`Value`], failing on strings that aren't
+    /// UTF-8.
+    pub fn to_owned(&self) -> Result<Value> {
+        let values = |values: &[ValueRef]| {
+            values
+                .iter()
+                .map(ValueRef::to_owned)
+                .collect::<Result<Vec<_>>>()
+        };
+
+        Ok(match self {
+            ValueRef::U32(n) => Value::U32(*n),
+            ValueRef::U256(n) => Value::U256(*n),
+            ValueRef::Field(n) => Value::Field(*n),
+            ValueRef::I32(n) => Value::I32(*n),
+            ValueRef::I64(n) => Value::I64(*n),
+            ValueRef::Address(addr) => Value::Address(*addr),
+            ValueRef::Hash(hash) => Value::Hash(*hash),
+            ValueRef::Bool(b) => Value::Bool(*b),
+            ValueRef::FixedArray(elements, ty) => {
+                Value::FixedArray(values(elements)?, (*ty).clone())
+            }
+            ValueRef::String(words) => {
+                Value::String(String::from_utf8(words.iter().map(|b| *b as u8).collect())?)
+            }
+            ValueRef::Fields(words) => Value::Fields(words.to_vec()),
+            ValueRef::Array(elements, ty) => Value::Array(values(elements)?, (*ty).clone()),
+            ValueRef::Tuple(members) => Value::Tuple(
+                members
+                    .iter()
+                    .map(|(name, value)| Ok((name.to_string(), value.to_owned()?)))
+                    .collect::<Result<_>>()?,
+            ),
+            ValueRef::None(ty) => Value::None((*ty).clone()),
+            ValueRef::Some(value) => Value::Some(Box::new(ValueRef::to_owned(value)?)),
+            ValueRef::Map(entries, key_ty, value_ty) => Value::Map(
+                entries
+                    .iter()
+                    .map(|(key, value)| Ok((key.to_owned()?, value.to_owned()?)))
+                    .collect::<Result<_>>()?,
+                (*key_ty).clone(),
+                (*value_ty).clone(),
+            ),
+        })
+    }
+}
+
+impl Value {
+    /// Decodes values from bytes using the given type hint, borrowing
+    /// strings and fields from `bs` instead of copying them.
+    ///
+    /// ```
+    /// use ola_lang_abi::{Type, Value, ValueRef};
+    ///
+    /// let bs = Value::encode(&[Value::String("ola".to_string())]);
+    /// let tys = [Type::String];
+    ///
+    /// let values = Value::decode_borrowed(&bs, &tys).unwrap();
+    /// assert_eq!(values[0], ValueRef::String(&bs[1..]));
+    /// assert_eq!(values[0].to_owned().unwrap(), Value::String("ola".to_string()));
+    /// ```
+    pub fn decode_borrowed<'a>(bs: &'a [u64], tys: &'a [Type]) -> Result<Vec<ValueRef<'a>>> {
+        let mut at = 0;
+
+        tys.iter()
+            .map(|ty| {
+                let (value, consumed) = decode(bs, ty, 0, at)?;
+                at += consumed;
+                Ok(value)
+            })
+            .collect()
+    }
+}
+
+fn words<'a>(bs: &'a [u64], ty: &Type, at: usize, len: u64) -> Result<&'a [u64]> {
+    bs.get(span(at, len)?)
+        .ok_or_else(|| AbiError::Truncated(format!("reached end of input while decoding {:?}", ty)))
+}
+
+fn decode<'a>(
+    bs: &'a [u64],
+    ty: &'a Type,
+    base_addr: usize,
+    at: usize,
+) -> Result<(ValueRef<'a>, usize)> {
+    let at = offset(base_addr, at)?;
+
+    match ty {
+        Type::U32 => Ok((ValueRef::U32(words(bs, ty, at, 1)?[0]), 1)),
+        Type::Field => Ok((ValueRef::Field(words(bs, ty, at, 1)?[0]), 1)),
+        Type::Bool => Ok((ValueRef::Bool(words(bs, ty, at, 1)?[0] == 1), 1)),
+        Type::I32 | Type::I64 => {
+            let word = words(bs, ty, at, 1)?[0];
+            let n = word_to_signed(word).ok_or_else(|| {
+                AbiError::InvalidInput(format!("word {:#x} at {} is not a field element", word, at))
+            })?;
+
+            let value = match ty {
+                Type::I32 => ValueRef::I32(i32::try_from(n).map_err(|_| {
+                    AbiError::InvalidInput(format!("{} at word {} doesn't fit in i32", n, at))
+                })?),
+                _ => ValueRef::I64(n),
+            };
+
+            Ok((value, 1))
+        }
+        Type::U256 => Ok((
+            ValueRef::U256(FixedArray8(words(bs, ty, at, 8)?.try_into().unwrap())),
+            8,
+        )),
+        Type::Address => Ok((
+            ValueRef::Address(FixedArray4(words(bs, ty, at, 4)?.try_into().unwrap())),
+            4,
+        )),
+        Type::Hash => Ok((
+            ValueRef::Hash(FixedArray4(words(bs, ty, at, 4)?.try_into().unwrap())),
+            4,
+        )),
+        Type::String | Type::Fields => {
+            let len = words(bs, ty, at, 1)?[0];
+            let fields = words(bs, ty, at + 1, len)?;
+
+            let value = match ty {
+                Type::String => ValueRef::String(fields),
+                _ => ValueRef::Fields(fields),
+            };
+
+            // the length word followed by the fields
+            Ok((value, fields.len() + 1))
+        }
+        Type::FixedArray(elem, size) => {
+            let mut values = Vec::with_capacity(capacity_hint(bs, at, *size));
+            let mut consumed = 0;
+
+            for _ in 0..*size {
+                let (value, n) = decode(bs, elem, at, consumed)?;
+                values.push(value);
+                consumed += n;
+            }
+
+            Ok((ValueRef::FixedArray(values, elem), consumed))
+        }
+        Type::Array(elem) => {
+            let len = words(bs, ty, at, 1)?[0];
+            let at = at + 1;
+
+            let mut values = Vec::with_capacity(capacity_hint(bs, at, len));
+            let mut consumed = 0;
+
+            for _ in 0..len {
+                let (value, n) = decode(bs, elem, at, consumed)?;
+                values.push(value);
+                consumed += n;
+            }
+
+            Ok((ValueRef::Array(values, elem), consumed + 1))
+        }
+        Type::Tuple(tys) => {
+            let mut members = Vec::with_capacity(tys.len());
+            let mut consumed = 0;
+
+            for (name, ty) in tys {
+                let (value, n) = decode(bs, ty, at, consumed)?;
+                members.push((name.as_str(), value));
+                consumed += n;
+            }
+
+            Ok((ValueRef::Tuple(members), consumed))
+        }
+        Type::Option(ty) => match words(bs, ty, at, 1)?[0] {
+            0 => Ok((ValueRef::None(ty), 1)),
+            1 => {
+                let (value, consumed) = decode(bs, ty, at, 1)?;
+                Ok((ValueRef::Some(Box::new(value)), consumed + 1))
+            }
+            flag => Err(AbiError::InvalidInput(format!(
+                "option flag {:#x} at word {} is neither 0 nor 1",
+                flag, at
+            ))),
+        },
+        Type::Map(key_ty, value_ty) => {
+            let len = words(bs, ty, at, 1)?[0];
+            let at = at + 1;
+
+            let mut entries = Vec::with_capacity(capacity_hint(bs, at, len));
+            let mut consumed = 0;
+
+            for _ in 0..len {
+                let (key, n) = decode(bs, key_ty, at, consumed)?;
+                consumed += n;
+                let (value, n) = decode(bs, value_ty, at, consumed)?;
+                consumed += n;
+                entries.push((key, value));
+            }
+
+            Ok((ValueRef::Map(entries, key_ty, value_ty), consumed + 1))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn decode_borrowed_matches_owned() {
+        let values = vec![
+            Value::U32(7),
+            Value::I64(-3),
+            Value::String("olavm".to_string()),
+            Value::Array(
+                vec![
+                    Value::Tuple(vec![
+                        ("id".to_string(), Value::U32(1)),
+                        ("blob".to_string(), Value::Fields(vec![1, 2, 3])),
+                    ]),
+                    Value::Tuple(vec![
+                        ("id".to_string(), Value::U32(2)),
+                        ("blob".to_string(), Value::Fields(vec![])),
+                    ]),
+                ],
+                Type::Tuple(vec![
+                    ("id".to_string(), Type::U32),
+                    ("blob".to_string(), Type::Fields),
+                ]),
+            ),
+            Value::FixedArray(
+                vec![Value::Address(FixedArray4([1, 2, 3, 4])); 2],
+                Type::Address,
+            ),
+            Value::Some(Box::new(Value::String("ola".to_string()))),
+            Value::None(Type::U32),
+            Value::Map(
+                vec![(Value::U32(1), Value::String("one".to_string()))],
+                Type::U32,
+                Type::String,
+            ),
+        ];
+        let tys = values.iter().map(Value::type_of).collect::<Vec<_>>();
+        let bs = Value::encode(&values);
+
+        let borrowed = Value::decode_borrowed(&bs, &tys).unwrap();
+        assert_eq!(borrowed[2], ValueRef::String(&bs[3..8]));
+        assert_eq!(
+            borrowed
+                .iter()
+                .map(|value| value.to_owned().unwrap())
+                .collect::<Vec<_>>(),
+            values
+        );
+
+        assert!(Value::decode_borrowed(&bs[..bs.len() - 1], &tys).is_err());
+        assert!(
+            Value::decode_borrowed(&[2, 0xff, 0xfe], &[Type::String]).unwrap()[0]
+                .to_owned()
+                .is_err()
+        );
+        assert!(Value::decode_borrowed(&[u64::MAX], &[Type::Fields]).is_err());
+    }
+}
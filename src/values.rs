@@ -1,31 +1,180 @@
-use anyhow::{anyhow, Result};
+use crate::alloc_prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    encoder::default_len,
+    error::{AbiError, Result},
+    types::Type,
+    DecodeIter, DecodeOptions, Decoder, Encoder, IntegerPacking, MAX_TYPE_DEPTH,
+};
+use alloc::collections::BTreeSet;
+use core::fmt;
+
+/// Order `p = 2^64 - 2^32 + 1` of the Goldilocks field of Ola words.
+pub const FIELD_ORDER: u64 = 0xffff_ffff_0000_0001;
+
+/// Largest magnitude of a signed integer encoded as a field element: words
+/// up to `(p - 1) / 2` are non-negative, larger words are negative.
+pub const SIGNED_MAX: i64 = ((FIELD_ORDER - 1) / 2) as i64;
+
+/// Maximum number of fixed size array elements of a default value, see
+/// [`Value::default_for`].
+pub const MAX_DEFAULT_ELEMENTS: u64 = 1 << 20;
+
+/// Encodes a signed integer as a field element, negative `x` as `p - |x|`.
+///
+/// Integers beyond [`SIGNED_MAX`] don't round-trip.
+pub(crate) fn signed_to_word(n: i64) -> u64 {
+    if n >= 0 {
+        n as u64
+    } else {
+        FIELD_ORDER - n.unsigned_abs()
+    }
+}
 
-use crate::types::Type;
-use std::fmt;
+/// Decodes a field element as a signed integer, `None` if the word isn't
+/// below the field order.
+pub(crate) fn word_to_signed(word: u64) -> Option<i64> {
+    if word >= FIELD_ORDER {
+        None
+    } else if word <= SIGNED_MAX as u64 {
+        Some(word as i64)
+    } else {
+        Some(-((FIELD_ORDER - word) as i64))
+    }
+}
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// 4 word value (address, hash or event topic), most significant word
+/// first, see [`WordOrder`](crate::WordOrder).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct FixedArray4(pub [u64; 4]);
 
+/// Parses a hex string, see [`FixedArray4::from_hex`].
+///
+/// Deprecated: panics on invalid hex, parse untrusted input with
+/// [`str::parse`] or [`FixedArray4::from_hex`] instead. A fallible
+/// `TryFrom<&str>` would conflict with this impl.
 impl From<&str> for FixedArray4 {
     fn from(s: &str) -> Self {
-        let cleaned = s.trim_start_matches("0x");
-        let padded = format!("{:0>64}", cleaned);
-        let mut result = [0; 4];
-        for (i, chunk) in padded.as_bytes().rchunks(16).rev().enumerate() {
-            let chunk_str = std::str::from_utf8(chunk).expect("Invalid UTF-8");
-            result[i] =
-                u64::from_str_radix(chunk_str, 16).expect("Failed to parse hex string") as u64;
-        }
-        FixedArray4(result)
+        FixedArray4::from_hex(s).unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+/// Parses a hex string, see [`FixedArray4::from_hex`].
+///
+/// ```
+/// use ola_lang_abi::FixedArray4;
+///
+/// let address: FixedArray4 = "0xAbC".parse().unwrap();
+/// assert_eq!(address, FixedArray4([0, 0, 0, 0xabc]));
+/// assert!("0xnot-hex".parse::<FixedArray4>().is_err());
+/// ```
+impl core::str::FromStr for FixedArray4 {
+    type Err = AbiError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        FixedArray4::from_hex(s)
+    }
+}
+
+impl From<[u8; 32]> for FixedArray4 {
+    fn from(bytes: [u8; 32]) -> Self {
+        FixedArray4::from_bytes32(&bytes)
     }
 }
 
 impl FixedArray4 {
+    /// The all zero address/hash.
+    pub const ZERO: FixedArray4 = FixedArray4([0; 4]);
+
+    /// Parses a big-endian hex string of up to 64 digits, in any case, with
+    /// or without `0x` prefix, left padded with zeros.
+    ///
+    /// ```
+    /// use ola_lang_abi::FixedArray4;
+    ///
+    /// assert_eq!(FixedArray4::from_hex("0x1").unwrap(), FixedArray4([0, 0, 0, 1]));
+    /// assert!(FixedArray4::from_hex(&"f".repeat(65)).is_err());
+    /// ```
+    pub fn from_hex(hex: &str) -> Result<Self> {
+        let digits = hex
+            .strip_prefix("0x")
+            .or_else(|| hex.strip_prefix("0X"))
+            .unwrap_or(hex);
+
+        if digits.len() > 64 {
+            return Err(AbiError::InvalidInput(format!(
+                "expected at most 64 hex digits, got {} digits",
+                digits.len()
+            )));
+        }
+        if let Some(c) = digits.chars().find(|c| !c.is_ascii_hexdigit()) {
+            return Err(AbiError::InvalidInput(format!(
+                "invalid hex digit {:?} in {:?}",
+                c, hex
+            )));
+        }
+
+        let padded = format!("{:0>64}", digits);
+        let mut limbs = [0; 4];
+        for (limb, chunk) in limbs.iter_mut().zip(padded.as_bytes().chunks_exact(16)) {
+            // ascii hex digits, checked above
+            let chunk = core::str::from_utf8(chunk).unwrap();
+            *limb = u64::from_str_radix(chunk, 16).unwrap();
+        }
+
+        Ok(FixedArray4(limbs))
+    }
+
+    /// Creates a value from 32 big-endian bytes.
+    pub fn from_bytes32(bytes: &[u8; 32]) -> Self {
+        let mut limbs = [0; 4];
+        for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(8)) {
+            *limb = u64::from_be_bytes(chunk.try_into().unwrap());
+        }
+        FixedArray4(limbs)
+    }
+
+    /// Creates a value from up to 32 big-endian bytes, left padded with zeros.
+    pub fn from_slice(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() > 32 {
+            return Err(AbiError::InvalidInput(format!(
+                "expected at most 32 bytes, got {} bytes",
+                bytes.len()
+            )));
+        }
+
+        let mut padded = [0; 32];
+        padded[32 - bytes.len()..].copy_from_slice(bytes);
+        Ok(FixedArray4::from_bytes32(&padded))
+    }
+
+    /// Returns the 32 big-endian bytes of the value.
+    pub fn to_bytes32(&self) -> [u8; 32] {
+        let mut bytes = [0; 32];
+        for (chunk, limb) in bytes.chunks_exact_mut(8).zip(self.0) {
+            chunk.copy_from_slice(&limb.to_be_bytes());
+        }
+        bytes
+    }
+
+    /// Creates a random value, each word a field element, below
+    /// [`FIELD_ORDER`].
+    #[cfg(feature = "rand")]
+    pub fn random() -> Self {
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+        FixedArray4(core::array::from_fn(|_| rng.gen_range(0..FIELD_ORDER)))
+    }
+
+    /// Renders the words as `0x` prefixed hex, 16 digits per word, most
+    /// significant word first.
     pub fn to_hex_string(&self) -> String {
         let mut hex_string = String::with_capacity(66); // 64 for data + 2 for "0x" prefix
         hex_string.push_str("0x");
         for &value in self.0.iter() {
-            hex_string.push_str(&format!("{:016x}", value as u64));
+            hex_string.push_str(&format!("{:016x}", value));
         }
         hex_string
     }
@@ -35,30 +184,108 @@ impl fmt::Display for FixedArray4 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "0x")?;
         for &value in self.0.iter() {
-            write!(f, "{:016x}", value as u64)?;
+            write!(f, "{:016x}", value)?;
         }
         Ok(())
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// 256-bit unsigned integer, as 8 big-endian 32-bit limbs, one per word.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct FixedArray8(pub [u64; 8]);
 
+/// Parses a hex string, see [`FixedArray8::from_hex`].
+///
+/// Deprecated: panics on invalid hex, parse untrusted input with
+/// [`str::parse`] or [`FixedArray8::from_hex`] instead.
 impl From<&str> for FixedArray8 {
     fn from(s: &str) -> Self {
-        let cleaned = s.trim_start_matches("0x");
-        let padded = format!("{:0>64}", cleaned);
-        let mut result = [0; 8];
-        for (i, chunk) in padded.as_bytes().rchunks(8).rev().enumerate() {
-            let chunk_str = std::str::from_utf8(chunk).expect("Invalid UTF-8");
-            result[i] =
-                u64::from_str_radix(chunk_str, 16).expect("Failed to parse hex string") as u64;
-        }
-        FixedArray8(result)
+        FixedArray8::from_hex(s).unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+/// Parses a hex string, see [`FixedArray8::from_hex`].
+impl core::str::FromStr for FixedArray8 {
+    type Err = AbiError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        FixedArray8::from_hex(s)
+    }
+}
+
+impl From<u64> for FixedArray8 {
+    fn from(n: u64) -> Self {
+        FixedArray8::from(n as u128)
+    }
+}
+
+impl From<u128> for FixedArray8 {
+    fn from(n: u128) -> Self {
+        let mut bytes = [0; 32];
+        bytes[16..].copy_from_slice(&n.to_be_bytes());
+        FixedArray8::from_bytes32(&bytes)
+    }
+}
+
+impl From<[u8; 32]> for FixedArray8 {
+    fn from(bytes: [u8; 32]) -> Self {
+        FixedArray8::from_bytes32(&bytes)
     }
 }
 
 impl FixedArray8 {
+    /// Zero.
+    pub const ZERO: FixedArray8 = FixedArray8([0; 8]);
+
+    /// Parses a big-endian hex string of up to 64 digits, with or without
+    /// `0x` prefix, left padded with zeros.
+    ///
+    /// ```
+    /// use ola_lang_abi::FixedArray8;
+    ///
+    /// assert_eq!(FixedArray8::from_hex("0x100000002").unwrap(), FixedArray8([0, 0, 0, 0, 0, 0, 1, 2]));
+    /// assert!(FixedArray8::from_hex("0xg").is_err());
+    /// ```
+    pub fn from_hex(hex: &str) -> Result<Self> {
+        Ok(FixedArray8::from_bytes32(
+            &FixedArray4::from_hex(hex)?.to_bytes32(),
+        ))
+    }
+
+    /// Creates a value from 32 big-endian bytes.
+    pub fn from_bytes32(bytes: &[u8; 32]) -> Self {
+        let mut limbs = [0; 8];
+        for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(4)) {
+            *limb = u32::from_be_bytes(chunk.try_into().unwrap()) as u64;
+        }
+        FixedArray8(limbs)
+    }
+
+    /// Returns the 32 big-endian bytes of the value, ignoring the bits of
+    /// the limbs above 32.
+    pub fn to_bytes32(&self) -> [u8; 32] {
+        let mut bytes = [0; 32];
+        for (chunk, limb) in bytes.chunks_exact_mut(4).zip(self.0) {
+            chunk.copy_from_slice(&(limb as u32).to_be_bytes());
+        }
+        bytes
+    }
+
+    /// Returns the value as `u128`, `None` if it doesn't fit.
+    pub fn to_u128(&self) -> Option<u128> {
+        let bytes = self.to_bytes32();
+
+        (self.is_valid() && bytes[..16].iter().all(|b| *b == 0))
+            .then(|| u128::from_be_bytes(bytes[16..].try_into().unwrap()))
+    }
+
+    /// Returns whether every limb fits in 32 bits.
+    pub fn is_valid(&self) -> bool {
+        self.0.iter().all(|limb| *limb <= u32::MAX as u64)
+    }
+
+    /// Renders the limbs as `0x` prefixed hex, 8 digits per limb, most
+    /// significant limb first.
     pub fn to_hex_string(&self) -> String {
         let mut hex_string = String::with_capacity(66); // 64 for data + 2 for "0x" prefix
         hex_string.push_str("0x");
@@ -80,7 +307,19 @@ impl fmt::Display for FixedArray8 {
 }
 
 /// ABI decoded value.
-#[derive(Debug, Clone, Eq, PartialEq)]
+///
+/// Values are totally ordered so they can be sorted and used as `BTreeMap`
+/// keys. Values of different variants are ordered by variant, in declaration
+/// order (`U32 < U256 < Field < Address < Hash < Bool < FixedArray < String
+/// < Fields < Array < Tuple < None < Some < Map < Placeholder < I32 < I64`),
+/// later variants being added last to keep it stable.
+/// Values of the same variant are ordered by content: numerically for
+/// integers, limb by limb for `U256`, `Address` and `Hash`, bytewise for
+/// strings, and lexicographically for arrays, tuples and maps. The element
+/// type of arrays and the key and value types of maps only break ties, but
+/// tuples compare member by member, by name first: `(b: 1)` sorts after
+/// `(a: 2)`.
+#[derive(Debug, Clone, Eq, PartialEq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum Value {
     /// Unsigned int value (uint32).
     U32(u64),
@@ -100,129 +339,289 @@ pub enum Value {
     /// UTF-8 string value (string).
     String(String),
     /// Dynamic size field value.
-    Fields(Vec<u64>),
+    ///
+    /// Deserialized from a words array, or from the `0x` hex or base64
+    /// packing of [`FieldsEncoding`](crate::FieldsEncoding).
+    Fields(#[serde(deserialize_with = "deserialize_fields")] Vec<u64>),
     /// Dynamic size array value (T[]).
     Array(Vec<Value>, Type),
     /// Tuple value (tuple(T1, T2, ..., Tn)).
     ///
     /// This variant's vector items have the form (name, value).
     Tuple(Vec<(String, Value)>),
+    /// Absent optional value (T?) of the given type.
+    None(Type),
+    /// Present optional value (T?).
+    Some(Box<Value>),
+    /// Key-value map value (map(K,V)) of the given key and value types.
+    ///
+    /// This variant's vector items have the form (key, value), in encoding
+    /// order.
+    Map(Vec<(Value, Value)>, Type, Type),
+    /// Named slot of the given type, filled in after encoding, see
+    /// [`Abi::encode_template`](crate::Abi::encode_template). Encodes as the
+    /// default value of its type.
+    Placeholder(String, Type),
+    /// Signed int value (int32), encoded as a field element, see
+    /// [`FIELD_ORDER`].
+    I32(i32),
+    /// Signed int value (int64), encoded as a field element, up to
+    /// [`SIGNED_MAX`] in magnitude.
+    I64(i64),
+}
+
+fn deserialize_fields<'de, D>(deserializer: D) -> Result<Vec<u64>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Fields {
+        Words(Vec<u64>),
+        Packed(String),
+    }
+
+    match Fields::deserialize(deserializer)? {
+        Fields::Words(words) => Ok(words),
+        Fields::Packed(packed) if packed.starts_with("0x") => {
+            crate::calldata::from_hex(&packed).map_err(serde::de::Error::custom)
+        }
+        Fields::Packed(packed) => {
+            crate::calldata::from_base64(&packed).map_err(serde::de::Error::custom)
+        }
+    }
 }
 
 impl Value {
     /// Decodes values from bytes using the given type hint.
     pub fn decode_from_slice(bs: &[u64], tys: &[Type]) -> Result<Vec<Value>> {
-        tys.iter()
-            .try_fold((vec![], 0), |(mut values, at), ty| {
-                let (value, consumed) = Self::decode(bs, ty, 0, at)?;
-                values.push(value);
+        Decoder::new().decode(bs, tys)
+    }
 
-                Ok((values, at + consumed))
-            })
-            .map(|(values, _)| values)
+    /// Decodes values from bytes using the given type hint and options.
+    pub fn decode_with_options(
+        bs: &[u64],
+        tys: &[Type],
+        options: DecodeOptions,
+    ) -> Result<Vec<Value>> {
+        Decoder::new().with_options(options).decode(bs, tys)
+    }
+
+    /// Lazily decodes values from bytes using the given type hint, see
+    /// [`DecodeIter`].
+    pub fn decode_iter<'a>(bs: &'a [u64], tys: &'a [Type]) -> DecodeIter<'a, 'static> {
+        Decoder::new().decode_iter(bs, tys)
     }
 
     /// Encodes values into bytes.
     pub fn encode(values: &[Self]) -> Vec<u64> {
         let mut buf = vec![];
-        for value in values {
-            match value {
-                Value::U32(i) => {
-                    let start = buf.len();
-                    buf.resize(start + 1, *i);
-                }
-
-                Value::U256(num) => {
-                    let start = buf.len();
-                    buf.resize(start + 8, 0);
-
-                    // big-endian, as if it were a uint160.
-                    buf[start..(start + 8)].copy_from_slice(&num.0);
-                }
-
-                Value::Field(i) => {
-                    let start = buf.len();
-                    buf.resize(start + 1, *i);
-                }
+        Value::encode_into(values, &mut buf);
 
-                Value::Address(addr) => {
-                    let start = buf.len();
-                    buf.resize(start + 4, 0);
-
-                    // big-endian, as if it were a uint160.
-                    buf[start..(start + 4)].copy_from_slice(&addr.0);
-                }
-
-                Value::Hash(hash) => {
-                    let start = buf.len();
-                    buf.resize(start + 4, 0);
-
-                    // big-endian, as if it were a uint160.
-                    buf[start..(start + 4)].copy_from_slice(&hash.0);
-                }
+        buf
+    }
 
-                Value::Bool(b) => {
-                    let start = buf.len();
-                    buf.resize(start + 1, 0);
+    /// Encodes values, appending them to `buf`, whose capacity is reserved
+    /// once up front. Reuse the buffer across calls to avoid allocating.
+    ///
+    /// ```
+    /// use ola_lang_abi::Value;
+    ///
+    /// let mut buf = Vec::new();
+    /// for id in 0..3 {
+    ///     buf.clear();
+    ///     Value::encode_into(&[Value::U32(id), Value::String("ola".to_string())], &mut buf);
+    ///     assert_eq!(buf, [id, 3, 111, 108, 97]);
+    /// }
+    /// ```
+    pub fn encode_into(values: &[Self], buf: &mut Vec<u64>) {
+        buf.reserve(Value::encoded_len(values));
+        Encoder::new(buf).encode(values);
+    }
 
-                    if *b {
-                        buf[start] = 1;
-                    }
-                }
+    /// Returns the number of words the values encode to with the default
+    /// [`EncodeOptions`](crate::EncodeOptions).
+    pub fn encoded_len(values: &[Self]) -> usize {
+        values.iter().map(Value::word_len).sum()
+    }
 
-                Value::FixedArray(values, _) => {
-                    // write array values
-                    let bytes = Self::encode(values);
-                    buf.extend(bytes);
-                }
+    fn word_len(&self) -> usize {
+        match self {
+            Value::U32(_) | Value::Field(_) | Value::I32(_) | Value::I64(_) | Value::Bool(_) => 1,
+            Value::Address(_) | Value::Hash(_) => 4,
+            Value::U256(_) => 8,
+            // length word followed by one word per byte
+            Value::String(s) => 1 + s.len(),
+            Value::Fields(words) => 1 + words.len(),
+            Value::FixedArray(values, _) => Value::encoded_len(values),
+            Value::Array(values, _) => 1 + Value::encoded_len(values),
+            Value::Tuple(members) => members.iter().map(|(_, value)| value.word_len()).sum(),
+            // presence flag word, followed by the value if any
+            Value::None(_) => 1,
+            Value::Some(value) => 1 + value.word_len(),
+            // length word, followed by the keys and values
+            Value::Map(entries, ..) => {
+                1 + entries
+                    .iter()
+                    .map(|(key, value)| key.word_len() + value.word_len())
+                    .sum::<usize>()
+            }
+            Value::Placeholder(_, ty) => default_len(ty, IntegerPacking::WordPerInteger) as usize,
+        }
+    }
 
-                Value::Tuple(values) => {
-                    let values: Vec<_> = values.iter().cloned().map(|(_, value)| value).collect();
+    /// Returns the default (zero) value of the given type.
+    ///
+    /// Fixed size arrays are filled with default elements, dynamic arrays,
+    /// strings, fields and maps are empty, and optional values absent.
+    ///
+    /// Types nesting deeper than [`MAX_TYPE_DEPTH`] levels, or whose fixed
+    /// size arrays add up to more than [`MAX_DEFAULT_ELEMENTS`] elements, are
+    /// an error rather than an unbounded allocation.
+    ///
+    /// ```
+    /// use ola_lang_abi::{Type, Value};
+    ///
+    /// let ty: Type = "u32[2]".parse().unwrap();
+    /// assert_eq!(
+    ///     Value::default_for(&ty).unwrap(),
+    ///     Value::FixedArray(vec![Value::U32(0), Value::U32(0)], Type::U32)
+    /// );
+    /// assert!(Value::default_for(&"u32[4294967295]".parse().unwrap()).is_err());
+    /// ```
+    pub fn default_for(ty: &Type) -> Result<Value> {
+        if Value::default_elements(ty, 0)? > MAX_DEFAULT_ELEMENTS {
+            return Err(AbiError::InvalidInput(format!(
+                "default value of {} has more than {} elements",
+                ty, MAX_DEFAULT_ELEMENTS
+            )));
+        }
 
-                    let bytes = Self::encode(&values);
-                    buf.extend(bytes);
-                }
+        Ok(Value::default_unbounded(ty))
+    }
 
-                Value::String(value) => {
-                    let start = buf.len();
-                    let value_len = value.as_bytes().len();
-                    let new_len = start + value_len + 1;
-                    buf.resize(new_len, value_len as u64);
-
-                    // TODO Currently, Ola can only encode strings into arrays based on fields
-                    // and does not support encoding into u8 type arrays.
-                    // write bytes
-                    buf[start + 1..(new_len)].copy_from_slice(
-                        value
-                            .as_bytes()
-                            .into_iter()
-                            .map(|x| *x as u64)
-                            .collect::<Vec<u64>>()
-                            .as_slice(),
-                    );
-                }
+    /// Number of fixed size array elements of the default value of a type
+    /// nested `depth` levels deep, saturating.
+    fn default_elements(ty: &Type, depth: usize) -> Result<u64> {
+        if depth > MAX_TYPE_DEPTH {
+            return Err(AbiError::InvalidInput(format!(
+                "type nests deeper than {} levels",
+                MAX_TYPE_DEPTH
+            )));
+        }
 
-                Value::Fields(value) => {
-                    let start = buf.len();
-                    let value_len = value.len();
-                    let new_len = start + value_len + 1;
-                    buf.resize(new_len, value_len as u64);
+        match ty {
+            Type::FixedArray(elem, size) => Ok(Value::default_elements(elem, depth + 1)?
+                .saturating_add(1)
+                .saturating_mul(*size)),
+            Type::Tuple(tys) => tys.iter().try_fold(0u64, |elements, (_, ty)| {
+                Ok(elements.saturating_add(Value::default_elements(ty, depth + 1)?))
+            }),
+            _ => Ok(0),
+        }
+    }
 
-                    // write bytes
-                    buf[start + 1..new_len].copy_from_slice(value);
-                }
+    /// Default value of a type, without limits.
+    fn default_unbounded(ty: &Type) -> Value {
+        match ty {
+            Type::U32 => Value::U32(0),
+            Type::U256 => Value::U256(FixedArray8([0; 8])),
+            Type::Field => Value::Field(0),
+            Type::I32 => Value::I32(0),
+            Type::I64 => Value::I64(0),
+            Type::Address => Value::Address(FixedArray4([0; 4])),
+            Type::Hash => Value::Hash(FixedArray4([0; 4])),
+            Type::Bool => Value::Bool(false),
+            Type::FixedArray(ty, size) => Value::FixedArray(
+                (0..*size).map(|_| Value::default_unbounded(ty)).collect(),
+                *ty.clone(),
+            ),
+            Type::String => Value::String(String::new()),
+            Type::Fields => Value::Fields(vec![]),
+            Type::Array(ty) => Value::Array(vec![], *ty.clone()),
+            Type::Tuple(tys) => Value::Tuple(
+                tys.iter()
+                    .map(|(name, ty)| (name.clone(), Value::default_unbounded(ty)))
+                    .collect(),
+            ),
+            Type::Option(ty) => Value::None(*ty.clone()),
+            Type::Map(key, value) => Value::Map(vec![], *key.clone(), *value.clone()),
+        }
+    }
 
-                Value::Array(values, _) => {
-                    let start = buf.len();
-                    buf.resize(start + 1, values.len() as u64);
-                    // write array values
-                    let bytes = Self::encode(values);
-                    buf.extend(bytes);
+    /// Checks that the value is of the given type, tuple member names aside.
+    pub fn check_type(&self, ty: &Type) -> Result<()> {
+        let matches = match (self, ty) {
+            (Value::FixedArray(values, _), Type::FixedArray(ty, size)) => {
+                if values.len() as u64 != *size {
+                    return Err(AbiError::LengthMismatch(format!(
+                        "expected {} elements of {}, got {}",
+                        size,
+                        ty,
+                        values.len()
+                    )));
                 }
-            };
+                return values.iter().try_for_each(|value| value.check_type(ty));
+            }
+            (Value::Array(values, _), Type::Array(ty)) => {
+                return values.iter().try_for_each(|value| value.check_type(ty));
+            }
+            (Value::Tuple(values), Type::Tuple(tys)) if values.len() == tys.len() => {
+                return values
+                    .iter()
+                    .zip(tys)
+                    .try_for_each(|((_, value), (_, ty))| value.check_type(ty));
+            }
+            (Value::Some(value), Type::Option(ty)) => return value.check_type(ty),
+            (Value::None(none_ty), Type::Option(ty)) => none_ty == &**ty,
+            (Value::Map(entries, ..), Type::Map(key_ty, value_ty)) => {
+                let mut keys = BTreeSet::new();
+                return entries.iter().try_for_each(|(key, value)| {
+                    key.check_type(key_ty)?;
+                    value.check_type(value_ty)?;
+                    if !keys.insert(key) {
+                        return Err(AbiError::InvalidInput(format!(
+                            "duplicate map key {:?}",
+                            key
+                        )));
+                    }
+                    Ok(())
+                });
+            }
+            (Value::I64(n), Type::I64) if n.unsigned_abs() > SIGNED_MAX as u64 => {
+                return Err(AbiError::InvalidInput(format!(
+                    "i64 {} is out of the field element range",
+                    n
+                )));
+            }
+            (Value::U256(n), Type::U256) if !n.is_valid() => {
+                return Err(AbiError::InvalidInput(format!(
+                    "u256 {:?} has limbs wider than 32 bits",
+                    n.0
+                )));
+            }
+            (Value::U32(_), Type::U32)
+            | (Value::I32(_), Type::I32)
+            | (Value::I64(_), Type::I64)
+            | (Value::U256(_), Type::U256)
+            | (Value::Field(_), Type::Field)
+            | (Value::Address(_), Type::Address)
+            | (Value::Hash(_), Type::Hash)
+            | (Value::Bool(_), Type::Bool)
+            | (Value::String(_), Type::String)
+            | (Value::Fields(_), Type::Fields) => true,
+            (Value::Placeholder(_, placeholder_ty), ty) => placeholder_ty == ty,
+            _ => false,
+        };
+
+        if matches {
+            Ok(())
+        } else {
+            Err(AbiError::TypeMismatch {
+                expected: ty.clone(),
+                found: self.type_of(),
+            })
         }
-
-        buf
     }
 
     /// Returns the type of the given value.
@@ -231,6 +630,8 @@ impl Value {
             Value::U32(_) => Type::U32,
             Value::U256(_) => Type::U256,
             Value::Field(_) => Type::Field,
+            Value::I32(_) => Type::I32,
+            Value::I64(_) => Type::I64,
             Value::Address(_) => Type::Address,
             Value::Hash(_) => Type::Hash,
             Value::Bool(_) => Type::Bool,
@@ -246,164 +647,117 @@ impl Value {
                     .map(|(name, value)| (name.clone(), value.type_of()))
                     .collect(),
             ),
+            Value::None(ty) => Type::Option(Box::new(ty.clone())),
+            Value::Some(value) => Type::Option(Box::new(value.type_of())),
+            Value::Map(_, key, value) => Type::Map(Box::new(key.clone()), Box::new(value.clone())),
+            Value::Placeholder(_, ty) => ty.clone(),
         }
     }
+}
 
-    fn decode(bs: &[u64], ty: &Type, base_addr: usize, at: usize) -> Result<(Value, usize)> {
-        match ty {
-            Type::U32 => {
-                let at = base_addr + at;
-                let slice = bs
-                    .get(at..(at + 1))
-                    .ok_or_else(|| anyhow!("reached end of input while decoding {:?}", ty))?;
-
-                let u32_value = slice[0];
-
-                Ok((Value::U32(u32_value), 1))
-            }
-
-            Type::U256 => {
-                let at = base_addr + at;
-                let slice = bs
-                    .get(at..(at + 8))
-                    .ok_or_else(|| anyhow!("reached end of input while decoding {:?}", ty))?;
-
-                let mut u256_value = [0u64; 8];
-                u256_value.copy_from_slice(slice);
-
-                Ok((Value::U256(FixedArray8(u256_value)), 8))
-            }
-
-            Type::Field => {
-                let at = base_addr + at;
-                let slice = bs
-                    .get(at..(at + 1))
-                    .ok_or_else(|| anyhow!("reached end of input while decoding {:?}", ty))?;
-
-                let field_value = slice[0];
-
-                Ok((Value::Field(field_value), 1))
-            }
-
-            Type::Address => {
-                let at = base_addr + at;
-                let slice = bs
-                    .get(at..(at + 4))
-                    .ok_or_else(|| anyhow!("reached end of input while decoding {:?}", ty))?;
-
-                let mut addr = [0u64; 4];
-                addr.copy_from_slice(slice);
-
-                Ok((Value::Address(FixedArray4(addr)), 4))
-            }
-
-            Type::Hash => {
-                let at = base_addr + at;
-                let slice = bs
-                    .get(at..(at + 4))
-                    .ok_or_else(|| anyhow!("reached end of input while decoding {:?}", ty))?;
-
-                let mut hash = [0u64; 4];
-                hash.copy_from_slice(slice);
-
-                Ok((Value::Hash(FixedArray4(hash)), 4))
-            }
-
-            Type::Bool => {
-                let at = base_addr + at;
-                let slice = bs
-                    .get(at..(at + 1))
-                    .ok_or_else(|| anyhow!("reached end of input while decoding bool"))?;
-
-                let b = slice[0] == 1;
-
-                Ok((Value::Bool(b), 1))
-            }
-            Type::FixedArray(ty, size) => (0..(*size))
-                .try_fold((vec![], 0), |(mut values, total_consumed), _| {
-                    let (value, consumed) = Self::decode(bs, ty, base_addr, at + total_consumed)?;
-
-                    values.push(value);
-
-                    Ok((values, total_consumed + consumed))
-                })
-                .map(|(values, consumed)| (Value::FixedArray(values, *ty.clone()), consumed)),
-
-            Type::String => {
-                let (bytes_value, consumed) = Self::decode(bs, &Type::Fields, base_addr, at)?;
-
-                let bytes = if let Value::Fields(bytes) = bytes_value {
-                    bytes
-                } else {
-                    // should always be Value::Bytes
-                    unreachable!();
-                };
-
-                let s = String::from_utf8(bytes.into_iter().map(|b| b as u8).collect())?;
-
-                Ok((Value::String(s), consumed))
-            }
+#[cfg(test)]
+mod test {
 
-            Type::Fields => {
-                let at = base_addr + at;
-                let field_len_slice = bs
-                    .get(at..(at + 1))
-                    .ok_or_else(|| anyhow!("reached end of input while decoding fields length"))?;
-                let field_len = field_len_slice[0] as usize;
-
-                let at = at + 1;
-                let fields_value = bs
-                    .get(at..(at + field_len))
-                    .ok_or_else(|| anyhow!("reached end of input while decoding bytes"))?
-                    .to_vec();
-
-                // consumes only the first 32 bytes, i.e. the offset pointer
-                Ok((Value::Fields(fields_value), field_len + 1))
-            }
+    use super::*;
 
-            Type::Array(ty) => {
-                let at = base_addr + at;
+    use pretty_assertions::assert_eq;
 
-                let array_len_slice = bs
-                    .get(at..(at + 1))
-                    .ok_or_else(|| anyhow!("reached end of input while decoding array length"))?;
-                let array_len = array_len_slice[0];
+    #[test]
+    #[cfg(feature = "rand")]
+    fn random_words_are_field_elements() {
+        for _ in 0..1000 {
+            assert!(FixedArray4::random()
+                .0
+                .iter()
+                .all(|&word| word < FIELD_ORDER));
+        }
+    }
 
-                let at = at + 1;
+    #[test]
+    fn default_for_is_bounded() {
+        let ty = |s: &str| s.parse::<Type>().unwrap();
 
-                (0..array_len)
-                    .try_fold((vec![], 0), |(mut values, total_consumed), _| {
-                        let (value, consumed) = Self::decode(bs, ty, at, total_consumed)?;
-                        values.push(value);
+        assert_eq!(
+            Value::default_for(&ty("(u32,string?)[2]")).unwrap(),
+            Value::default_unbounded(&ty("(u32,string?)[2]"))
+        );
+        assert!(Value::default_for(&ty("u32[1048576]")).is_ok());
 
-                        Ok((values, total_consumed + consumed))
-                    })
-                    .map(|(values, total_consumed)| {
-                        (Value::Array(values, *ty.clone()), total_consumed + 1)
-                    })
-            }
+        for ty in [ty("u32[4294967295]"), ty("u32[1024][1024]")] {
+            assert_eq!(
+                Value::default_for(&ty).unwrap_err().to_string(),
+                format!("default value of {} has more than 1048576 elements", ty)
+            );
+        }
 
-            Type::Tuple(tys) => tys
-                .iter()
-                .cloned()
-                .try_fold((vec![], 0), |(mut values, total_consumed), (name, ty)| {
-                    let (value, consumed) = Self::decode(bs, &ty, base_addr, at + total_consumed)?;
+        let deep = (0..=MAX_TYPE_DEPTH).fold(Type::U32, |ty, _| Type::FixedArray(Box::new(ty), 1));
+        assert_eq!(
+            Value::default_for(&deep).unwrap_err().to_string(),
+            "type nests deeper than 64 levels"
+        );
+    }
 
-                    values.push((name, value));
+    #[test]
+    fn encoded_len_matches_encoding() {
+        let values = vec![
+            Value::U256(FixedArray8::from(7u64)),
+            Value::String("olavm".to_string()),
+            Value::Fields(vec![1, 2]),
+            Value::Array(
+                vec![Value::Tuple(vec![
+                    (
+                        "owner".to_string(),
+                        Value::Address(FixedArray4([0, 0, 0, 1])),
+                    ),
+                    ("ids".to_string(), Value::Array(vec![], Type::U32)),
+                ])],
+                Type::Tuple(vec![
+                    ("owner".to_string(), Type::Address),
+                    ("ids".to_string(), Type::Array(Box::new(Type::U32))),
+                ]),
+            ),
+            Value::FixedArray(vec![Value::I64(-1), Value::Bool(true)], Type::I64),
+            Value::Placeholder("nonce".to_string(), Type::Hash),
+            Value::Placeholder(
+                "books".to_string(),
+                "(u32,string,bool?)[3]".parse().unwrap(),
+            ),
+        ];
 
-                    Ok((values, total_consumed + consumed))
-                })
-                .map(|(values, total_consumed)| (Value::Tuple(values), total_consumed)),
-        }
+        let mut buf = vec![42];
+        Value::encode_into(&values, &mut buf);
+        assert_eq!(buf[0], 42);
+        assert_eq!(buf[1..], Value::encode(&values));
+        assert_eq!(Value::encoded_len(&values), buf.len() - 1);
     }
-}
 
-#[cfg(test)]
-mod test {
+    #[test]
+    fn signed_field_encoding() {
+        let values = [Value::I32(-1), Value::I32(5), Value::I64(-SIGNED_MAX)];
+        let encoded = Value::encode(&values);
+        assert_eq!(
+            encoded,
+            vec![FIELD_ORDER - 1, 5, FIELD_ORDER - SIGNED_MAX as u64]
+        );
+        assert_eq!(
+            Value::decode_from_slice(&encoded, &[Type::I32, Type::I32, Type::I64]).unwrap(),
+            values
+        );
+        assert_eq!(
+            Value::decode_from_slice(&[SIGNED_MAX as u64 + 1], &[Type::I64]).unwrap(),
+            vec![Value::I64(-SIGNED_MAX)]
+        );
 
-    use super::*;
+        // not a field element
+        assert!(Value::decode_from_slice(&[FIELD_ORDER], &[Type::I64]).is_err());
+        // out of i32 range
+        assert!(Value::decode_from_slice(&[FIELD_ORDER - (1 << 31) - 1], &[Type::I32]).is_err());
+        assert!(Value::decode_from_slice(&[1 << 31], &[Type::I32]).is_err());
 
-    use pretty_assertions::assert_eq;
+        assert!(Value::I64(i64::MIN).check_type(&Type::I64).is_err());
+        assert!(Value::I64(SIGNED_MAX).check_type(&Type::I64).is_ok());
+        assert_eq!(Type::I32.to_string(), "i32");
+    }
 
     #[test]
     fn decode_uint() {
@@ -430,6 +784,37 @@ mod test {
         assert_eq!(v, vec![Value::U256(FixedArray8([1, 2, 3, 4, 5, 6, 7, 10]))]);
     }
 
+    #[test]
+    fn u256_conversions() {
+        let n = FixedArray8::from(u128::MAX - 1);
+        assert_eq!(
+            n.0,
+            [
+                0,
+                0,
+                0,
+                0,
+                0xffff_ffff,
+                0xffff_ffff,
+                0xffff_ffff,
+                0xffff_fffe
+            ]
+        );
+        assert_eq!(n.to_u128(), Some(u128::MAX - 1));
+        assert_eq!(FixedArray8::from_bytes32(&n.to_bytes32()), n);
+        assert_eq!(FixedArray8::from_hex(&n.to_hex_string()).unwrap(), n);
+        assert_eq!(FixedArray8::from(10u64), FixedArray8::from("0x0a"));
+
+        assert_eq!(FixedArray8([1, 0, 0, 0, 0, 0, 0, 0]).to_u128(), None);
+        assert!(FixedArray8::from_hex(&"f".repeat(65)).is_err());
+
+        let wide = FixedArray8([0, 0, 0, 0, 0, 0, 0, 1 << 32]);
+        assert!(!wide.is_valid());
+        assert_eq!(wide.to_u128(), None);
+        assert!(Value::U256(wide).check_type(&Type::U256).is_err());
+        assert!(Value::U256(n).check_type(&Type::U256).is_ok());
+    }
+
     #[test]
     fn decode_field() {
         let bs = vec![100, 200, 300];
@@ -508,7 +893,7 @@ mod test {
     fn decode_string() {
         let source = "olavm"
             .as_bytes()
-            .into_iter()
+            .iter()
             .map(|x| *x as u64)
             .collect::<Vec<u64>>();
         let mut bs = vec![source.len() as u64];
@@ -523,7 +908,7 @@ mod test {
     fn decode_fields() {
         let source = "hello,world"
             .as_bytes()
-            .into_iter()
+            .iter()
             .map(|x| *x as u64)
             .collect::<Vec<u64>>();
         let mut bs = vec![source.len() as u64];
@@ -628,7 +1013,7 @@ mod test {
         let str = "olavm".to_string();
         let source = str
             .as_bytes()
-            .into_iter()
+            .iter()
             .map(|x| *x as u64)
             .collect::<Vec<u64>>();
         bs.resize(2, 0);
@@ -687,6 +1072,66 @@ mod test {
         );
     }
 
+    #[test]
+    fn value_ordering() {
+        let mut values = vec![
+            Value::Tuple(vec![("a".to_string(), Value::U32(1))]),
+            Value::String("b".to_string()),
+            Value::U32(7),
+            Value::Bool(true),
+            Value::String("a".to_string()),
+            Value::Array(vec![Value::U32(2)], Type::U32),
+            Value::U32(3),
+            Value::Array(vec![Value::U32(1), Value::U32(9)], Type::U32),
+            Value::Address(FixedArray4([0, 0, 1, 0])),
+            Value::Address(FixedArray4([0, 0, 0, 9])),
+        ];
+
+        values.sort();
+
+        assert_eq!(
+            values,
+            vec![
+                Value::U32(3),
+                Value::U32(7),
+                Value::Address(FixedArray4([0, 0, 0, 9])),
+                Value::Address(FixedArray4([0, 0, 1, 0])),
+                Value::Bool(true),
+                Value::String("a".to_string()),
+                Value::String("b".to_string()),
+                Value::Array(vec![Value::U32(1), Value::U32(9)], Type::U32),
+                Value::Array(vec![Value::U32(2)], Type::U32),
+                Value::Tuple(vec![("a".to_string(), Value::U32(1))]),
+            ]
+        );
+
+        // signed ints, added later, order after the original variants
+        assert!(Value::Field(0) < Value::Address(FixedArray4([0; 4])));
+        assert!(Value::Placeholder("x".to_string(), Type::U32) < Value::I32(-1));
+        assert!(Value::I32(1) < Value::I64(-1));
+        assert!(Type::Map(Box::new(Type::U32), Box::new(Type::U32)) < Type::I32);
+
+        // member names come before their values
+        let tuple = |name: &str, n| Value::Tuple(vec![(name.to_string(), Value::U32(n))]);
+        assert!(tuple("a", 2) < tuple("b", 1));
+        assert!(tuple("a", 1) < tuple("a", 2));
+        assert!(
+            Value::Array(vec![Value::U32(1)], Type::Field)
+                < Value::Array(vec![Value::U32(2)], Type::U32)
+        );
+
+        let counts = [Value::U32(2), Value::U32(1), Value::U32(2)]
+            .into_iter()
+            .fold(alloc::collections::BTreeMap::new(), |mut counts, value| {
+                *counts.entry(value).or_insert(0) += 1;
+                counts
+            });
+        assert_eq!(
+            counts.into_iter().collect::<Vec<_>>(),
+            vec![(Value::U32(1), 1), (Value::U32(2), 2)]
+        );
+    }
+
     #[test]
     fn encode_u32() {
         let value = Value::U32(12);
@@ -825,4 +1270,194 @@ mod test {
         let expected = [5, 111, 108, 97, 118, 109, 99, 2, 1, 2, 1, 3];
         assert_eq!(Value::encode(&values), expected);
     }
+
+    /// Round trips `values` of type `tys` through the given word layout.
+    fn assert_layout(tys: &[Type], values: &[Value], layout: &[u64]) {
+        assert_eq!(Value::encode(values), layout);
+        assert_eq!(Value::decode_from_slice(layout, tys).unwrap(), values);
+        assert_eq!(
+            values.iter().map(Value::type_of).collect::<Vec<_>>(),
+            tys.to_vec()
+        );
+    }
+
+    #[test]
+    fn nested_fixed_array_layout() {
+        // u32[2][3]: three u32[2], laid out back to back
+        let ty = Type::FixedArray(Box::new(Type::FixedArray(Box::new(Type::U32), 2)), 3);
+        let value = Value::FixedArray(
+            (0..3)
+                .map(|i| {
+                    Value::FixedArray(vec![Value::U32(2 * i), Value::U32(2 * i + 1)], Type::U32)
+                })
+                .collect(),
+            Type::FixedArray(Box::new(Type::U32), 2),
+        );
+
+        assert!(!ty.is_dynamic());
+        assert_layout(
+            &[ty, Type::U32],
+            &[value, Value::U32(9)],
+            &[0, 1, 2, 3, 4, 5, 9],
+        );
+    }
+
+    #[test]
+    fn fixed_array_of_tuples_layout() {
+        // (u32,address)[4]: every tuple inlined, no length prefix
+        let tuple_ty = Type::Tuple(vec![
+            ("id".to_string(), Type::U32),
+            ("owner".to_string(), Type::Address),
+        ]);
+        let ty = Type::FixedArray(Box::new(tuple_ty.clone()), 4);
+        let value = Value::FixedArray(
+            (0..4)
+                .map(|i| {
+                    Value::Tuple(vec![
+                        ("id".to_string(), Value::U32(i)),
+                        ("owner".to_string(), Value::Address(FixedArray4([i; 4]))),
+                    ])
+                })
+                .collect(),
+            tuple_ty,
+        );
+
+        let layout = (0..4).flat_map(|i| [i; 5]).collect::<Vec<_>>();
+
+        assert!(!ty.is_dynamic());
+        assert_layout(&[ty], &[value], &layout);
+    }
+
+    #[test]
+    fn fixed_array_of_dynamic_members_layout() {
+        // dynamic members are inlined too, each with its own length prefix
+        let ty = Type::FixedArray(Box::new(Type::String), 2);
+        let value = Value::FixedArray(
+            vec![
+                Value::String("ab".to_string()),
+                Value::String(String::new()),
+            ],
+            Type::String,
+        );
+
+        assert!(ty.is_dynamic());
+        assert_layout(
+            &[ty, Type::U32],
+            &[value, Value::U32(7)],
+            &[2, 97, 98, 0, 7],
+        );
+
+        // (string,u32[])[2]
+        let tuple_ty = Type::Tuple(vec![
+            ("name".to_string(), Type::String),
+            ("ids".to_string(), Type::Array(Box::new(Type::U32))),
+        ]);
+        let ty = Type::FixedArray(Box::new(tuple_ty.clone()), 2);
+        let value = Value::FixedArray(
+            vec![
+                Value::Tuple(vec![
+                    ("name".to_string(), Value::String("a".to_string())),
+                    (
+                        "ids".to_string(),
+                        Value::Array(vec![Value::U32(1), Value::U32(2)], Type::U32),
+                    ),
+                ]),
+                Value::Tuple(vec![
+                    ("name".to_string(), Value::String(String::new())),
+                    ("ids".to_string(), Value::Array(vec![], Type::U32)),
+                ]),
+            ],
+            tuple_ty,
+        );
+
+        assert!(ty.is_dynamic());
+        assert_layout(&[ty], &[value], &[1, 97, 2, 1, 2, 0, 0]);
+    }
+
+    #[test]
+    fn array_of_fixed_arrays_of_strings_layout() {
+        // string[2][]
+        let inner = Type::FixedArray(Box::new(Type::String), 2);
+        let ty = Type::Array(Box::new(inner.clone()));
+        let value = Value::Array(
+            vec![Value::FixedArray(
+                vec![
+                    Value::String("a".to_string()),
+                    Value::String("b".to_string()),
+                ],
+                Type::String,
+            )],
+            inner,
+        );
+
+        assert_layout(&[ty], &[value], &[1, 1, 97, 1, 98]);
+    }
+
+    #[test]
+    fn truncated_fixed_array_of_tuples() {
+        let ty = Type::FixedArray(
+            Box::new(Type::Tuple(vec![
+                ("id".to_string(), Type::U32),
+                ("owner".to_string(), Type::Address),
+            ])),
+            2,
+        );
+
+        assert!(Value::decode_from_slice(&[1, 1, 1, 1, 1, 2, 2], &[ty]).is_err());
+    }
+
+    #[test]
+    fn fixed_array4_from_hex() {
+        assert_eq!(FixedArray4::from("0x1"), FixedArray4([0, 0, 0, 1]));
+        assert_eq!(FixedArray4::from("abc"), FixedArray4([0, 0, 0, 0xabc]));
+        // odd length, crossing a limb boundary
+        assert_eq!(
+            FixedArray4::from_hex("0x10000000000000002").unwrap(),
+            FixedArray4([0, 0, 1, 2])
+        );
+        assert_eq!(
+            FixedArray4::from_hex(&"f".repeat(64)).unwrap(),
+            FixedArray4([u64::MAX; 4])
+        );
+        assert_eq!(FixedArray4::from_hex("0x").unwrap(), FixedArray4::ZERO);
+
+        assert_eq!(
+            FixedArray4::from_hex(&format!("0x1{}", "0".repeat(64)))
+                .unwrap_err()
+                .to_string(),
+            "expected at most 64 hex digits, got 65 digits"
+        );
+        assert!(FixedArray4::from_hex("0x+1").is_err());
+        assert!(FixedArray4::from_hex("0xg").is_err());
+
+        assert_eq!(
+            "0XaBcDeF".parse::<FixedArray4>().unwrap(),
+            FixedArray4([0, 0, 0, 0xabcdef])
+        );
+        assert_eq!(
+            "0x0x1".parse::<FixedArray4>().unwrap_err().to_string(),
+            "invalid hex digit 'x' in \"0x0x1\""
+        );
+        assert!("".parse::<FixedArray4>().is_ok());
+        assert!(" 0x1".parse::<FixedArray4>().is_err());
+    }
+
+    #[test]
+    fn fixed_array4_from_bytes() {
+        let mut bytes = [0u8; 32];
+        bytes[7] = 1;
+        bytes[31] = 4;
+
+        let value = FixedArray4::from_bytes32(&bytes);
+        assert_eq!(value, FixedArray4([1, 0, 0, 4]));
+        assert_eq!(FixedArray4::from(bytes), value);
+        assert_eq!(value.to_bytes32(), bytes);
+
+        assert_eq!(
+            FixedArray4::from_slice(&[4]).unwrap(),
+            FixedArray4([0, 0, 0, 4])
+        );
+        assert_eq!(FixedArray4::from_slice(&[]).unwrap(), FixedArray4::ZERO);
+        assert!(FixedArray4::from_slice(&[0; 33]).is_err());
+    }
 }
@@ -0,0 +1,151 @@
+//! Varint packing of integer sequences, see [`IntegerPacking::Varint`].
+//!
+//! Integers are LEB128 encoded (7 bits per byte, low groups first, the high
+//! bit set on all bytes but the last) and the bytes packed 7 per word, the
+//! first byte most significant, so that every word stays below the field
+//! order. The last word is padded with zero bytes.
+//!
+//! [`IntegerPacking::Varint`]: crate::IntegerPacking::Varint
+
+use crate::alloc_prelude::*;
+
+use crate::{
+    error::{AbiError, Result},
+    Write64,
+};
+
+/// Bytes packed into a word.
+const WORD_BYTES: usize = 7;
+
+/// Longest LEB128 encoding of a `u64`.
+const MAX_VARINT_BYTES: usize = 10;
+
+/// Writes integers varint packed.
+pub(crate) fn pack<W: Write64 + ?Sized>(integers: &[u64], sink: &mut W) {
+    let mut word = 0;
+    let mut len = 0;
+
+    for &integer in integers {
+        let mut rest = integer;
+        loop {
+            let byte = (rest & 0x7f) as u8;
+            rest >>= 7;
+            let byte = if rest == 0 { byte } else { byte | 0x80 };
+
+            word = (word << 8) | byte as u64;
+            len += 1;
+            if len == WORD_BYTES {
+                sink.write_word(word);
+                word = 0;
+                len = 0;
+            }
+
+            if rest == 0 {
+                break;
+            }
+        }
+    }
+
+    if len > 0 {
+        sink.write_word(word << (8 * (WORD_BYTES - len)));
+    }
+}
+
+/// Reads `count` varint packed integers from the start of `words`, returning
+/// them with the number of words they take.
+pub(crate) fn unpack(words: &[u64], count: u64) -> Result<(Vec<u64>, usize)> {
+    // each integer takes at least a byte
+    if count > words.len().saturating_mul(WORD_BYTES) as u64 {
+        return Err(AbiError::Truncated(format!(
+            "{} packed integers don't fit in {} words",
+            count,
+            words.len()
+        )));
+    }
+
+    let mut integers = Vec::with_capacity(count as usize);
+    let mut bytes = words.iter().enumerate().flat_map(|(i, word)| {
+        (0..WORD_BYTES).map(move |j| (i, (word >> (8 * (WORD_BYTES - 1 - j))) as u8))
+    });
+    let mut consumed = 0;
+
+    while (integers.len() as u64) < count {
+        let mut integer = 0u64;
+        for shift in 0..MAX_VARINT_BYTES {
+            let (i, byte) = bytes.next().ok_or_else(|| {
+                AbiError::Truncated("reached end of input while unpacking integers".to_string())
+            })?;
+            if words[i] >> (8 * WORD_BYTES) != 0 {
+                return Err(AbiError::InvalidInput(format!(
+                    "packed word {:#x} is wider than {} bytes",
+                    words[i], WORD_BYTES
+                )));
+            }
+            consumed = i + 1;
+
+            let bits = (byte & 0x7f) as u64;
+            if shift == MAX_VARINT_BYTES - 1 && bits > 1 {
+                return Err(AbiError::InvalidInput(
+                    "packed integer overflows u64".to_string(),
+                ));
+            }
+            integer |= bits << (7 * shift);
+
+            if byte & 0x80 == 0 {
+                break;
+            }
+            if shift == MAX_VARINT_BYTES - 1 {
+                return Err(AbiError::InvalidInput(
+                    "packed integer overflows u64".to_string(),
+                ));
+            }
+        }
+        integers.push(integer);
+    }
+
+    // the padding of the last word
+    if bytes
+        .take_while(|&(i, _)| i < consumed)
+        .any(|(_, byte)| byte != 0)
+    {
+        return Err(AbiError::InvalidInput(
+            "packed integers are followed by non-zero padding".to_string(),
+        ));
+    }
+
+    Ok((integers, consumed))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn varint_round_trip() {
+        let integers = [0, 1, 127, 128, 300, u32::MAX as u64, u64::MAX, 5];
+
+        let mut words = vec![];
+        pack(&integers, &mut words);
+        // 1 + 1 + 1 + 2 + 2 + 5 + 10 + 1 bytes
+        assert_eq!(words.len(), 4);
+        assert!(words.iter().all(|&word| word >> 56 == 0));
+
+        words.push(42);
+        assert_eq!(
+            unpack(&words, integers.len() as u64).unwrap(),
+            (integers.to_vec(), 4)
+        );
+        assert_eq!(unpack(&words, 0).unwrap(), (vec![], 0));
+
+        let mut small = vec![];
+        pack(&[1, 2, 3], &mut small);
+        assert_eq!(small, [0x01_02_03_00_00_00_00]);
+
+        assert!(unpack(&[1 << 56], 1).is_err());
+        assert!(unpack(&small, 2).is_err());
+        assert!(unpack(&[], 1).is_err());
+        assert!(unpack(&[0x00ff_ffff_ffff_ffff, 0x00ff_ffff_0000_0000], 1).is_err());
+    }
+}
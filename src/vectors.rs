@@ -0,0 +1,214 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{AbiError, Result},
+    Abi, FixedArray4, FixedArray8, Function, Param, Type, Value, MAX_DEFAULT_ELEMENTS,
+};
+
+/// Encode/decode test vector of a function call, for validating other
+/// implementations against this crate.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestVector {
+    /// Function signature.
+    pub signature: String,
+    /// Function method id.
+    pub selector: u64,
+    /// Input values.
+    pub inputs: Vec<Value>,
+    /// Encoded input calldata, `[params..., param-len, method_id]`.
+    pub calldata: Vec<u64>,
+    /// Output values.
+    pub outputs: Vec<Value>,
+    /// Encoded output data, `[params..., param-len]`.
+    pub output_data: Vec<u64>,
+}
+
+/// Returns deterministic test vectors for every function of the ABI: one
+/// with default (zero) values and one with representative non-zero values.
+///
+/// Params whose values would have more than [`MAX_DEFAULT_ELEMENTS`] array
+/// elements are an error, see [`Value::default_for`].
+pub fn test_vectors(abi: &Abi) -> Result<Vec<TestVector>> {
+    let mut vectors = Vec::with_capacity(abi.functions.len() * 2);
+    for f in &abi.functions {
+        vectors.push(test_vector(abi, f, |ty, _| Value::default_for(ty))?);
+        vectors.push(test_vector(abi, f, |ty, seed| {
+            let mut elements = MAX_DEFAULT_ELEMENTS;
+            sample_value(ty, seed, &mut elements).ok_or_else(|| {
+                AbiError::InvalidInput(format!(
+                    "sample value of {} has more than {} elements",
+                    ty, MAX_DEFAULT_ELEMENTS
+                ))
+            })
+        })?);
+    }
+
+    Ok(vectors)
+}
+
+fn test_vector(
+    abi: &Abi,
+    f: &Function,
+    value: impl Fn(&Type, u64) -> Result<Value>,
+) -> Result<TestVector> {
+    let values = |params: &[Param]| {
+        params
+            .iter()
+            .enumerate()
+            .map(|(i, param)| value(&param.type_, i as u64))
+            .collect::<Result<Vec<_>>>()
+    };
+
+    let inputs = values(&f.inputs)?;
+    let outputs = values(&f.outputs)?;
+
+    let mut calldata = abi.encode_values(&inputs)?;
+    calldata.push(f.method_id());
+
+    Ok(TestVector {
+        signature: f.signature(),
+        selector: f.method_id(),
+        calldata,
+        output_data: abi.encode_values(&outputs)?,
+        inputs,
+        outputs,
+    })
+}
+
+/// Takes `n` array elements from the budget of a sample value.
+fn take(elements: &mut u64, n: u64) -> Option<()> {
+    *elements = elements.checked_sub(n)?;
+    Some(())
+}
+
+/// Representative value of a type, varying with `seed`, or `None` when it
+/// has more array elements than left in `elements`.
+fn sample_value(ty: &Type, seed: u64, elements: &mut u64) -> Option<Value> {
+    let value = match ty {
+        Type::U32 => Value::U32(u32::MAX as u64 - seed),
+        Type::U256 => Value::U256(FixedArray8([u32::MAX as u64, 0, 1, 2, 3, 4, 5, seed])),
+        // the largest goldilocks field element
+        Type::Field => Value::Field(0xffff_ffff_0000_0000 - seed),
+        Type::I32 => Value::I32(i32::MIN + seed as i32),
+        // the most negative field-encoded i64
+        Type::I64 => Value::I64(-crate::SIGNED_MAX + seed as i64),
+        Type::Address => Value::Address(FixedArray4([seed, 1, 2, 3])),
+        Type::Hash => Value::Hash(FixedArray4([u64::MAX, seed, 0, 1])),
+        Type::Bool => Value::Bool(seed & 1 == 0),
+        Type::String => Value::String(format!("ola-{}", seed)),
+        Type::Fields => Value::Fields(vec![seed, 1, 0xffff_ffff_0000_0000]),
+        Type::FixedArray(ty, size) => {
+            take(elements, *size)?;
+            Value::FixedArray(
+                (0..*size)
+                    .map(|i| sample_value(ty, seed.wrapping_add(i), elements))
+                    .collect::<Option<_>>()?,
+                *ty.clone(),
+            )
+        }
+        Type::Array(ty) => {
+            take(elements, 2)?;
+            Value::Array(
+                (0..2)
+                    .map(|i| sample_value(ty, seed + i, elements))
+                    .collect::<Option<_>>()?,
+                *ty.clone(),
+            )
+        }
+        Type::Tuple(tys) => Value::Tuple(
+            tys.iter()
+                .enumerate()
+                .map(|(i, (name, ty))| {
+                    Some((name.clone(), sample_value(ty, seed + i as u64, elements)?))
+                })
+                .collect::<Option<_>>()?,
+        ),
+        Type::Option(ty) => Value::Some(Box::new(sample_value(ty, seed, elements)?)),
+        // a single entry, as keys of other samples may collide
+        Type::Map(key, value) => {
+            take(elements, 1)?;
+            Value::Map(
+                vec![(
+                    sample_value(key, seed, elements)?,
+                    sample_value(value, seed, elements)?,
+                )],
+                *key.clone(),
+                *value.clone(),
+            )
+        }
+    };
+
+    Some(value)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn vectors_round_trip() {
+        let abi: Abi = serde_json::from_str(
+            r#"[{
+                "type": "function",
+                "name": "createBook",
+                "inputs": [
+                    { "name": "id", "type": "u32" },
+                    { "name": "tags", "type": "string[]" },
+                    {
+                        "name": "meta",
+                        "type": "tuple",
+                        "components": [
+                            { "name": "owner", "type": "address" },
+                            { "name": "price", "type": "u256" }
+                        ]
+                    }
+                ],
+                "outputs": [{ "name": "ok", "type": "bool" }]
+            }]"#,
+        )
+        .unwrap();
+
+        let vectors = test_vectors(&abi).unwrap();
+        assert_eq!(vectors.len(), 2);
+        assert_eq!(vectors, test_vectors(&abi).unwrap());
+
+        for vector in &vectors {
+            let (f, params) = abi.decode_input_from_slice(&vector.calldata).unwrap();
+            assert_eq!(f.signature(), vector.signature);
+            assert_eq!(
+                params.iter().map(|p| p.value.clone()).collect::<Vec<_>>(),
+                vector.inputs
+            );
+
+            let (_, params) = abi
+                .decode_output_from_slice(&vector.signature, &vector.output_data)
+                .unwrap();
+            assert_eq!(
+                params.iter().map(|p| p.value.clone()).collect::<Vec<_>>(),
+                vector.outputs
+            );
+        }
+
+        assert_eq!(vectors[1].calldata[0], u32::MAX as u64);
+        assert_eq!(vectors[1].outputs, vec![Value::Bool(true)]);
+    }
+
+    #[test]
+    fn huge_params_are_an_error() {
+        let abi = Abi::from_human_readable(&["function huge(u32[4294967295] ids)"]).unwrap();
+        assert_eq!(
+            test_vectors(&abi).unwrap_err().to_string(),
+            "default value of u32[4294967295] has more than 1048576 elements"
+        );
+
+        // dynamic arrays double the samples of every level
+        let abi = Abi::from_human_readable(&["function deep(u32[1024][][][][][][][][][][][] ids)"])
+            .unwrap();
+        assert_eq!(
+            test_vectors(&abi).unwrap_err().to_string(),
+            "sample value of u32[1024][][][][][][][][][][][] has more than 1048576 elements"
+        );
+    }
+}
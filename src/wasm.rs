@@ -0,0 +1,413 @@
+//! JavaScript bindings, enabled with the `wasm` feature.
+//!
+//! Every entry point comes in two flavours: one taking the ABI JSON file
+//! content as bytes, and one (suffixed `_with_abi`) taking an already parsed
+//! JS object, so frontends importing the ABI as a module don't have to
+//! re-stringify it on every call. Callers decoding many calls against the
+//! same ABI should parse it once into an [`AbiHandle`] instead.
+//!
+//! Errors are thrown as `{ code, message, context }` objects, see
+//! [`AbiError::to_json`]. Panics are caught at every entry point and thrown
+//! with the `PANIC` code instead of tearing down the instance; this needs a
+//! build with unwinding (`-C panic=unwind`), as `wasm32-unknown-unknown`
+//! aborts on panic by default. Call [`init_panic_hook`] once at startup so that panics are
+//! logged to the console either way.
+
+use std::{
+    any::Any,
+    panic::{self, AssertUnwindSafe},
+    sync::Once,
+};
+
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    calldata, hash::topic_of, version_info as native_version_info, Abi, AbiError, DecodedParams,
+    FixedArray4, RawLog, Value,
+};
+
+/// Decoded function call or event log returned to JS.
+#[derive(Serialize)]
+struct DecodedCall {
+    name: String,
+    params: DecodedParams,
+}
+
+/// Converts an error to the `{ code, message, context }` object thrown to
+/// JS, see [`AbiError::to_json`].
+fn to_js_error(err: impl Into<AbiError>) -> JsValue {
+    to_js_error_object(err.into().to_json())
+}
+
+fn to_js_error_object(error: serde_json::Value) -> JsValue {
+    // as plain objects rather than `Map`s
+    error
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .unwrap_or_else(|_| JsValue::from_str(&error["message"].to_string()))
+}
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = console, js_name = error)]
+    fn console_error(message: &str);
+}
+
+/// Installs a panic hook logging panic messages and locations with
+/// `console.error`, instead of the bare `unreachable` trap. Calling it more
+/// than once is harmless.
+#[wasm_bindgen]
+pub fn init_panic_hook() {
+    static HOOK: Once = Once::new();
+
+    HOOK.call_once(|| {
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if cfg!(target_arch = "wasm32") {
+                console_error(&info.to_string());
+            } else {
+                default_hook(info);
+            }
+        }));
+    });
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic")
+}
+
+/// Runs an entry point, turning a panic into a `PANIC` error.
+fn catch_panic<T>(f: impl FnOnce() -> Result<T, JsValue>) -> Result<T, JsValue> {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        Err(to_js_error_object(serde_json::json!({
+            "code": "PANIC",
+            "message": format!("panicked: {}", panic_message(payload.as_ref())),
+            "context": {},
+        })))
+    })
+}
+
+fn invalid_abi(err: impl std::fmt::Display) -> JsValue {
+    to_js_error(AbiError::InvalidAbi(err.to_string()))
+}
+
+fn invalid_input(err: impl std::fmt::Display) -> JsValue {
+    to_js_error(AbiError::InvalidInput(err.to_string()))
+}
+
+fn to_js<T: Serialize>(value: &T) -> Result<JsValue, JsValue> {
+    let serializer =
+        serde_wasm_bindgen::Serializer::new().serialize_large_number_types_as_bigints(true);
+    value
+        .serialize(&serializer)
+        .map_err(|e| to_js_error(anyhow::Error::msg(e.to_string())))
+}
+
+fn abi_from_bytes(file_content: &[u8]) -> Result<Abi, JsValue> {
+    serde_json::from_slice(file_content).map_err(invalid_abi)
+}
+
+fn abi_from_js(abi: JsValue) -> Result<Abi, JsValue> {
+    serde_wasm_bindgen::from_value(abi).map_err(invalid_abi)
+}
+
+fn decode_input(abi: &Abi, input: &[u64]) -> Result<JsValue, JsValue> {
+    let (function, params) = abi.decode_input_from_slice(input).map_err(to_js_error)?;
+
+    to_js(&DecodedCall {
+        name: function.name.clone(),
+        params,
+    })
+}
+
+fn decode_output(abi: &Abi, signature: &str, output: &[u64]) -> Result<JsValue, JsValue> {
+    let (function, params) = abi
+        .decode_output_from_slice(signature, output)
+        .map_err(to_js_error)?;
+
+    to_js(&DecodedCall {
+        name: function.name.clone(),
+        params,
+    })
+}
+
+fn topics_from_js(topics: Vec<JsValue>) -> Result<Vec<FixedArray4>, JsValue> {
+    topics
+        .into_iter()
+        .map(|topic| {
+            let hex = topic
+                .as_string()
+                .ok_or_else(|| invalid_input(format!("expected hex topic, got {:?}", topic)))?;
+            FixedArray4::from_hex(&hex).map_err(to_js_error)
+        })
+        .collect()
+}
+
+fn decode_log(abi: &Abi, topics: Vec<FixedArray4>, data: &[u64]) -> Result<JsValue, JsValue> {
+    let (event, params) = abi
+        .decode_log(RawLog {
+            topics,
+            data: data.to_vec(),
+        })
+        .map_err(to_js_error)?;
+
+    to_js(&DecodedCall {
+        name: event.name.clone(),
+        params,
+    })
+}
+
+fn encode_input(abi: &Abi, signature: &str, params: JsValue) -> Result<Vec<u64>, JsValue> {
+    let params: Vec<Value> = serde_wasm_bindgen::from_value(params).map_err(invalid_input)?;
+
+    abi.encode_input_with_signature(signature, &params)
+        .map_err(to_js_error)
+}
+
+/// ABI parsed once and kept on the wasm side, for decoding and encoding many
+/// calls without re-parsing the ABI each time.
+///
+/// ```js
+/// const abi = new AbiHandle(fileContent);
+/// const calls = transactions.map((tx) => abi.decode_input(tx.calldata));
+/// abi.free();
+/// ```
+#[wasm_bindgen]
+pub struct AbiHandle {
+    abi: Abi,
+}
+
+#[wasm_bindgen]
+impl AbiHandle {
+    /// Parses the ABI file content.
+    #[wasm_bindgen(constructor)]
+    pub fn new(file_content: &[u8]) -> Result<AbiHandle, JsValue> {
+        catch_panic(|| {
+            Ok(AbiHandle {
+                abi: abi_from_bytes(file_content)?,
+            })
+        })
+    }
+
+    /// Converts a parsed ABI object.
+    pub fn from_abi(abi: JsValue) -> Result<AbiHandle, JsValue> {
+        catch_panic(|| {
+            Ok(AbiHandle {
+                abi: abi_from_js(abi)?,
+            })
+        })
+    }
+
+    /// Decodes function input calldata.
+    pub fn decode_input(&self, input: &[u64]) -> Result<JsValue, JsValue> {
+        catch_panic(|| decode_input(&self.abi, input))
+    }
+
+    /// Decodes function output data.
+    pub fn decode_output(&self, signature: &str, output: &[u64]) -> Result<JsValue, JsValue> {
+        catch_panic(|| decode_output(&self.abi, signature, output))
+    }
+
+    /// Decodes an event log from its `0x` prefixed hex topics and data
+    /// words.
+    pub fn decode_log(&self, topics: Vec<String>, data: &[u64]) -> Result<JsValue, JsValue> {
+        catch_panic(|| {
+            let topics = topics
+                .iter()
+                .map(|topic| FixedArray4::from_hex(topic).map_err(to_js_error))
+                .collect::<Result<_, _>>()?;
+
+            decode_log(&self.abi, topics, data)
+        })
+    }
+
+    /// Encodes function input calldata.
+    pub fn encode_input(&self, signature: &str, params: JsValue) -> Result<Vec<u64>, JsValue> {
+        catch_panic(|| encode_input(&self.abi, signature, params))
+    }
+}
+
+/// Parses a human-readable ABI, one entry per line, e.g.
+/// `function transfer(address to, u32 amount) returns (bool)`, into a
+/// handle, for frontends that don't ship the ABI JSON file. See
+/// [`Abi::from_human_readable`] for the syntax.
+#[wasm_bindgen]
+pub fn parse_human_readable_from_js(lines: Vec<String>) -> Result<AbiHandle, JsValue> {
+    catch_panic(|| {
+        Ok(AbiHandle {
+            abi: Abi::from_human_readable(&lines).map_err(to_js_error)?,
+        })
+    })
+}
+
+/// Decodes function input calldata against the ABI file content.
+#[wasm_bindgen]
+pub fn decode_input_from_js(file_content: &[u8], input: &[u64]) -> Result<JsValue, JsValue> {
+    catch_panic(|| decode_input(&abi_from_bytes(file_content)?, input))
+}
+
+/// Decodes function input calldata against a parsed ABI object.
+#[wasm_bindgen]
+pub fn decode_input_from_js_with_abi(abi: JsValue, input: &[u64]) -> Result<JsValue, JsValue> {
+    catch_panic(|| decode_input(&abi_from_js(abi)?, input))
+}
+
+/// Decodes function output data against the ABI file content.
+#[wasm_bindgen]
+pub fn decode_output_from_js(
+    file_content: &[u8],
+    signature: &str,
+    output: &[u64],
+) -> Result<JsValue, JsValue> {
+    catch_panic(|| decode_output(&abi_from_bytes(file_content)?, signature, output))
+}
+
+/// Decodes function output data against a parsed ABI object.
+#[wasm_bindgen]
+pub fn decode_output_from_js_with_abi(
+    abi: JsValue,
+    signature: &str,
+    output: &[u64],
+) -> Result<JsValue, JsValue> {
+    catch_panic(|| decode_output(&abi_from_js(abi)?, signature, output))
+}
+
+/// Decodes the call of an Ola node transaction JSON against the ABI file
+/// content.
+#[wasm_bindgen]
+pub fn decode_transaction_from_js(file_content: &[u8], tx_json: &str) -> Result<JsValue, JsValue> {
+    catch_panic(|| {
+        let tx = abi_from_bytes(file_content)?
+            .decode_transaction(tx_json)
+            .map_err(to_js_error)?;
+        to_js(&tx)
+    })
+}
+
+/// Decodes the call of an Ola node transaction JSON against a parsed ABI
+/// object.
+#[wasm_bindgen]
+pub fn decode_transaction_from_js_with_abi(
+    abi: JsValue,
+    tx_json: &str,
+) -> Result<JsValue, JsValue> {
+    catch_panic(|| {
+        let tx = abi_from_js(abi)?
+            .decode_transaction(tx_json)
+            .map_err(to_js_error)?;
+        to_js(&tx)
+    })
+}
+
+/// Decodes an event log from its `0x` prefixed hex topics and data words
+/// against the ABI file content, returning the event name and params.
+#[wasm_bindgen]
+pub fn decode_log_from_js(
+    file_content: &[u8],
+    topics: Vec<JsValue>,
+    data: &[u64],
+) -> Result<JsValue, JsValue> {
+    catch_panic(|| {
+        decode_log(
+            &abi_from_bytes(file_content)?,
+            topics_from_js(topics)?,
+            data,
+        )
+    })
+}
+
+/// Decodes an event log from its `0x` prefixed hex topics and data words
+/// against a parsed ABI object.
+#[wasm_bindgen]
+pub fn decode_log_from_js_with_abi(
+    abi: JsValue,
+    topics: Vec<JsValue>,
+    data: &[u64],
+) -> Result<JsValue, JsValue> {
+    catch_panic(|| decode_log(&abi_from_js(abi)?, topics_from_js(topics)?, data))
+}
+
+/// Encodes function input calldata using the ABI file content.
+#[wasm_bindgen]
+pub fn encode_input_from_js(
+    file_content: &[u8],
+    signature: &str,
+    params: JsValue,
+) -> Result<Vec<u64>, JsValue> {
+    catch_panic(|| encode_input(&abi_from_bytes(file_content)?, signature, params))
+}
+
+/// Encodes function input calldata using a parsed ABI object.
+#[wasm_bindgen]
+pub fn encode_input_from_js_with_abi(
+    abi: JsValue,
+    signature: &str,
+    params: JsValue,
+) -> Result<Vec<u64>, JsValue> {
+    catch_panic(|| encode_input(&abi_from_js(abi)?, signature, params))
+}
+
+/// Computes the topic of an event signature, e.g.
+/// `Transfer(address,address,u32)`, as `0x` prefixed hex, for building log
+/// filters without the ABI file.
+///
+/// Whitespace is ignored, so `Transfer(address, address, u32)` has the same
+/// topic.
+#[wasm_bindgen]
+pub fn event_topic_from_js(signature: &str) -> Result<String, JsValue> {
+    catch_panic(|| {
+        let signature = signature
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>();
+
+        Ok(topic_of(&signature).to_hex_string())
+    })
+}
+
+/// Renders calldata words as hex, optionally `0x` prefixed and with a word
+/// separator.
+#[wasm_bindgen]
+pub fn calldata_to_hex(
+    words: &[u64],
+    prefix: bool,
+    separator: Option<String>,
+) -> Result<String, JsValue> {
+    catch_panic(|| {
+        let format = calldata::HexFormat::new().prefix(prefix);
+
+        Ok(match separator {
+            Some(separator) => format.separator(&separator),
+            None => format,
+        }
+        .format(words))
+    })
+}
+
+/// Parses hex rendered calldata words.
+#[wasm_bindgen]
+pub fn calldata_from_hex(hex: &str) -> Result<Vec<u64>, JsValue> {
+    catch_panic(|| calldata::from_hex(hex).map_err(invalid_input))
+}
+
+/// Returns the version, features and hash backends of this build.
+#[wasm_bindgen]
+pub fn version_info() -> Result<JsValue, JsValue> {
+    catch_panic(|| to_js(&native_version_info()))
+}
+
+/// Lists the entries of unknown kinds in the ABI file content, which the
+/// other entry points reject.
+#[wasm_bindgen]
+pub fn skipped_entries(file_content: &[u8]) -> Result<JsValue, JsValue> {
+    catch_panic(|| {
+        let json = std::str::from_utf8(file_content).map_err(invalid_abi)?;
+        let abi = Abi::from_json_permissive(json).map_err(to_js_error)?;
+
+        to_js(&abi.skipped)
+    })
+}
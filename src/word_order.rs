@@ -0,0 +1,151 @@
+use crate::{FixedArray4, FixedArray8};
+
+/// Order of the words of a multi-word value: addresses, hashes, event
+/// topics and `u256` limbs.
+///
+/// This crate keeps words most significant first everywhere:
+///
+/// - [`FixedArray4`] and [`FixedArray8`] store word 0 as the most
+///   significant one, and their hex strings are the words' 16 (resp. 8)
+///   digit big-endian renderings concatenated in that order, so
+///   `0x...0001` parses as `[0, 0, 0, 1]`.
+/// - Event topics are the poseidon output words in output order, compared
+///   word by word with the topics of a log; an indexed `u32` topic is the
+///   value in the last word.
+/// - Encoded values ([`Value::encode`](crate::Value::encode)) lay their words
+///   out in the same order.
+///
+/// Method ids are single words: the first 4 bytes of the keccak256 hash of
+/// the signature read as a big-endian `u32`, e.g. `0xa9059cbb` for
+/// `transfer(address,uint256)`, unless configured otherwise with
+/// [`SelectorEndianness`](crate::SelectorEndianness).
+///
+/// Tools that put the least significant word first convert with
+/// [`FixedArray4::from_words`] and [`FixedArray4::to_words`]:
+///
+/// ```
+/// use ola_lang_abi::{FixedArray4, WordOrder};
+///
+/// let topic = FixedArray4::from_words([10, 0, 0, 0], WordOrder::LittleEndian);
+/// assert_eq!(topic, FixedArray4([0, 0, 0, 10]));
+/// assert_eq!(topic.to_words(WordOrder::LittleEndian), [10, 0, 0, 0]);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum WordOrder {
+    /// Most significant word first, the order used by this crate.
+    #[default]
+    BigEndian,
+    /// Least significant word first.
+    LittleEndian,
+}
+
+impl WordOrder {
+    /// Reorders words given in this order into big-endian order.
+    pub fn to_big_endian<const N: usize>(self, mut words: [u64; N]) -> [u64; N] {
+        if self == WordOrder::LittleEndian {
+            words.reverse();
+        }
+        words
+    }
+
+    /// Reorders big-endian words into this order.
+    pub fn from_big_endian<const N: usize>(self, words: [u64; N]) -> [u64; N] {
+        // reversing is its own inverse
+        self.to_big_endian(words)
+    }
+}
+
+impl FixedArray4 {
+    /// Creates a value from words in the given order.
+    pub fn from_words(words: [u64; 4], order: WordOrder) -> Self {
+        FixedArray4(order.to_big_endian(words))
+    }
+
+    /// Returns the words of the value in the given order.
+    pub fn to_words(&self, order: WordOrder) -> [u64; 4] {
+        order.from_big_endian(self.0)
+    }
+}
+
+impl FixedArray8 {
+    /// Creates a value from 32-bit limbs in the given order.
+    pub fn from_words(words: [u64; 8], order: WordOrder) -> Self {
+        FixedArray8(order.to_big_endian(words))
+    }
+
+    /// Returns the 32-bit limbs of the value in the given order.
+    pub fn to_words(&self, order: WordOrder) -> [u64; 8] {
+        order.from_big_endian(self.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    /// First topic of the `BookExample` log in `examples/log_data.rs`.
+    const LOG_TOPIC: [u64; 4] = [
+        876009939773297099,
+        9423535973325601276,
+        68930750687700470,
+        16776232995860792718,
+    ];
+
+    #[test]
+    fn fixture_word_order() {
+        let topic = FixedArray4(LOG_TOPIC);
+
+        // hex is the big-endian words concatenated
+        assert_eq!(
+            topic.to_hex_string(),
+            format!(
+                "0x{:016x}{:016x}{:016x}{:016x}",
+                LOG_TOPIC[0], LOG_TOPIC[1], LOG_TOPIC[2], LOG_TOPIC[3]
+            )
+        );
+        assert_eq!(topic.to_hex_string().parse::<FixedArray4>().unwrap(), topic);
+        assert_eq!(&topic.to_bytes32()[..8], &LOG_TOPIC[0].to_be_bytes());
+
+        let mut reversed = LOG_TOPIC;
+        reversed.reverse();
+        assert_eq!(topic.to_words(WordOrder::LittleEndian), reversed);
+        assert_eq!(
+            FixedArray4::from_words(reversed, WordOrder::LittleEndian),
+            topic
+        );
+        assert_eq!(topic.to_words(WordOrder::BigEndian), LOG_TOPIC);
+
+        // an indexed u32 topic of the same log
+        assert_eq!(
+            "0xa".parse::<FixedArray4>().unwrap(),
+            FixedArray4([0, 0, 0, 10])
+        );
+    }
+
+    #[test]
+    fn u256_limb_order() {
+        let n = FixedArray8::from(0x1_0000_0002u64);
+
+        assert_eq!(n.0, [0, 0, 0, 0, 0, 0, 1, 2]);
+        assert_eq!(
+            n.to_words(WordOrder::LittleEndian),
+            [2, 1, 0, 0, 0, 0, 0, 0]
+        );
+        assert_eq!(
+            FixedArray8::from_words([2, 1, 0, 0, 0, 0, 0, 0], WordOrder::LittleEndian),
+            n
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "abi")]
+    fn method_id_is_big_endian_prefix() {
+        // keccak256("transfer(address,uint256)") = a9059cbb2ab09eb2...
+        assert_eq!(
+            crate::hash::method_id_of("transfer(address,uint256)"),
+            0xa9059cbb
+        );
+    }
+}
@@ -0,0 +1,205 @@
+//! Tests of the `ola-abi` binary, run with `--features cli`.
+
+#![cfg(feature = "cli")]
+
+use std::process::{Command, Output};
+
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+const BOOK_ABI: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/examples/BookExample.json");
+
+const CREATE_BOOK: &str = "[60,5,111,108,97,118,109,7,120553111]";
+
+const BOOK_CREATED: &str = "0xb4477bffdb1a0c04ef159e06600b6cb368aab881062295f410be181706fc25bf";
+
+fn ola_abi(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_ola-abi"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+/// Runs a command expected to succeed, returning its stdout.
+fn success(args: &[&str]) -> String {
+    let output = ola_abi(args);
+    assert!(
+        output.status.success(),
+        "{:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn json(stdout: &str) -> serde_json::Value {
+    serde_json::from_str(stdout).unwrap()
+}
+
+#[test]
+fn encode() {
+    assert_eq!(
+        success(&["encode", BOOK_ABI, "createBook", r#"[60, "olavm"]"#]).trim(),
+        CREATE_BOOK
+    );
+    assert_eq!(
+        success(&[
+            "encode",
+            BOOK_ABI,
+            "createBook(u32,string)",
+            r#"[60, "olavm"]"#,
+            "--hex"
+        ])
+        .trim(),
+        "0x000000000000003c000000000000000500000000000000\
+         6f000000000000006c0000000000000061000000000000\
+         0076000000000000006d00000000000000070000000007\
+         2f7e97"
+    );
+}
+
+#[test]
+fn decode_input_round_trips_through_encode() {
+    let values = success(&["decode-input", BOOK_ABI, CREATE_BOOK]);
+    assert_eq!(json(&values), json!([60, "olavm"]));
+
+    assert_eq!(
+        success(&["encode", BOOK_ABI, "createBook", &values]).trim(),
+        CREATE_BOOK
+    );
+
+    let pretty = success(&["decode-input", BOOK_ABI, CREATE_BOOK, "--pretty"]);
+    assert!(pretty.starts_with("createBook(u32,string)\n"));
+    assert!(pretty.contains("id: u32 = 60"));
+}
+
+#[test]
+fn units() {
+    let pretty = success(&[
+        "decode-input",
+        BOOK_ABI,
+        CREATE_BOOK,
+        "--pretty",
+        "--units",
+        "id=1:OLA",
+    ]);
+    assert!(pretty.contains("id: u32 = 6 OLA"));
+
+    let output = ola_abi(&[
+        "decode-input",
+        BOOK_ABI,
+        CREATE_BOOK,
+        "--pretty",
+        "--units",
+        "id=4294967295",
+    ]);
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "error: invalid decimals 4294967295, expected at most 77\n"
+    );
+}
+
+#[test]
+fn pretty_param_docs() {
+    let abi = std::env::temp_dir().join("ola-abi-pretty-param-docs.json");
+    std::fs::write(
+        &abi,
+        json!([{
+            "type": "function",
+            "name": "withdraw",
+            "notice": "Withdraws from the vault.",
+            "inputs": [
+                { "name": "amount", "type": "u32", "doc": "Amount in gwei." },
+                { "name": "memo", "type": "string" }
+            ],
+            "outputs": []
+        }])
+        .to_string(),
+    )
+    .unwrap();
+    let abi = abi.to_str().unwrap();
+
+    let calldata = success(&["encode", abi, "withdraw", r#"[7, "a"]"#]);
+    assert_eq!(
+        success(&["decode-input", abi, calldata.trim(), "--pretty"]),
+        "withdraw(u32,string)\n\
+         \x20 // Withdraws from the vault.\n\
+         \x20 amount: u32 = 7  // Amount in gwei.\n\
+         \x20 memo: string = \"a\"\n"
+    );
+}
+
+#[test]
+fn decode_log() {
+    let id = format!("0x{:064x}", 10);
+    let args = [
+        "decode-log",
+        BOOK_ABI,
+        "--topic",
+        BOOK_CREATED,
+        "--topic",
+        &id,
+        "--topic",
+        &id,
+        "[5,104,101,108,108,111]",
+    ];
+
+    assert_eq!(json(&success(&args)), json!([10, id, "hello"]));
+
+    let mut extra = args.to_vec();
+    extra.extend(["--topic", &id]);
+    assert!(!ola_abi(&extra).status.success());
+
+    extra.push("--ignore-extra-topics");
+    let output = ola_abi(&extra);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("ignored 1 extra topics"));
+}
+
+#[test]
+fn selectors() {
+    let selectors = json(&success(&["selectors", BOOK_ABI]));
+
+    assert_eq!(
+        selectors["functions"]["createBook(u32,string)"],
+        "0x072f7e97"
+    );
+    assert_eq!(
+        selectors["events"]["BookCreated(u32,string,string)"],
+        BOOK_CREATED
+    );
+    assert_eq!(selectors["errors"], json!({}));
+}
+
+#[test]
+fn validate() {
+    assert!(success(&["validate", BOOK_ABI]).ends_with(": 3 functions, 1 events, 0 errors\n"));
+    assert!(!ola_abi(&["validate", "missing.json"]).status.success());
+}
+
+#[test]
+fn json_errors() {
+    let output = ola_abi(&["--json-errors", "decode-input", BOOK_ABI, "[1,0,5]"]);
+    assert!(!output.status.success());
+    assert_eq!(
+        json(&String::from_utf8(output.stderr).unwrap()),
+        json!({
+            "code": "FUNCTION_NOT_FOUND",
+            "message": "ABI function not found",
+            "context": {}
+        })
+    );
+
+    let output = ola_abi(&["--json-errors", "validate", "missing.json"]);
+    let error = json(&String::from_utf8(output.stderr).unwrap());
+    assert_eq!(error["code"], "IO");
+    assert_eq!(error["context"]["path"], "missing.json");
+
+    let output = ola_abi(&["decode-input", BOOK_ABI, "[1,0,5]"]);
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "error: ABI function not found\n"
+    );
+}
@@ -0,0 +1,120 @@
+#![cfg(feature = "codegen")]
+
+use ola_lang_abi::{codegen, Abi, Event, FixedArray4, Value};
+use pretty_assertions::assert_eq;
+
+mod generated {
+    include!("codegen/approve_filter.rs");
+}
+
+mod bindings {
+    include!("codegen/book_contract.rs");
+}
+
+use bindings::{BookExampleContract, CreateBookOutput};
+use generated::ApproveFilter;
+
+const APPROVE_ABI: &str = r#"[{
+    "type": "event",
+    "name": "Approve",
+    "inputs": [
+        {"name": "x", "type": "u32", "indexed": true},
+        {"name": "y", "type": "string", "indexed": true}
+    ],
+    "anonymous": false
+}]"#;
+
+#[test]
+fn generated_filter_is_up_to_date() {
+    let abi: Abi = serde_json::from_str(APPROVE_ABI).unwrap();
+
+    assert_eq!(ApproveFilter::TOPIC, abi.events[0].topic());
+    assert_eq!(
+        codegen::event_filters(&abi),
+        include_str!("codegen/approve_filter.rs")
+    );
+}
+
+#[test]
+fn generated_filter_topics() {
+    assert_eq!(ApproveFilter::SIGNATURE, "Approve(u32,string)");
+    assert_eq!(
+        ApproveFilter::new().topics(),
+        vec![Some(ApproveFilter::TOPIC), None, None]
+    );
+
+    let filter = ApproveFilter::new().x(10).y("ola");
+    assert_eq!(
+        filter.topics(),
+        vec![
+            Some(ApproveFilter::TOPIC),
+            Some(FixedArray4([0, 0, 0, 10])),
+            Some(Event::encode_topic(&Value::String("ola".to_string()))),
+        ]
+    );
+}
+
+#[test]
+fn generated_bindings_are_up_to_date() {
+    let abi = Abi::load("examples/BookExample.json").unwrap();
+
+    assert_eq!(
+        codegen::contract_bindings(&abi, "BookExampleContract"),
+        include_str!("codegen/book_contract.rs")
+    );
+}
+
+#[test]
+fn generated_bindings_round_trip() {
+    let abi = Abi::load("examples/BookExample.json").unwrap();
+
+    assert_eq!(
+        BookExampleContract::create_book(60, "ola".to_string()),
+        abi.encode_input_with_signature(
+            "createBook(u32,string)",
+            &[Value::U32(60), Value::String("ola".to_string())],
+        )
+        .unwrap()
+    );
+
+    let book = Value::Tuple(vec![
+        ("book_id".to_string(), Value::U32(60)),
+        ("book_name".to_string(), Value::String("ola".to_string())),
+        ("author".to_string(), Value::String("vm".to_string())),
+    ]);
+    let mut output = Value::encode(std::slice::from_ref(&book));
+    output.push(output.len() as u64);
+
+    assert_eq!(
+        BookExampleContract::decode_create_book_output(&output).unwrap(),
+        CreateBookOutput {
+            book_id: 60,
+            book_name: "ola".to_string(),
+            author: "vm".to_string(),
+        }
+    );
+    assert!(BookExampleContract::decode_create_book_output(&output[1..]).is_err());
+
+    let calldata = BookExampleContract::get_book_id(book);
+    let (f, decoded) = abi.decode_input_from_slice(&calldata).unwrap();
+    assert_eq!(f.name, "getBookId");
+    assert_eq!(decoded[0].param.name, "_book");
+    assert_eq!(
+        BookExampleContract::decode_get_book_id_output(&[60, 1])
+            .unwrap()
+            .output0,
+        60
+    );
+
+    let calldata = BookExampleContract::get_book_name(decoded[0].value.clone());
+    assert_eq!(
+        abi.decode_input_from_slice(&calldata).unwrap().0.name,
+        "getBookName"
+    );
+    assert_eq!(
+        BookExampleContract::decode_get_book_name_output(&[3, 111, 108, 97, 4])
+            .unwrap()
+            .output0,
+        "ola"
+    );
+}
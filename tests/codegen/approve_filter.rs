@@ -0,0 +1,44 @@
+// Generated by ola-lang-abi, do not edit.
+
+/// Topic filter of the `Approve(u32,string)` event.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ApproveFilter {
+    x: Option<ola_lang_abi::FixedArray4>,
+    y: Option<ola_lang_abi::FixedArray4>,
+}
+
+impl ApproveFilter {
+    /// Signature of the event.
+    pub const SIGNATURE: &str = "Approve(u32,string)";
+    /// Topic of the event.
+    pub const TOPIC: ola_lang_abi::FixedArray4 = ola_lang_abi::FixedArray4([
+        0xf9c165d12acc9776,
+        0x822ff3684d676f56,
+        0x7781b3609185e4a0,
+        0x1ed1ea5138eaf215,
+    ]);
+
+    /// Creates a filter matching every `Approve` log.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Matches logs whose `x` is `value`.
+    pub fn x(mut self, value: u32) -> Self {
+        let value = ola_lang_abi::Value::U32(value.into());
+        self.x = Some(ola_lang_abi::Event::encode_topic(&value));
+        self
+    }
+
+    /// Matches logs whose `y` is `value`.
+    pub fn y(mut self, value: &str) -> Self {
+        let value = ola_lang_abi::Value::String(value.to_string());
+        self.y = Some(ola_lang_abi::Event::encode_topic(&value));
+        self
+    }
+
+    /// Returns the topics to query, `None` matching any value.
+    pub fn topics(&self) -> Vec<Option<ola_lang_abi::FixedArray4>> {
+        vec![Some(Self::TOPIC), self.x, self.y]
+    }
+}
@@ -0,0 +1,112 @@
+// Generated by ola-lang-abi, do not edit.
+
+/// Typed bindings of the `BookExampleContract` contract functions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BookExampleContract;
+
+impl BookExampleContract {
+    /// Encodes a call to `createBook(u32,string)`.
+    pub fn create_book(id: u32, name: String) -> Vec<u64> {
+        let mut calldata = ola_lang_abi::Value::encode(&[
+            ola_lang_abi::Value::U32(id.into()),
+            ola_lang_abi::Value::String(name),
+        ]);
+        calldata.push(calldata.len() as u64);
+        calldata.push(0x072f7e97);
+        calldata
+    }
+
+    /// Decodes the output of `createBook(u32,string)`.
+    pub fn decode_create_book_output(output: &[u64]) -> Result<CreateBookOutput, ola_lang_abi::AbiError> {
+        // output = [param1, param2, .. , param-len]
+        let (_, params) = output.split_last().ok_or_else(|| {
+            ola_lang_abi::AbiError::Truncated("missing output param-len".to_string())
+        })?;
+        let tys = [
+            ola_lang_abi::Type::Tuple(vec![("book_id".to_string(), ola_lang_abi::Type::U32), ("book_name".to_string(), ola_lang_abi::Type::String), ("author".to_string(), ola_lang_abi::Type::String)]),
+        ];
+        let mut values = ola_lang_abi::codec::decode(&tys, params)?.into_iter();
+        let mut values = match values.next().unwrap() {
+            ola_lang_abi::Value::Tuple(members) => members.into_iter().map(|(_, value)| value),
+            _ => unreachable!("decoded as a tuple"),
+        };
+
+        Ok(CreateBookOutput {
+            book_id: ola_lang_abi::FromValue::from_value(&values.next().unwrap())?,
+            book_name: ola_lang_abi::FromValue::from_value(&values.next().unwrap())?,
+            author: ola_lang_abi::FromValue::from_value(&values.next().unwrap())?,
+        })
+    }
+
+    /// Encodes a call to `getBookName((u32,string,string))`.
+    pub fn get_book_name(_book: ola_lang_abi::Value) -> Vec<u64> {
+        let mut calldata = ola_lang_abi::Value::encode(&[
+            _book,
+        ]);
+        calldata.push(calldata.len() as u64);
+        calldata.push(0x2510835c);
+        calldata
+    }
+
+    /// Decodes the output of `getBookName((u32,string,string))`.
+    pub fn decode_get_book_name_output(output: &[u64]) -> Result<GetBookNameOutput, ola_lang_abi::AbiError> {
+        // output = [param1, param2, .. , param-len]
+        let (_, params) = output.split_last().ok_or_else(|| {
+            ola_lang_abi::AbiError::Truncated("missing output param-len".to_string())
+        })?;
+        let tys = [
+            ola_lang_abi::Type::String,
+        ];
+        let mut values = ola_lang_abi::codec::decode(&tys, params)?.into_iter();
+
+        Ok(GetBookNameOutput {
+            output0: ola_lang_abi::FromValue::from_value(&values.next().unwrap())?,
+        })
+    }
+
+    /// Encodes a call to `getBookId((u32,string,string))`.
+    pub fn get_book_id(_book: ola_lang_abi::Value) -> Vec<u64> {
+        let mut calldata = ola_lang_abi::Value::encode(&[
+            _book,
+        ]);
+        calldata.push(calldata.len() as u64);
+        calldata.push(0x4bd9143d);
+        calldata
+    }
+
+    /// Decodes the output of `getBookId((u32,string,string))`.
+    pub fn decode_get_book_id_output(output: &[u64]) -> Result<GetBookIdOutput, ola_lang_abi::AbiError> {
+        // output = [param1, param2, .. , param-len]
+        let (_, params) = output.split_last().ok_or_else(|| {
+            ola_lang_abi::AbiError::Truncated("missing output param-len".to_string())
+        })?;
+        let tys = [
+            ola_lang_abi::Type::U32,
+        ];
+        let mut values = ola_lang_abi::codec::decode(&tys, params)?.into_iter();
+
+        Ok(GetBookIdOutput {
+            output0: ola_lang_abi::FromValue::from_value(&values.next().unwrap())?,
+        })
+    }
+}
+
+/// Output of `createBook(u32,string)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreateBookOutput {
+    pub book_id: u32,
+    pub book_name: String,
+    pub author: String,
+}
+
+/// Output of `getBookName((u32,string,string))`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GetBookNameOutput {
+    pub output0: String,
+}
+
+/// Output of `getBookId((u32,string,string))`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GetBookIdOutput {
+    pub output0: u32,
+}
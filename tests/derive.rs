@@ -0,0 +1,90 @@
+#![cfg(feature = "derive")]
+
+use ola_lang_abi::{AbiDecode, AbiEncode, AbiType, FixedArray4, FromValue, ToValue, Type, Value};
+use pretty_assertions::assert_eq;
+
+#[derive(Debug, Clone, PartialEq, AbiEncode, AbiDecode)]
+struct Book {
+    id: u32,
+    #[abi(rename = "bookName")]
+    name: String,
+}
+
+#[derive(Debug, PartialEq, AbiEncode, AbiDecode)]
+struct Shelf {
+    owner: FixedArray4,
+    books: Vec<Book>,
+    position: (i32, i32),
+}
+
+#[derive(Debug, PartialEq, AbiEncode, AbiDecode)]
+struct Pair(u32, bool);
+
+fn book(id: u32, name: &str) -> Book {
+    Book {
+        id,
+        name: name.to_string(),
+    }
+}
+
+#[test]
+fn struct_to_tuple() {
+    assert_eq!(
+        book(60, "olavm").to_value(),
+        Value::Tuple(vec![
+            ("id".to_string(), Value::U32(60)),
+            ("bookName".to_string(), Value::String("olavm".to_string())),
+        ])
+    );
+    assert_eq!(
+        Book::abi_type(),
+        Type::Tuple(vec![
+            ("id".to_string(), Type::U32),
+            ("bookName".to_string(), Type::String),
+        ])
+    );
+    assert_eq!(
+        Value::encode(&[book(60, "ola").to_value()]),
+        vec![60, 3, 111, 108, 97]
+    );
+}
+
+#[test]
+fn nested_round_trip() {
+    let shelf = Shelf {
+        owner: FixedArray4([1, 2, 3, 4]),
+        books: vec![book(1, "ola"), book(2, "vm")],
+        position: (-3, 4),
+    };
+
+    assert_eq!(
+        Shelf::abi_type().to_string(),
+        "(address,(u32,string)[],(i32,i32))"
+    );
+
+    let value = shelf.to_value();
+    assert_eq!(value.type_of(), Shelf::abi_type());
+    assert_eq!(Shelf::from_value(&value).unwrap(), shelf);
+
+    let decoded = Value::decode_from_slice(&Value::encode(&[value]), &[Shelf::abi_type()]).unwrap();
+    assert_eq!(Shelf::from_value(&decoded[0]).unwrap(), shelf);
+}
+
+#[test]
+fn tuple_struct_by_position() {
+    let pair = Pair(7, true);
+
+    assert_eq!(pair.to_value(), (7u32, true).to_value());
+    assert_eq!(Pair::from_value(&(7u32, true).to_value()).unwrap(), pair);
+}
+
+#[test]
+fn decode_mismatch() {
+    assert_eq!(
+        Book::from_value(&(1u32,).to_value())
+            .unwrap_err()
+            .to_string(),
+        "expected Book tuple of 2 members, got (u32)"
+    );
+    assert!(Book::from_value(&(1u32, true).to_value()).is_err());
+}
@@ -0,0 +1,182 @@
+//! Encode/decode round trips of every type built from the type constructors
+//! up to depth 2, each with default, sample and empty values, next to
+//! dynamic neighbours, with both integer packings.
+
+use ola_lang_abi::{
+    DecodeOptions, Decoder, EncodeOptions, Encoder, FixedArray4, FixedArray8, IntegerPacking, Type,
+    Value, SIGNED_MAX,
+};
+
+const LEAVES: [Type; 10] = [
+    Type::U32,
+    Type::U256,
+    Type::Field,
+    Type::I32,
+    Type::I64,
+    Type::Hash,
+    Type::Address,
+    Type::Bool,
+    Type::String,
+    Type::Fields,
+];
+
+/// Every type nesting the constructors at most `depth` deep.
+fn types(depth: usize) -> Vec<Type> {
+    let mut all = LEAVES.to_vec();
+    if depth == 0 {
+        return all;
+    }
+
+    for ty in types(depth - 1) {
+        all.push(Type::FixedArray(Box::new(ty.clone()), 2));
+        all.push(Type::Array(Box::new(ty.clone())));
+        all.push(Type::Tuple(vec![("a".to_string(), ty.clone())]));
+        all.push(Type::Option(Box::new(ty.clone())));
+        all.push(Type::Map(Box::new(Type::U32), Box::new(ty.clone())));
+        all.push(Type::Tuple(vec![
+            ("a".to_string(), ty),
+            ("b".to_string(), Type::String),
+        ]));
+    }
+
+    all
+}
+
+/// Value of a type, `len` elements long for dynamic types and varying with
+/// `seed`.
+fn sample(ty: &Type, seed: u64, len: u64) -> Value {
+    match ty {
+        Type::U32 => Value::U32(u32::MAX as u64 - seed),
+        Type::U256 => Value::U256(FixedArray8([u32::MAX as u64, 0, 1, 2, 3, 4, 5, seed])),
+        Type::Field => Value::Field(0xffff_ffff_0000_0000 - seed),
+        Type::I32 => Value::I32(i32::MIN + seed as i32),
+        Type::I64 => Value::I64(-SIGNED_MAX + seed as i64),
+        Type::Hash => Value::Hash(FixedArray4([u64::MAX, seed, 0, 1])),
+        Type::Address => Value::Address(FixedArray4([seed, 1, 2, 3])),
+        Type::Bool => Value::Bool(seed & 1 == 0),
+        Type::String => Value::String("ola".repeat(len as usize)),
+        Type::Fields => Value::Fields((0..len).map(|i| seed + i).collect()),
+        Type::FixedArray(ty, size) => Value::FixedArray(
+            (0..*size).map(|i| sample(ty, seed + i, len)).collect(),
+            *ty.clone(),
+        ),
+        Type::Array(ty) => Value::Array(
+            (0..len).map(|i| sample(ty, seed + i, len)).collect(),
+            *ty.clone(),
+        ),
+        Type::Tuple(tys) => Value::Tuple(
+            tys.iter()
+                .enumerate()
+                .map(|(i, (name, ty))| (name.clone(), sample(ty, seed + i as u64, len)))
+                .collect(),
+        ),
+        // absent for empty samples
+        Type::Option(ty) if len == 0 => Value::None(*ty.clone()),
+        Type::Option(ty) => Value::Some(Box::new(sample(ty, seed, len))),
+        Type::Map(key, value) => Value::Map(
+            (0..len)
+                .map(|i| (sample(key, seed + i, len), sample(value, seed + i, len)))
+                .collect(),
+            *key.clone(),
+            *value.clone(),
+        ),
+    }
+}
+
+/// Checks the round trip of `value` between dynamic neighbours, returning
+/// what went wrong.
+fn round_trip(ty: &Type, value: &Value) -> Result<(), String> {
+    value
+        .check_type(ty)
+        .map_err(|err| format!("generated value doesn't type check: {}", err))?;
+
+    let types = [Type::Fields, ty.clone(), Type::String];
+    let values = [
+        Value::Fields(vec![1, 2]),
+        value.clone(),
+        Value::String("end".to_string()),
+    ];
+    let words = Value::encode(&values);
+
+    if Value::encoded_len(&values) != words.len() {
+        return Err(format!(
+            "encoded_len is {}, encoding is {} words",
+            Value::encoded_len(&values),
+            words.len()
+        ));
+    }
+
+    let value_len = Value::encode(std::slice::from_ref(value)).len();
+    if let Some(size) = ty.static_size() {
+        if size != value_len as u64 {
+            return Err(format!(
+                "static size is {}, encoding is {} words",
+                size, value_len
+            ));
+        }
+    }
+
+    let decoded = Value::decode_with_options(
+        &words,
+        &types,
+        DecodeOptions {
+            strict: true,
+            ..Default::default()
+        },
+    )
+    .map_err(|err| format!("decoding failed: {}", err))?;
+    if decoded != values {
+        return Err(format!("decoded {:?}", decoded[1]));
+    }
+
+    let mut packed = vec![];
+    Encoder::with_options(
+        &mut packed,
+        EncodeOptions {
+            integer_packing: IntegerPacking::Varint,
+            ..Default::default()
+        },
+    )
+    .encode(&values);
+    let decoded = Decoder::new()
+        .with_options(DecodeOptions {
+            strict: true,
+            integer_packing: IntegerPacking::Varint,
+        })
+        .decode(&packed, &types)
+        .map_err(|err| format!("decoding varint packed failed: {}", err))?;
+    if decoded != values {
+        return Err(format!("decoded varint packed {:?}", decoded[1]));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn every_type_to_depth_2_round_trips() {
+    let types = types(2);
+    assert_eq!(types.len(), 430);
+
+    let mut failures = vec![];
+    for ty in &types {
+        let values = [
+            Value::default_for(ty).unwrap(),
+            sample(ty, 0, 0),
+            sample(ty, 1, 1),
+            sample(ty, 7, 3),
+        ];
+
+        for value in &values {
+            if let Err(err) = round_trip(ty, value) {
+                failures.push(format!("{} with {:?}: {}", ty, value, err));
+            }
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "{} failing combinations:\n{}",
+        failures.len(),
+        failures.join("\n")
+    );
+}
@@ -0,0 +1,221 @@
+//! Tests of the JS bindings, at the boundary where u64 precision and the
+//! serde shape of values matter.
+//!
+//! Run with `wasm-pack test --node -- --features wasm`.
+
+#![cfg(all(target_arch = "wasm32", feature = "wasm"))]
+
+use js_sys::{Array, BigInt, Object, Reflect};
+use ola_lang_abi::{wasm, Abi, Event, Value};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+const BOOK_ABI: &[u8] = include_bytes!("../examples/BookExample.json");
+
+const FIELD_ABI: &[u8] = br#"[{
+    "type": "function",
+    "name": "store",
+    "inputs": [{ "name": "value", "type": "field" }],
+    "outputs": [{ "name": "stored", "type": "field" }]
+}]"#;
+
+/// Externally tagged value, e.g. `{ U32: 60n }`.
+fn tagged(tag: &str, value: impl Into<JsValue>) -> JsValue {
+    let object = Object::new();
+    Reflect::set(&object, &tag.into(), &value.into()).unwrap();
+    object.into()
+}
+
+fn get(value: &JsValue, key: &str) -> JsValue {
+    Reflect::get(value, &key.into()).unwrap()
+}
+
+fn param_value(decoded: &JsValue, i: u32) -> JsValue {
+    get(
+        &Reflect::get_u32(&get(decoded, "params"), i).unwrap(),
+        "value",
+    )
+}
+
+#[wasm_bindgen_test]
+fn book_input_round_trip() {
+    let params = Array::new();
+    params.push(&tagged("U32", BigInt::from(60u64)));
+    params.push(&tagged("String", "olavm"));
+
+    let calldata =
+        wasm::encode_input_from_js(BOOK_ABI, "createBook(u32,string)", params.into()).unwrap();
+
+    let abi: Abi = serde_json::from_slice(BOOK_ABI).unwrap();
+    assert_eq!(
+        calldata,
+        abi.encode_input_with_signature(
+            "createBook(u32,string)",
+            &[Value::U32(60), Value::String("olavm".to_string())]
+        )
+        .unwrap()
+    );
+
+    let decoded = wasm::decode_input_from_js(BOOK_ABI, &calldata).unwrap();
+    assert_eq!(get(&decoded, "name"), JsValue::from("createBook"));
+    assert_eq!(
+        get(&param_value(&decoded, 0), "U32"),
+        JsValue::from(BigInt::from(60u64))
+    );
+    assert_eq!(
+        get(&param_value(&decoded, 1), "String"),
+        JsValue::from("olavm")
+    );
+}
+
+#[wasm_bindgen_test]
+fn field_keeps_u64_precision() {
+    // not representable as a JS number
+    let value = u64::MAX - 1;
+
+    let params = Array::new();
+    params.push(&tagged("Field", BigInt::from(value)));
+    let calldata = wasm::encode_input_from_js(FIELD_ABI, "store(field)", params.into()).unwrap();
+    assert_eq!(calldata[0], value);
+
+    let decoded = wasm::decode_output_from_js(FIELD_ABI, "store(field)", &[value, 1]).unwrap();
+    assert_eq!(
+        get(&param_value(&decoded, 0), "Field"),
+        JsValue::from(BigInt::from(value))
+    );
+}
+
+#[wasm_bindgen_test]
+fn parsed_abi_object() {
+    let abi = js_sys::JSON::parse(std::str::from_utf8(BOOK_ABI).unwrap()).unwrap();
+
+    let params = Array::new();
+    params.push(&tagged("U32", BigInt::from(7u64)));
+    params.push(&tagged("String", ""));
+
+    let calldata =
+        wasm::encode_input_from_js_with_abi(abi.clone(), "createBook(u32,string)", params.into())
+            .unwrap();
+
+    let decoded = wasm::decode_input_from_js_with_abi(abi, &calldata).unwrap();
+    assert_eq!(get(&decoded, "name"), JsValue::from("createBook"));
+}
+
+#[wasm_bindgen_test]
+fn abi_handle() {
+    let abi: Abi = serde_json::from_slice(BOOK_ABI).unwrap();
+    let handle = wasm::AbiHandle::new(BOOK_ABI).unwrap();
+
+    let params = Array::new();
+    params.push(&tagged("U32", BigInt::from(60u64)));
+    params.push(&tagged("String", "olavm"));
+    let calldata = handle
+        .encode_input("createBook(u32,string)", params.into())
+        .unwrap();
+
+    let decoded = handle.decode_input(&calldata).unwrap();
+    assert_eq!(get(&decoded, "name"), JsValue::from("createBook"));
+
+    let event = &abi.events[0];
+    let topics = vec![
+        event.topic().to_hex_string(),
+        Event::encode_topic(&Value::U32(60)).to_hex_string(),
+        Event::encode_topic(&Value::String("olavm".to_string())).to_hex_string(),
+    ];
+    let data = Value::encode(&[Value::String("ola".to_string())]);
+
+    let decoded = handle.decode_log(topics, &data).unwrap();
+    assert_eq!(get(&decoded, "name"), JsValue::from("BookCreated"));
+
+    let err = handle
+        .decode_log(vec!["0xzz".to_string()], &data)
+        .unwrap_err();
+    assert_eq!(get(&err, "code"), JsValue::from("INVALID_INPUT"));
+    assert!(wasm::AbiHandle::new(b"not json").is_err());
+}
+
+#[wasm_bindgen_test]
+fn log_from_hex_topics() {
+    let abi: Abi = serde_json::from_slice(BOOK_ABI).unwrap();
+    let topics = vec![
+        JsValue::from(abi.events[0].topic().to_hex_string()),
+        JsValue::from(Event::encode_topic(&Value::U32(60)).to_hex_string()),
+        JsValue::from(Event::encode_topic(&Value::String("olavm".to_string())).to_hex_string()),
+    ];
+    let data = Value::encode(&[Value::String("ola".to_string())]);
+
+    let decoded = wasm::decode_log_from_js(BOOK_ABI, topics, &data).unwrap();
+    assert_eq!(get(&decoded, "name"), JsValue::from("BookCreated"));
+    assert_eq!(
+        get(&param_value(&decoded, 2), "String"),
+        JsValue::from("ola")
+    );
+
+    let err = wasm::decode_log_from_js(BOOK_ABI, vec![JsValue::from(1)], &data).unwrap_err();
+    assert_eq!(get(&err, "code"), JsValue::from("INVALID_INPUT"));
+}
+
+#[wasm_bindgen_test]
+fn human_readable_abi_handle() {
+    let handle = wasm::parse_human_readable_from_js(vec![
+        "function createBook(u32 id, string name)".to_string(),
+    ])
+    .unwrap();
+
+    let params = Array::new();
+    params.push(&tagged("U32", BigInt::from(60u64)));
+    params.push(&tagged("String", "olavm"));
+    let calldata = handle
+        .encode_input("createBook(u32,string)", params.into())
+        .unwrap();
+
+    let decoded = handle.decode_input(&calldata).unwrap();
+    assert_eq!(get(&decoded, "name"), JsValue::from("createBook"));
+
+    let err = wasm::parse_human_readable_from_js(vec!["function f(u33)".to_string()]).unwrap_err();
+    assert_eq!(get(&err, "code"), JsValue::from("INVALID_ABI"));
+}
+
+#[wasm_bindgen_test]
+fn errors_are_js_objects() {
+    let err = wasm::decode_input_from_js(BOOK_ABI, &[1]).unwrap_err();
+    assert_eq!(get(&err, "code"), JsValue::from("TRUNCATED"));
+    assert_eq!(
+        get(&err, "message"),
+        JsValue::from("missing function param-len and method_id")
+    );
+
+    let err = wasm::decode_input_from_js(BOOK_ABI, &[0, 0]).unwrap_err();
+    assert_eq!(get(&err, "code"), JsValue::from("FUNCTION_NOT_FOUND"));
+
+    let err = wasm::decode_input_from_js(b"not json", &[0, 0]).unwrap_err();
+    assert_eq!(get(&err, "code"), JsValue::from("INVALID_ABI"));
+}
+
+#[wasm_bindgen_test]
+fn malformed_input_throws() {
+    wasm::init_panic_hook();
+    wasm::init_panic_hook();
+
+    // huge lengths and offsets are errors, not traps
+    let err = wasm::decode_input_from_js(BOOK_ABI, &[u64::MAX, u64::MAX, 2, 0]).unwrap_err();
+    assert!(get(&err, "code").is_string());
+
+    let err = wasm::calldata_from_hex("0xzz").unwrap_err();
+    assert_eq!(get(&err, "code"), JsValue::from("INVALID_INPUT"));
+}
+
+#[wasm_bindgen_test]
+fn event_topic_from_signature() {
+    let abi: Abi = serde_json::from_slice(BOOK_ABI).unwrap();
+    let event = &abi.events[0];
+
+    assert_eq!(
+        wasm::event_topic_from_js(&event.signature()).unwrap(),
+        event.topic().to_hex_string()
+    );
+    assert_eq!(
+        wasm::event_topic_from_js(&event.signature().replace(',', ", ")).unwrap(),
+        event.topic().to_hex_string()
+    );
+}